@@ -0,0 +1,168 @@
+use std::{collections::HashMap, rc::Rc};
+
+use crate::expression::{Expression, Symbol};
+
+/// Handle to an expression stored in an `ExprArena`. Within one arena, two ids are equal exactly
+/// when the expressions they refer to are equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ExprId(u32);
+
+/// One node of an interned expression, referring to its children by id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Node {
+    And(ExprId, ExprId),
+    Or(ExprId, ExprId),
+    Implies(ExprId, ExprId),
+    Iff(ExprId, ExprId),
+    Not(ExprId),
+    Var(Symbol),
+    Top,
+    Bottom,
+}
+
+/// Stores every distinct subexpression it is given once, so that expressions can be compared and
+/// hashed as plain integers. The proof search keeps one for each search, in which every line it
+/// derives and the conclusion it looks for are interned.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{arena::ExprArena, proof::parse_expression};
+///
+/// let mut arena = ExprArena::new();
+/// let a = arena.intern(&parse_expression("(P&Q)>R").unwrap());
+/// let b = arena.intern(&parse_expression("(P&Q)>R").unwrap());
+/// assert_eq!(a, b);
+/// // `P`, `Q`, `P & Q`, `R` and the conditional itself
+/// assert_eq!(arena.len(), 5);
+/// assert_eq!(arena.expression(a), parse_expression("(P&Q)>R").unwrap());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ExprArena {
+    nodes: Vec<Node>,
+    ids: HashMap<Node, ExprId>,
+    /// Expressions already built by `shared`, indexed by id
+    shared: Vec<Option<Rc<Expression>>>,
+}
+
+/// The hash-consing pool of expressions, which is an `ExprArena`.
+pub type ExprPool = ExprArena;
+
+impl ExprArena {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the id of `expression`, adding it and any of its subexpressions that are not
+    /// already in the arena.
+    pub fn intern(&mut self, expression: &Expression) -> ExprId {
+        let node = match expression {
+            Expression::And(left, right) => Node::And(self.intern(left), self.intern(right)),
+            Expression::Or(left, right) => Node::Or(self.intern(left), self.intern(right)),
+            Expression::Implies(left, right) => {
+                Node::Implies(self.intern(left), self.intern(right))
+            }
+            Expression::Iff(left, right) => Node::Iff(self.intern(left), self.intern(right)),
+            Expression::Not(expr) => Node::Not(self.intern(expr)),
+            Expression::Var(var) => Node::Var(*var),
+            Expression::Top => Node::Top,
+            Expression::Bottom => Node::Bottom,
+        };
+        self.insert(node)
+    }
+
+    /// Returns the id of the expression made of `node`, whose children must already be in the
+    /// arena, adding it if it is not there yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use propositional_logic_calculator::{
+    ///     arena::{ExprArena, Node},
+    ///     proof::parse_expression,
+    /// };
+    ///
+    /// let mut arena = ExprArena::new();
+    /// let p = arena.insert(Node::Var('P'.into()));
+    /// let negation = arena.insert(Node::Not(p));
+    /// assert_eq!(negation, arena.intern(&parse_expression("-P").unwrap()));
+    /// ```
+    pub fn insert(&mut self, node: Node) -> ExprId {
+        if let Some(id) = self.ids.get(&node) {
+            return *id;
+        }
+        let id = ExprId(self.nodes.len() as u32);
+        self.nodes.push(node);
+        self.ids.insert(node, id);
+        id
+    }
+
+    /// The node stored for `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` was not returned by this arena.
+    pub fn node(&self, id: ExprId) -> Node {
+        self.nodes[id.0 as usize]
+    }
+
+    /// Rebuilds the `Expression` that `id` refers to.
+    pub fn expression(&self, id: ExprId) -> Expression {
+        match self.node(id) {
+            Node::And(left, right) => {
+                Expression::And(self.expression(left).wrap(), self.expression(right).wrap())
+            }
+            Node::Or(left, right) => {
+                Expression::Or(self.expression(left).wrap(), self.expression(right).wrap())
+            }
+            Node::Implies(left, right) => {
+                Expression::Implies(self.expression(left).wrap(), self.expression(right).wrap())
+            }
+            Node::Iff(left, right) => {
+                Expression::Iff(self.expression(left).wrap(), self.expression(right).wrap())
+            }
+            Node::Not(expr) => Expression::Not(self.expression(expr).wrap()),
+            Node::Var(var) => Expression::Var(var),
+            Node::Top => Expression::Top,
+            Node::Bottom => Expression::Bottom,
+        }
+    }
+
+    /// Builds the `Expression` that `id` refers to, reusing the allocations of any subexpressions
+    /// built by earlier calls. Repeated subformulas then share one allocation, both within one
+    /// expression and across all expressions built this way from the same arena. Comparing two
+    /// `Rc`s checks their pointers first, so equal subformulas built this way compare in constant
+    /// time.
+    pub fn shared(&mut self, id: ExprId) -> Rc<Expression> {
+        let index = id.0 as usize;
+        if let Some(Some(expression)) = self.shared.get(index) {
+            return expression.clone();
+        }
+        let expression = Rc::new(match self.node(id) {
+            Node::And(left, right) => Expression::And(self.shared(left), self.shared(right)),
+            Node::Or(left, right) => Expression::Or(self.shared(left), self.shared(right)),
+            Node::Implies(left, right) => {
+                Expression::Implies(self.shared(left), self.shared(right))
+            }
+            Node::Iff(left, right) => Expression::Iff(self.shared(left), self.shared(right)),
+            Node::Not(expr) => Expression::Not(self.shared(expr)),
+            Node::Var(var) => Expression::Var(var),
+            Node::Top => Expression::Top,
+            Node::Bottom => Expression::Bottom,
+        });
+        if self.shared.len() <= index {
+            self.shared.resize(index + 1, None);
+        }
+        self.shared[index] = Some(expression.clone());
+        expression
+    }
+
+    /// The number of distinct subexpressions stored.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
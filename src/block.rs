@@ -0,0 +1,97 @@
+use crate::lines::{Line, Rule};
+
+/// A block being filled while arranging lines, with the sub proof it belongs to, recorded as the
+/// assumption rule and the line it was derived from. The outermost block belongs to none.
+type OpenBlock = (Vec<ProofBlock>, Option<(Rule, Option<usize>)>);
+
+/// Part of a proof laid out as nested blocks, as in Fitch notation, rather than as the flat list
+/// of lines used by Lemmon notation.
+///
+/// A sub proof is a block that starts with the assumption opening it. The line that discharges
+/// the assumption follows the block, in the enclosing one. Each case of a vE is a block of its
+/// own, so the two cases sit side by side before the vE line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProofBlock {
+    Line(Line),
+    SubProof(Vec<ProofBlock>),
+}
+
+impl ProofBlock {
+    /// The lines of this block in order, including those of any blocks inside it.
+    pub fn lines(&self) -> Vec<Line> {
+        match self {
+            ProofBlock::Line(line) => vec![line.clone()],
+            ProofBlock::SubProof(blocks) => to_lines(blocks),
+        }
+    }
+}
+
+/// Arranges flat proof lines into nested blocks. `to_lines` turns the blocks back into exactly
+/// the same lines.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{
+///     block::{to_blocks, to_lines, ProofBlock},
+///     proof::{parse_expression, Proof},
+/// };
+///
+/// let mut proof = Proof::new(
+///     vec![parse_expression("P>Q").unwrap()],
+///     parse_expression("-Q>-P").unwrap(),
+/// );
+/// proof.search().unwrap();
+/// let blocks = to_blocks(proof.lines());
+/// assert!(blocks.iter().any(|block| matches!(block, ProofBlock::SubProof(_))));
+/// assert_eq!(to_lines(&blocks), proof.lines());
+/// ```
+pub fn to_blocks(lines: &[Line]) -> Vec<ProofBlock> {
+    let mut open: Vec<OpenBlock> = vec![(Vec::new(), None)];
+    for line in lines {
+        match line.rule {
+            Rule::OrEliminationAssumption
+            | Rule::ConditionalProofAssumption
+            | Rule::ReductioAdAbsurdiumAssumption => {
+                let subproof = (line.rule.clone(), line.deduction_lines.first().copied());
+                // The second case of a vE closes the first rather than going inside it
+                if line.rule == Rule::OrEliminationAssumption
+                    && open.last().and_then(|(_, s)| s.as_ref()) == Some(&subproof)
+                {
+                    close(&mut open);
+                }
+                open.push((Vec::new(), Some(subproof)));
+            }
+            Rule::OrElimination | Rule::ConditionalProof | Rule::ReductioAdAbsurdium
+                if open.len() > 1 =>
+            {
+                close(&mut open)
+            }
+            _ => (),
+        }
+        open.last_mut()
+            .unwrap()
+            .0
+            .push(ProofBlock::Line(line.clone()));
+    }
+    // Sub proofs that were never discharged still end with the lines
+    while open.len() > 1 {
+        close(&mut open);
+    }
+    open.pop().unwrap().0
+}
+
+/// Flattens nested blocks into proof lines, in the order they appear. The lines keep the numbers
+/// and citations they have in the blocks.
+pub fn to_lines(blocks: &[ProofBlock]) -> Vec<Line> {
+    blocks.iter().flat_map(ProofBlock::lines).collect()
+}
+
+/// Ends the innermost open block, adding it to the one around it.
+fn close(open: &mut Vec<OpenBlock>) {
+    let (blocks, _) = open.pop().unwrap();
+    open.last_mut()
+        .unwrap()
+        .0
+        .push(ProofBlock::SubProof(blocks));
+}
@@ -0,0 +1,184 @@
+use std::fmt::{self, Display};
+
+use crate::{
+    expression::{Expression, Symbol},
+    semantics::{collect_atoms, Model},
+};
+
+/// A sentence letter or its negation, as it appears in a clause.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Literal {
+    pub var: Symbol,
+    /// `false` for the negation of `var`
+    pub positive: bool,
+}
+
+impl Literal {
+    pub fn new(var: impl Into<Symbol>, positive: bool) -> Self {
+        Literal {
+            var: var.into(),
+            positive,
+        }
+    }
+
+    /// The literal with the opposite sign.
+    pub fn negate(self) -> Self {
+        Literal {
+            positive: !self.positive,
+            ..self
+        }
+    }
+
+    /// The value of this literal under `model`, or `None` if its letter is unassigned.
+    pub fn evaluate(self, model: &Model) -> Option<bool> {
+        model.get(self.var).map(|value| value == self.positive)
+    }
+}
+
+impl Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.positive {
+            write!(f, "~")?;
+        }
+        write!(f, "{}", self.var)
+    }
+}
+
+/// A disjunction of literals. The empty clause is false.
+pub type Clause = Vec<Literal>;
+
+/// Returns `true` if `model` makes some literal of `clause` true.
+pub fn satisfies_clause(model: &Model, clause: &[Literal]) -> bool {
+    clause
+        .iter()
+        .any(|literal| literal.evaluate(model) == Some(true))
+}
+
+/// The result of `Expression::tseitin`: clauses that are satisfiable exactly when the formula is,
+/// with a fresh sentence letter defined for each connective instead of distributing one
+/// connective over another, so that there are at most four clauses for each connective of the
+/// formula.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tseitin {
+    /// The clauses, the last of which asserts `root`
+    pub clauses: Vec<Clause>,
+    /// Each fresh letter with the subformula it stands for, in the order they were introduced,
+    /// so that a subformula comes after the subformulas it contains
+    pub definitions: Vec<(Symbol, Expression)>,
+    /// The literal that stands for the whole formula
+    pub root: Literal,
+}
+
+impl Tseitin {
+    /// Returns `true` if `model` satisfies every clause.
+    pub fn is_satisfied_by(&self, model: &Model) -> bool {
+        self.clauses
+            .iter()
+            .all(|clause| satisfies_clause(model, clause))
+    }
+
+    /// Extends `model`, which should assign every sentence letter of the original formula, with
+    /// the value of each fresh letter, so that it satisfies the clauses whenever it satisfies the
+    /// formula.
+    pub fn extend(&self, model: &Model) -> Model {
+        let mut model = model.clone();
+        for (var, definition) in &self.definitions {
+            if let Some(value) = model.evaluate(definition) {
+                model.set(*var, value);
+            }
+        }
+        model
+    }
+}
+
+/// Letters for fresh atoms, `X1`, `X2` and so on, that are not in `used`.
+pub(crate) fn fresh_atoms(used: Vec<Symbol>) -> impl Iterator<Item = Symbol> {
+    (1..)
+        .map(|index| Symbol::indexed('X', index))
+        .filter(move |var| !used.contains(var))
+}
+
+/// Builds the Tseitin encoding of `expression`, as described on `Expression::tseitin`.
+pub(crate) fn tseitin(expression: &Expression) -> Tseitin {
+    let mut encoder = Encoder {
+        fresh: Box::new(fresh_atoms(collect_atoms([expression]))),
+        clauses: Vec::new(),
+        definitions: Vec::new(),
+    };
+    let root = encoder.encode(expression);
+    let mut clauses = encoder.clauses;
+    clauses.push(vec![root]);
+    Tseitin {
+        clauses,
+        definitions: encoder.definitions,
+        root,
+    }
+}
+
+struct Encoder {
+    fresh: Box<dyn Iterator<Item = Symbol>>,
+    clauses: Vec<Clause>,
+    definitions: Vec<(Symbol, Expression)>,
+}
+
+impl Encoder {
+    /// The literal that stands for `expression`, adding the clauses that define it.
+    fn encode(&mut self, expression: &Expression) -> Literal {
+        let (left, right) = match expression {
+            Expression::Var(var) => return Literal::new(*var, true),
+            // Negation needs no letter of its own
+            Expression::Not(expr) => return self.encode(expr).negate(),
+            Expression::Top | Expression::Bottom => {
+                let x = self.define(expression);
+                self.clauses
+                    .push(vec![Literal::new(x.var, *expression == Expression::Top)]);
+                return x;
+            }
+            Expression::And(left, right)
+            | Expression::Or(left, right)
+            | Expression::Implies(left, right)
+            | Expression::Iff(left, right) => (self.encode(left), self.encode(right)),
+        };
+        let x = self.define(expression);
+        let (a, b) = (left, right);
+        let clauses = match expression {
+            // x -> a, x -> b, a & b -> x
+            Expression::And(..) => vec![
+                vec![x.negate(), a],
+                vec![x.negate(), b],
+                vec![x, a.negate(), b.negate()],
+            ],
+            // x -> a v b, a -> x, b -> x
+            Expression::Or(..) => vec![
+                vec![x.negate(), a, b],
+                vec![x, a.negate()],
+                vec![x, b.negate()],
+            ],
+            // x -> (a -> b), ~a -> x, b -> x
+            Expression::Implies(..) => vec![
+                vec![x.negate(), a.negate(), b],
+                vec![x, a],
+                vec![x, b.negate()],
+            ],
+            // x -> (a <-> b), and x when a and b agree
+            _ => vec![
+                vec![x.negate(), a.negate(), b],
+                vec![x.negate(), a, b.negate()],
+                vec![x, a, b],
+                vec![x, a.negate(), b.negate()],
+            ],
+        };
+        self.clauses.extend(clauses);
+        x
+    }
+
+    /// A fresh letter standing for `expression`.
+    fn define(&mut self, expression: &Expression) -> Literal {
+        let var = self
+            .fresh
+            .next()
+            .expect("a fresh sentence letter for each connective");
+        self.definitions.push((var, expression.clone()));
+        Literal::new(var, true)
+    }
+}
@@ -0,0 +1,94 @@
+use std::rc::Rc;
+
+use crate::{
+    expression::{Expression, Symbol},
+    proof::{Proof, SearchSettings},
+    semantics::{collect_atoms, entails_set, ConclusionMode},
+};
+
+/// Finds every formula of at most `max_size` (as counted by `Expression::size`) over the sentence
+/// letters of `premises` that the search can derive from them, with default search settings.
+///
+/// Each formula is returned as the conclusion of its completed proof. See
+/// `conclusions_with_settings`.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{enumerate::conclusions, proof::parse_expression};
+///
+/// let premises = vec![parse_expression("P&Q").unwrap()];
+/// let proofs = conclusions(&premises, 1);
+/// let found = proofs.iter().map(|p| p.conclusion().to_string()).collect::<Vec<_>>();
+/// assert_eq!(found, vec!["P", "Q"]);
+/// ```
+pub fn conclusions(premises: &[Expression], max_size: usize) -> Vec<Proof> {
+    conclusions_with_settings(premises, max_size, SearchSettings::default())
+}
+
+/// Finds every formula of at most `max_size` over the sentence letters of `premises` that the
+/// search can derive from them using `settings`.
+///
+/// The formulas are ordered by size. Those of the same size come in a fixed order: negations
+/// first, then conjunctions, disjunctions, conditionals and biconditionals. Formulas that the
+/// premises do not entail are skipped without a search, so only the valid ones cost a proof
+/// search. A valid formula is still missing from the result when the search
+/// fails to prove it within `settings`.
+pub fn conclusions_with_settings(
+    premises: &[Expression],
+    max_size: usize,
+    settings: SearchSettings,
+) -> Vec<Proof> {
+    let atoms = collect_atoms(premises);
+    formulas(&atoms, max_size)
+        .into_iter()
+        .filter(|formula| {
+            let conclusion = [formula.as_ref().clone()];
+            entails_set(premises, &conclusion, ConclusionMode::Conjunctive).is_valid()
+        })
+        .filter_map(|formula| {
+            let mut proof = Proof::with_settings(
+                premises.to_vec(),
+                formula.as_ref().clone(),
+                settings.clone(),
+            );
+            proof.search().ok().map(|_| proof)
+        })
+        .collect()
+}
+
+/// Every formula over `atoms` of at most `max_size`, smallest first. Formulas of each size are
+/// built from the smaller ones, so their parts are shared.
+fn formulas(atoms: &[Symbol], max_size: usize) -> Vec<Rc<Expression>> {
+    // `by_size[n]` holds the formulas of size `n + 1`
+    let mut by_size: Vec<Vec<Rc<Expression>>> = Vec::new();
+    for size in 1..=max_size {
+        let mut current = Vec::new();
+        if size == 1 {
+            current.extend(atoms.iter().map(|var| Expression::Var(*var).wrap()));
+        } else {
+            current.extend(
+                by_size[size - 2]
+                    .iter()
+                    .map(|inner| Expression::Not(inner.clone()).wrap()),
+            );
+            for connective in [
+                Expression::And,
+                Expression::Or,
+                Expression::Implies,
+                Expression::Iff,
+            ] {
+                // One size goes to the connective itself, the rest is split between the sides
+                for left_size in 1..size - 1 {
+                    for left in &by_size[left_size - 1] {
+                        for right in &by_size[size - left_size - 2] {
+                            current.push(connective(left.clone(), right.clone()).wrap());
+                        }
+                    }
+                }
+            }
+        }
+        by_size.push(current);
+    }
+    by_size.into_iter().flatten().collect()
+}
@@ -1,40 +1,234 @@
 // error.rs:
 //
-// This module defines custom error types used in the parser and proof modules.
+// This module defines custom error types used in the parser, proof and semantics modules.
 // These error types provide more detailed and context-specific error messages,
 // improving the debugging experience and user feedback.
 
-use crate::proof::SearchState;
+use crate::{proof::SearchState, trace::TraceEvent};
 
 /// Represents errors that can occur during parsing of logical expressions.
-#[derive(Debug, thiserror::Error)]
+///
+/// Every variant records the position of the problem in the input, counted in characters from 0.
+/// `position` returns it and `caret` points at it under the input.
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum ParserError {
     /// Error for characters that don't match valid characters in an expression.
     /// The offending character is provided in the error message.
-    #[error("'{0}' did not match one of the valid characters.")]
-    InvalidExpression(char),
+    #[error("'{0}' at position {1} did not match one of the valid characters.")]
+    InvalidExpression(char, usize),
 
     /// Error for situations where an expression is expected but none is provided.
-    #[error("Empty expression")]
-    EmptyExpression,
+    #[error("Empty expression at position {0}")]
+    EmptyExpression(usize),
 
     /// Error for missing left operand in a binary operation.
-    #[error("Expected left operand")]
-    ExpectedLeftOperand,
+    #[error("Expected left operand at position {0}")]
+    ExpectedLeftOperand(usize),
 
-    /// Error for missing expression after a negation '-' operator.
-    #[error("Expected expression after '-'")]
-    ExpectedExpressionAfterNegation,
+    /// Error for missing expression after a negation operator ('-', '~', '!' or '¬'). The position
+    /// is that of the negation sign.
+    #[error("Expected expression after negation at position {0}")]
+    ExpectedExpressionAfterNegation(usize),
 
     /// Error for an invalid operator in the expression.
     /// The invalid operator character is provided in the error message.
-    #[error("Invalid operator: '{0}'")]
-    InvalidOperator(char),
+    #[error("Invalid operator: '{0}' at position {1}")]
+    InvalidOperator(char, usize),
 
     /// Error for unmatched parentheses in an expression.
-    /// The partially parsed expression and the bracket index are provided.
-    #[error("Unmatched parentheses in expression: {0} at bracket {1}")]
-    UnmatchedParentheses(String, usize),
+    /// The text after the unclosed parenthesis, how many are left open and the position of the
+    /// unclosed parenthesis are provided.
+    #[error("Unmatched parentheses in expression: {0} at bracket {1}, opened at position {2}")]
+    UnmatchedParentheses(String, usize, usize),
+
+    /// Error, in strict parsing, for a binary operator whose grouping with the operator next to
+    /// it is left to precedence rather than shown with parentheses.
+    /// The operator character and its position are provided.
+    #[error("'{0}' at position {1} needs parentheses to show how it groups")]
+    AmbiguousGrouping(char, usize),
+
+    /// Error for a `v` followed by a digit or `_` while atoms are single letters, where `v` is
+    /// always disjunction and names cannot be used.
+    #[error("'v' at position {0} is disjunction and cannot be part of a name; read atoms as names to use names")]
+    DisjunctionInName(usize),
+
+    /// Error for a `v` where an operator should be while atoms are read as names, where `v` is an
+    /// atom rather than disjunction.
+    #[error(
+        "'v' at position {0} is an atom when atoms are names; write disjunction as '|' or '∨'"
+    )]
+    AtomNamedV(usize),
+
+    /// Error for a binary operator with nothing after it, found by `Parser::parse_recovering`.
+    /// The operator character and its position are provided.
+    #[error("'{0}' at position {1} has no right operand")]
+    MissingRightOperand(char, usize),
+
+    /// Error for an operator from another notation, such as `+` for disjunction or `=>` for the
+    /// conditional. The operator as written, the operator to write instead and its position are
+    /// provided.
+    #[error("'{0}' at position {2} is not an operator; did you mean '{1}'?")]
+    UnsupportedOperator(String, &'static str, usize),
+
+    /// Error for an atom name, at the given position, when every sentence letter is already taken.
+    #[error("No sentence letter left for the atom '{0}' at position {1}")]
+    TooManyNames(String, usize),
+
+    /// Error for an expression nested deeper than `ParserOptions::max_depth`, through
+    /// parentheses, negations or the right operands of binary operators. The limit and the
+    /// position of the subformula that goes past it are provided.
+    #[error("Expression is nested more than {0} deep at position {1}")]
+    TooDeeplyNested(usize, usize),
+}
+
+impl ParserError {
+    /// The position in the input, counted in characters from 0, that the error points at.
+    pub fn position(&self) -> usize {
+        match self {
+            ParserError::InvalidExpression(_, position)
+            | ParserError::EmptyExpression(position)
+            | ParserError::ExpectedLeftOperand(position)
+            | ParserError::ExpectedExpressionAfterNegation(position)
+            | ParserError::InvalidOperator(_, position)
+            | ParserError::UnmatchedParentheses(_, _, position)
+            | ParserError::AmbiguousGrouping(_, position)
+            | ParserError::DisjunctionInName(position)
+            | ParserError::AtomNamedV(position)
+            | ParserError::MissingRightOperand(_, position)
+            | ParserError::UnsupportedOperator(_, _, position)
+            | ParserError::TooManyNames(_, position)
+            | ParserError::TooDeeplyNested(_, position) => *position,
+        }
+    }
+
+    /// The text to write in place of what the error points at, when there is an obvious one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use propositional_logic_calculator::proof::parse_expression;
+    ///
+    /// assert_eq!(parse_expression("P => Q").unwrap_err().suggestion(), Some(">"));
+    /// assert_eq!(parse_expression("P & $").unwrap_err().suggestion(), None);
+    /// ```
+    pub fn suggestion(&self) -> Option<&'static str> {
+        match self {
+            ParserError::UnsupportedOperator(_, suggestion, _) => Some(suggestion),
+            _ => None,
+        }
+    }
+
+    /// Shows `input`, which should be the text that was parsed, with a caret under the position
+    /// of the error on the line below.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use propositional_logic_calculator::proof::parse_expression;
+    ///
+    /// let error = parse_expression("P & $").unwrap_err();
+    /// assert_eq!(error.caret("P & $"), "P & $\n    ^");
+    /// ```
+    pub fn caret(&self, input: &str) -> String {
+        format!("{}\n{}^", input, " ".repeat(self.position()))
+    }
+
+    /// The same error with its position moved `by` characters further on, for an expression
+    /// that was parsed from part of a longer input.
+    pub(crate) fn shifted(self, by: usize) -> Self {
+        self.moved(|position| position + by)
+    }
+
+    /// The same error with its position replaced by `f` of it, for an expression that was parsed
+    /// from a rewritten form of the input.
+    pub(crate) fn moved(self, f: impl Fn(usize) -> usize) -> Self {
+        match self {
+            ParserError::InvalidExpression(c, position) => {
+                ParserError::InvalidExpression(c, f(position))
+            }
+            ParserError::EmptyExpression(position) => ParserError::EmptyExpression(f(position)),
+            ParserError::ExpectedLeftOperand(position) => {
+                ParserError::ExpectedLeftOperand(f(position))
+            }
+            ParserError::ExpectedExpressionAfterNegation(position) => {
+                ParserError::ExpectedExpressionAfterNegation(f(position))
+            }
+            ParserError::InvalidOperator(c, position) => {
+                ParserError::InvalidOperator(c, f(position))
+            }
+            ParserError::UnmatchedParentheses(rest, open, position) => {
+                ParserError::UnmatchedParentheses(rest, open, f(position))
+            }
+            ParserError::AmbiguousGrouping(c, position) => {
+                ParserError::AmbiguousGrouping(c, f(position))
+            }
+            ParserError::DisjunctionInName(position) => ParserError::DisjunctionInName(f(position)),
+            ParserError::AtomNamedV(position) => ParserError::AtomNamedV(f(position)),
+            ParserError::MissingRightOperand(c, position) => {
+                ParserError::MissingRightOperand(c, f(position))
+            }
+            ParserError::UnsupportedOperator(found, suggestion, position) => {
+                ParserError::UnsupportedOperator(found, suggestion, f(position))
+            }
+            ParserError::TooManyNames(name, position) => {
+                ParserError::TooManyNames(name, f(position))
+            }
+            ParserError::TooDeeplyNested(limit, position) => {
+                ParserError::TooDeeplyNested(limit, f(position))
+            }
+        }
+    }
+}
+
+/// Represents errors that can occur when splitting an argument into its premises and conclusion.
+///
+/// Positions are counted in characters from 0 in the whole argument, so `caret` can point at the
+/// problem under it.
+#[derive(Debug, thiserror::Error)]
+pub enum ArgumentError {
+    /// Error for an argument with no delimiter between the premises and the conclusion.
+    #[error("Need a '/', '⊢', '|-', '∴' or 'therefore' to delimit assumptions and conclusion")]
+    MissingDelimiter,
+
+    /// Error for an argument with nothing before the delimiter.
+    #[error("Need at least one assumption before the conclusion")]
+    NoPremises,
+
+    /// Error for a premise with nothing in it, such as the one between the commas of `A,,B/C`.
+    /// The position of the comma that ends it, or of the delimiter, is provided.
+    #[error("Empty premise at position {0}")]
+    EmptyPremise(usize),
+
+    /// Error for a second delimiter after the conclusion has started.
+    /// The delimiter and its position are provided.
+    #[error("Unexpected '{0}' at position {1} after the conclusion")]
+    TrailingInput(String, usize),
+
+    /// Wraps a `ParserError` for a premise or the conclusion.
+    #[error(transparent)]
+    ParserError(#[from] ParserError),
+}
+
+impl ArgumentError {
+    /// The position in the argument that the error points at, if there is one.
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            ArgumentError::MissingDelimiter | ArgumentError::NoPremises => None,
+            ArgumentError::EmptyPremise(position) | ArgumentError::TrailingInput(_, position) => {
+                Some(*position)
+            }
+            ArgumentError::ParserError(err) => Some(err.position()),
+        }
+    }
+
+    /// Shows `input`, which should be the argument that was parsed, with a caret under the
+    /// position of the error on the line below. Without a position, `input` is shown alone.
+    pub fn caret(&self, input: &str) -> String {
+        match self.position() {
+            Some(position) => format!("{}\n{}^", input, " ".repeat(position)),
+            None => input.to_string(),
+        }
+    }
 }
 
 /// Represents errors that can occur during the proof process.
@@ -48,3 +242,142 @@ pub enum ProofError {
     #[error("Search error: {0}")]
     SearchError(SearchState),
 }
+
+/// Returned by semantic queries when no model makes all of the given formulas true.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("The formulas are inconsistent: no assignment makes them all true")]
+pub struct Inconsistent;
+
+/// Represents errors that can occur while evaluating an expression under an assignment.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum EvalError {
+    /// Error for a sentence letter, named as it is written, that the assignment gives no value.
+    #[error("No value assigned to the sentence letter '{0}'")]
+    UnassignedVariable(String),
+}
+
+/// Represents errors that can occur while reading a search trace.
+#[derive(Debug, thiserror::Error)]
+pub enum TraceError {
+    /// Wraps an I/O error from the underlying reader.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A line of the trace could not be read. The line number, counting from 1, is provided.
+    #[error("Malformed trace at line {0}")]
+    Malformed(usize),
+}
+
+/// Represents errors that can occur while reading a problem in TPTP format.
+#[derive(Debug, thiserror::Error)]
+pub enum TptpError {
+    /// Wraps an I/O error from the underlying reader.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The input does not follow the TPTP syntax.
+    /// The text found, or "end of input", and its line number, counting from 1, are provided.
+    #[error("Unexpected {0} at line {1}")]
+    Unexpected(String, usize),
+
+    /// The input uses something beyond propositional logic, such as a quantifier, a variable, a
+    /// predicate with arguments or an `include`.
+    /// The text found and its line number are provided.
+    #[error("'{0}' at line {1} is outside the propositional fragment")]
+    NotPropositional(String, usize),
+
+    /// A formula has a role that this crate does not know what to do with.
+    /// The role and its line number are provided.
+    #[error("Unknown role '{0}' at line {1}")]
+    UnknownRole(String, usize),
+
+    /// The problem has more than one conjecture. The line of the second one is provided.
+    #[error("Second conjecture at line {0}, only one is supported")]
+    MultipleConjectures(usize),
+
+    /// The problem has more atoms than there are sentence letters.
+    /// The first atom left without a letter is provided.
+    #[error("No sentence letter left for atom '{0}'")]
+    TooManyAtoms(String),
+
+    /// A formula is nested deeper than `ParserOptions::DEFAULT_MAX_DEPTH`, through parentheses
+    /// or negations. The limit and the line where it is passed are provided.
+    #[error("Nested more than {0} deep at line {1}")]
+    TooDeeplyNested(usize, usize),
+}
+
+/// Represents errors that can occur while reading an SMT-LIB script.
+#[derive(Debug, thiserror::Error)]
+pub enum SmtLibError {
+    /// The input is not a well formed list of commands.
+    /// The text found, or "end of input", and its line number, counting from 1, are provided.
+    #[error("Unexpected {0} at line {1}")]
+    Unexpected(String, usize),
+
+    /// The script uses something beyond Boolean propositional logic, such as another sort, a
+    /// function with arguments, a quantifier or `push`.
+    /// The text found and its line number are provided.
+    #[error("'{0}' at line {1} is not supported")]
+    Unsupported(String, usize),
+
+    /// A symbol is used without being declared or defined.
+    /// The symbol and its line number are provided.
+    #[error("Undeclared symbol '{0}' at line {1}")]
+    UndeclaredSymbol(String, usize),
+
+    /// The script declares more constants than there are sentence letters.
+    /// The first constant left without a letter is provided.
+    #[error("No sentence letter left for constant '{0}'")]
+    TooManyAtoms(String),
+
+    /// Lists are nested deeper than `ParserOptions::DEFAULT_MAX_DEPTH`.
+    /// The limit and the line of the list that goes past it are provided.
+    #[error("Nested more than {0} deep at line {1}")]
+    TooDeeplyNested(usize, usize),
+}
+
+/// Represents errors that can occur while reading a problem file.
+#[derive(Debug, thiserror::Error)]
+pub enum ProblemError {
+    /// The argument on a line could not be read. The line number, counting from 1, is provided.
+    #[error("{0} at line {1}")]
+    Argument(#[source] ArgumentError, usize),
+
+    /// A setting is not one of the fields of `SearchSettings` that a problem can set.
+    /// The name and its line number are provided.
+    #[error("Unknown setting '{0}' at line {1}")]
+    UnknownSetting(String, usize),
+
+    /// A setting is not written as `name=value` or its value cannot be read.
+    /// The setting as written and its line number are provided.
+    #[error("Invalid setting '{0}' at line {1}")]
+    InvalidSetting(String, usize),
+}
+
+/// Represents errors that can occur when reading or filling a formula template.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum TemplateError {
+    /// Wraps a `ParserError` for the template, with positions in the template as written.
+    #[error(transparent)]
+    ParserError(#[from] ParserError),
+
+    /// The template uses so many sentence letters that none is left to stand in for its holes.
+    #[error("No sentence letter left for hole ?{0}")]
+    TooManyHoles(usize),
+
+    /// No formula was given for a hole. The number of the hole is provided.
+    #[error("No filling for hole ?{0}")]
+    MissingFilling(usize),
+}
+
+/// Returned when replaying a search does not reproduce its recorded trace.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("Replay diverged from the trace at event {index}: expected {expected:?}, found {found:?}")]
+pub struct TraceDivergence {
+    /// Index of the first event that differs
+    pub index: usize,
+    /// The recorded event, or `None` if the replay went on past the end of the trace
+    pub expected: Option<TraceEvent>,
+    /// The replayed event, or `None` if the replay stopped before the end of the trace
+    pub found: Option<TraceEvent>,
+}
@@ -0,0 +1,212 @@
+use std::fmt::{self, Display};
+
+use crate::{
+    expression::Expression,
+    semantics::{Model, TruthTable},
+};
+
+/// Explains why an argument is invalid by pointing at a row of its truth table where every
+/// assumption is true and the conclusion is false.
+#[derive(Debug, Clone)]
+pub struct InvalidityExplanation {
+    conclusion: Expression,
+    table: TruthTable,
+    row: usize,
+}
+
+/// Builds an `InvalidityExplanation` for the argument, or returns `None` if it is valid.
+///
+/// The truth table has one column per sentence letter, followed by one per assumption and a final
+/// column for the conclusion. The highlighted row is the first countermodel in table order.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{explain::explain_invalidity, proof::parse_expression};
+///
+/// let assumptions = vec![parse_expression("P>Q").unwrap(), parse_expression("Q").unwrap()];
+/// let conclusion = parse_expression("P").unwrap();
+/// let explanation = explain_invalidity(&assumptions, &conclusion).unwrap();
+/// assert!(explanation.summary().contains("P is false and Q is true"));
+/// ```
+pub fn explain_invalidity(
+    assumptions: &[Expression],
+    conclusion: &Expression,
+) -> Option<InvalidityExplanation> {
+    let mut formulas = assumptions.to_vec();
+    formulas.push(conclusion.clone());
+    let table = TruthTable::new(&formulas);
+    let row = table.rows().iter().position(|row| {
+        let (conclusion, assumptions) = row.values.split_last().unwrap();
+        assumptions.iter().all(|value| *value) && !conclusion
+    })?;
+    Some(InvalidityExplanation {
+        conclusion: conclusion.clone(),
+        table,
+        row,
+    })
+}
+
+impl InvalidityExplanation {
+    /// The assignment that makes every assumption true and the conclusion false.
+    pub fn countermodel(&self) -> &Model {
+        &self.table.rows()[self.row].model
+    }
+
+    /// The full truth table of the argument.
+    pub fn table(&self) -> &TruthTable {
+        &self.table
+    }
+
+    /// Index of the highlighted countermodel row in `table().rows()`.
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    /// A one-sentence description of the countermodel.
+    pub fn summary(&self) -> String {
+        let assignment = describe_model(self.countermodel());
+        if self.table.formulas().len() == 1 {
+            format!(
+                "The argument is invalid: when {}, the conclusion {} is false.",
+                assignment, self.conclusion
+            )
+        } else {
+            format!(
+                "The argument is invalid: when {}, every assumption is true but the conclusion {} is false.",
+                assignment, self.conclusion
+            )
+        }
+    }
+
+    /// Renders the truth table as aligned plain text, marking the countermodel row with `>`.
+    pub fn to_text(&self) -> String {
+        let headers = self.headers();
+        let widths = headers.iter().map(|h| h.len()).collect::<Vec<usize>>();
+        let mut out = String::new();
+        out.push_str(&text_row("  ", &headers, &widths));
+        for (i, cells) in self.cells().into_iter().enumerate() {
+            let marker = if i == self.row { "> " } else { "  " };
+            out.push_str(&text_row(marker, &cells, &widths));
+        }
+        out.push('\n');
+        out.push_str(&self.summary());
+        out.push('\n');
+        out
+    }
+
+    /// Renders the truth table as a Markdown table with the countermodel row in bold.
+    pub fn to_markdown(&self) -> String {
+        let headers = self
+            .headers()
+            .iter()
+            .map(|h| format!("`{}`", h))
+            .collect::<Vec<String>>();
+        let mut out = format!("| {} |\n", headers.join(" | "));
+        out.push_str(&format!("|{}\n", "---|".repeat(headers.len())));
+        for (i, cells) in self.cells().into_iter().enumerate() {
+            let cells = if i == self.row {
+                cells.iter().map(|c| format!("**{}**", c)).collect()
+            } else {
+                cells
+            };
+            out.push_str(&format!("| {} |\n", cells.join(" | ")));
+        }
+        out.push('\n');
+        out.push_str(&self.summary());
+        out.push('\n');
+        out
+    }
+
+    /// Renders the truth table as an HTML `<table>`; the countermodel row has the class
+    /// `countermodel` so it can be styled by the page.
+    pub fn to_html(&self) -> String {
+        let mut out = String::from("<table class=\"truth-table\">\n<thead><tr>");
+        for header in self.headers() {
+            out.push_str(&format!("<th>{}</th>", escape_html(&header)));
+        }
+        out.push_str("</tr></thead>\n<tbody>\n");
+        for (i, cells) in self.cells().into_iter().enumerate() {
+            if i == self.row {
+                out.push_str("<tr class=\"countermodel\">");
+            } else {
+                out.push_str("<tr>");
+            }
+            for cell in cells {
+                out.push_str(&format!("<td>{}</td>", cell));
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</tbody>\n</table>\n");
+        out.push_str(&format!("<p>{}</p>\n", escape_html(&self.summary())));
+        out
+    }
+
+    fn headers(&self) -> Vec<String> {
+        self.table
+            .atoms()
+            .iter()
+            .map(ToString::to_string)
+            .chain(self.table.formulas().iter().map(ToString::to_string))
+            .collect()
+    }
+
+    fn cells(&self) -> Vec<Vec<String>> {
+        self.table
+            .rows()
+            .iter()
+            .map(|row| {
+                row.model
+                    .iter()
+                    .map(|(_, value)| value)
+                    .chain(row.values.iter().copied())
+                    .map(|value| truth_value(value).to_string())
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl Display for InvalidityExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_text())
+    }
+}
+
+fn truth_value(value: bool) -> &'static str {
+    if value {
+        "T"
+    } else {
+        "F"
+    }
+}
+
+fn describe_model(model: &Model) -> String {
+    let mut parts = model
+        .iter()
+        .map(|(var, value)| format!("{} is {}", var, if value { "true" } else { "false" }))
+        .collect::<Vec<String>>();
+    match parts.len() {
+        0 => String::new(),
+        1 => parts.remove(0),
+        _ => {
+            let last = parts.pop().unwrap();
+            format!("{} and {}", parts.join(", "), last)
+        }
+    }
+}
+
+fn text_row(marker: &str, cells: &[String], widths: &[usize]) -> String {
+    let cells = cells
+        .iter()
+        .zip(widths)
+        .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+        .collect::<Vec<String>>();
+    format!("{}{}\n", marker, cells.join(" | ").trim_end())
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -1,9 +1,25 @@
+use std::collections::{BTreeSet, HashMap};
 use std::fmt::{self, Display};
 use std::rc::Rc;
 
+use crate::{
+    cnf::{self, Tseitin},
+    error::EvalError,
+    fold::{fold_operands, visit_operands, Fold, Visitor},
+    minimize, normalize,
+    parser::PolishStyle,
+    sat,
+    semantics::{all_models, collect_atoms, Classification, MAX_TABLE_ATOMS},
+};
+
 /// Represents logical expressions in abstract syntax tree (AST) form.
 /// Supports basic logical operations like AND, OR, IMPLIES, and NOT, as well as variables.
-#[derive(Debug, PartialEq, Clone)]
+///
+/// Expressions are equal and hash alike when they have the same structure. They are ordered
+/// structurally: first by their outermost connective, in the order the variants are declared
+/// here, then by their operands from left to right, with letters ordered as `Symbol`s are. This
+/// order is fixed but is not that of the written forms.
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone)]
 pub enum Expression {
     /// Logical AND operation with two child `Expression` nodes.
     And(Rc<Expression>, Rc<Expression>),
@@ -14,11 +30,20 @@ pub enum Expression {
     /// Logical IMPLIES operation with two child `Expression` nodes.
     Implies(Rc<Expression>, Rc<Expression>),
 
+    /// Logical biconditional (IF AND ONLY IF) operation with two child `Expression` nodes.
+    Iff(Rc<Expression>, Rc<Expression>),
+
     /// Logical NOT operation with a single child `Expression` node.
     Not(Rc<Expression>),
 
-    /// Represents a variable in the logical expression, stored as a `String`.
-    Var(char),
+    /// A sentence letter, such as `P` or `P1`.
+    Var(Symbol),
+
+    /// The constant that is always true (verum).
+    Top,
+
+    /// The constant that is always false (falsum).
+    Bottom,
 }
 
 /// Implementation of the `Display` trait for the `Expression` enum.
@@ -29,8 +54,11 @@ impl Display for Expression {
             Expression::And(left, right) => write!(f, "({} & {})", left, right),
             Expression::Or(left, right) => write!(f, "({} v {})", left, right),
             Expression::Implies(left, right) => write!(f, "({} -> {})", left, right),
+            Expression::Iff(left, right) => write!(f, "({} <-> {})", left, right),
             Expression::Not(expr) => write!(f, "~{}", expr),
             Expression::Var(name) => write!(f, "{}", name),
+            Expression::Top => write!(f, "⊤"),
+            Expression::Bottom => write!(f, "⊥"),
         }
     }
 }
@@ -52,10 +80,10 @@ impl Expression {
     /// use propositional_logic_calculator::expression::Expression;
     /// use std::rc::Rc;
     ///
-    /// let expr = Expression::And(Expression::Var('A').wrap(),
+    /// let expr = Expression::And(Expression::Var('A'.into()).wrap(),
     ///     Expression::Or(
-    ///         Expression::Var('B').wrap(),
-    ///         Expression::Var('C').wrap(),
+    ///         Expression::Var('B'.into()).wrap(),
+    ///         Expression::Var('C'.into()).wrap(),
     ///     ).wrap(),
     /// );
     ///
@@ -67,7 +95,8 @@ impl Expression {
         match self {
             Expression::And(left, right)
             | Expression::Or(left, right)
-            | Expression::Implies(left, right) => {
+            | Expression::Implies(left, right)
+            | Expression::Iff(left, right) => {
                 expressions.push(self.clone());
                 expressions.extend(left.list_expressions());
                 expressions.extend(right.list_expressions());
@@ -76,9 +105,742 @@ impl Expression {
                 expressions.push(self.clone());
                 expressions.extend(expr.list_expressions());
             }
-            Expression::Var(_) => expressions.push(self.clone()),
+            Expression::Var(_) | Expression::Top | Expression::Bottom => {
+                expressions.push(self.clone())
+            }
         }
         expressions.dedup();
         expressions
     }
+
+    /// The names of the sentence letters in this `Expression`, written as `Symbol` displays them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use propositional_logic_calculator::proof::parse_expression;
+    ///
+    /// let atoms = parse_expression("(Q>P2)&(P2vT)").unwrap().atoms();
+    /// assert_eq!(atoms.into_iter().collect::<Vec<_>>(), ["P2", "Q"]);
+    /// ```
+    pub fn atoms(&self) -> BTreeSet<String> {
+        self.letters().iter().map(Symbol::to_string).collect()
+    }
+
+    /// The sentence letters in this `Expression`.
+    pub(crate) fn letters(&self) -> BTreeSet<Symbol> {
+        let mut letters = Letters(BTreeSet::new());
+        letters.visit(self);
+        letters.0
+    }
+
+    /// Writes this `Expression` in the parser's ASCII notation with only the parentheses that
+    /// precedence and associativity do not already imply, as described on `Parser::parse`.
+    /// `parse_expression` reads the result back into an equal `Expression`, unless it has a
+    /// sentence letter `T` or `F`, which the parser never produces. Those are written the same as
+    /// the constants and read back as `Top` and `Bottom`, and with a subscript they do not parse.
+    ///
+    /// Unlike `Display`, which brackets every binary connective, this gives one spelling for each
+    /// formula, so it suits storing formulas and comparing them as text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use propositional_logic_calculator::proof::parse_expression;
+    ///
+    /// let expression = parse_expression("((P&Q)v-(R>S))>(P>Q)").unwrap();
+    /// assert_eq!(expression.canonical(), "P & Q v -(R -> S) -> P -> Q");
+    /// assert_eq!(parse_expression(&expression.canonical()).unwrap(), expression);
+    /// ```
+    pub fn canonical(&self) -> String {
+        // Each binary connective with the least precedence each of its operands may have without
+        // parentheses. Operands on the side a connective groups towards need a higher one.
+        let (symbol, left, right, left_min, right_min) = match self {
+            Expression::Iff(left, right) => ("<->", left, right, 2, 1),
+            Expression::Implies(left, right) => ("->", left, right, 3, 2),
+            Expression::Or(left, right) => ("v", left, right, 3, 4),
+            Expression::And(left, right) => ("&", left, right, 4, 5),
+            Expression::Not(expr) => return format!("-{}", expr.canonical_operand(5)),
+            Expression::Var(name) => return name.to_string(),
+            Expression::Top => return "T".to_string(),
+            Expression::Bottom => return "F".to_string(),
+        };
+        format!(
+            "{} {} {}",
+            left.canonical_operand(left_min),
+            symbol,
+            right.canonical_operand(right_min)
+        )
+    }
+
+    /// The canonical form of this `Expression` as an operand, bracketed if its precedence is
+    /// below `min_precedence`.
+    fn canonical_operand(&self, min_precedence: u8) -> String {
+        let precedence = match self {
+            Expression::Iff(..) => 1,
+            Expression::Implies(..) => 2,
+            Expression::Or(..) => 3,
+            Expression::And(..) => 4,
+            _ => 5,
+        };
+        if precedence < min_precedence {
+            format!("({})", self.canonical())
+        } else {
+            self.canonical()
+        }
+    }
+
+    /// Writes this `Expression` in Polish notation in `style`, which `parse_polish` reads back
+    /// into an equal `Expression`. Returns `None` in the `Letters` style if a sentence letter is
+    /// one of the letters that style uses for connectives.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use propositional_logic_calculator::{parser::PolishStyle, proof::parse_expression};
+    ///
+    /// let expression = parse_expression("(P&Q)>-R").unwrap();
+    /// assert_eq!(expression.to_polish(PolishStyle::Symbols).unwrap(), "-> & P Q - R");
+    /// assert_eq!(expression.to_polish(PolishStyle::Letters).unwrap(), "CKPQNR");
+    /// assert_eq!(parse_expression("A&B").unwrap().to_polish(PolishStyle::Letters), None);
+    /// ```
+    pub fn to_polish(&self, style: PolishStyle) -> Option<String> {
+        let mut tokens = Vec::new();
+        self.polish_tokens(style, &mut tokens)?;
+        Some(match style {
+            PolishStyle::Symbols => tokens.join(" "),
+            PolishStyle::Letters => tokens.concat(),
+        })
+    }
+
+    /// Pushes the tokens of this `Expression` in Polish notation onto `tokens`.
+    fn polish_tokens(&self, style: PolishStyle, tokens: &mut Vec<String>) -> Option<()> {
+        let (symbol, letter, operands) = match self {
+            Expression::And(left, right) => ("&", 'K', vec![left, right]),
+            Expression::Or(left, right) => ("v", 'A', vec![left, right]),
+            Expression::Implies(left, right) => ("->", 'C', vec![left, right]),
+            Expression::Iff(left, right) => ("<->", 'E', vec![left, right]),
+            Expression::Not(expr) => ("-", 'N', vec![expr]),
+            Expression::Var(name) => {
+                let reserved = PolishStyle::LETTERS
+                    .iter()
+                    .any(|(letter, _)| Symbol::new(*letter) == *name);
+                if style == PolishStyle::Letters && reserved {
+                    return None;
+                }
+                tokens.push(name.to_string());
+                return Some(());
+            }
+            Expression::Top => ("T", 'T', vec![]),
+            Expression::Bottom => ("F", 'F', vec![]),
+        };
+        tokens.push(match style {
+            PolishStyle::Symbols => symbol.to_string(),
+            PolishStyle::Letters => letter.to_string(),
+        });
+        for operand in operands {
+            operand.polish_tokens(style, tokens)?;
+        }
+        Some(())
+    }
+
+    /// The number of connectives, sentence letters and constants in this `Expression`, counting repeats.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use propositional_logic_calculator::proof::parse_expression;
+    ///
+    /// assert_eq!(parse_expression("P>(P&-Q)").unwrap().size(), 6);
+    /// ```
+    pub fn size(&self) -> usize {
+        match self {
+            Expression::And(left, right)
+            | Expression::Or(left, right)
+            | Expression::Implies(left, right)
+            | Expression::Iff(left, right) => 1 + left.size() + right.size(),
+            Expression::Not(expr) => 1 + expr.size(),
+            Expression::Var(_) | Expression::Top | Expression::Bottom => 1,
+        }
+    }
+
+    /// The truth value of this `Expression` when each sentence letter has the value `assignment`
+    /// gives its name, written as `Symbol` displays it, such as `P` or `P1`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EvalError::UnassignedVariable` for the first sentence letter, from the left, that
+    /// has no value, even where the value of the rest would decide the result.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// use propositional_logic_calculator::{error::EvalError, proof::parse_expression};
+    ///
+    /// let assignment = HashMap::from([("P".to_string(), true), ("Q1".to_string(), false)]);
+    /// assert_eq!(parse_expression("P>Q1").unwrap().eval(&assignment), Ok(false));
+    /// assert_eq!(
+    ///     parse_expression("-PvR").unwrap().eval(&assignment),
+    ///     Err(EvalError::UnassignedVariable("R".to_string()))
+    /// );
+    /// ```
+    pub fn eval(&self, assignment: &HashMap<String, bool>) -> Result<bool, EvalError> {
+        Ok(match self {
+            Expression::And(left, right) => left.eval(assignment)? & right.eval(assignment)?,
+            Expression::Or(left, right) => left.eval(assignment)? | right.eval(assignment)?,
+            Expression::Implies(left, right) => !left.eval(assignment)? | right.eval(assignment)?,
+            Expression::Iff(left, right) => left.eval(assignment)? == right.eval(assignment)?,
+            Expression::Not(expr) => !expr.eval(assignment)?,
+            Expression::Var(var) => {
+                let name = var.to_string();
+                match assignment.get(&name) {
+                    Some(value) => *value,
+                    None => return Err(EvalError::UnassignedVariable(name)),
+                }
+            }
+            Expression::Top => true,
+            Expression::Bottom => false,
+        })
+    }
+
+    /// Whether this `Expression` is a tautology, a contradiction or contingent, found by
+    /// evaluating it under every model of its sentence letters until it has been both true and
+    /// false. With more than `MAX_TABLE_ATOMS` letters it instead asks `sat::find_model` for a
+    /// model of it and one of its negation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use propositional_logic_calculator::{proof::parse_expression, semantics::Classification};
+    ///
+    /// let classify = |x| parse_expression(x).unwrap().classify();
+    /// assert_eq!(classify("(P>Q)v(Q>P)"), Classification::Tautology);
+    /// assert_eq!(classify("P&-P"), Classification::Contradiction);
+    /// assert_eq!(classify("P>Q"), Classification::Contingent);
+    /// ```
+    pub fn classify(&self) -> Classification {
+        let atoms = collect_atoms([self]);
+        if atoms.len() > MAX_TABLE_ATOMS {
+            let true_somewhere = sat::find_model(std::slice::from_ref(self)).is_some();
+            let false_somewhere =
+                sat::find_model(&[Expression::Not(self.clone().wrap())]).is_some();
+            return match (true_somewhere, false_somewhere) {
+                (true, true) => Classification::Contingent,
+                (true, false) => Classification::Tautology,
+                _ => Classification::Contradiction,
+            };
+        }
+        let (mut true_somewhere, mut false_somewhere) = (false, false);
+        for model in all_models(&atoms) {
+            if model.satisfies(self) {
+                true_somewhere = true;
+            } else {
+                false_somewhere = true;
+            }
+            if true_somewhere && false_somewhere {
+                return Classification::Contingent;
+            }
+        }
+        if true_somewhere {
+            Classification::Tautology
+        } else {
+            Classification::Contradiction
+        }
+    }
+
+    /// Clauses that are satisfiable exactly when this `Expression` is, each a disjunction of
+    /// literals. Rather than distributing connectives over each other, which can make the clauses
+    /// exponentially larger than the formula, each connective is given a fresh sentence letter
+    /// (`X1`, `X2` and so on, skipping any the formula uses) that the clauses define to be
+    /// equivalent to it. The returned `Tseitin` lists those letters with the subformulas they
+    /// stand for.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the formula has more connectives than there are indexed sentence letters, which
+    /// is over fifty thousand.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use propositional_logic_calculator::{proof::parse_expression, semantics::Model};
+    ///
+    /// let formula = parse_expression("(P&Q)v-R").unwrap();
+    /// let tseitin = formula.tseitin();
+    /// // One letter each for `&` and `v`, with three clauses each and one asserting the whole
+    /// assert_eq!(tseitin.definitions.len(), 2);
+    /// assert_eq!(tseitin.clauses.len(), 7);
+    /// assert_eq!(tseitin.root.to_string(), "X2");
+    ///
+    /// let model = Model::from_iter([('P', true), ('Q', true), ('R', true)]);
+    /// assert!(tseitin.is_satisfied_by(&tseitin.extend(&model)));
+    /// ```
+    pub fn tseitin(&self) -> Tseitin {
+        cnf::tseitin(self)
+    }
+
+    /// Whether this `Expression` and `other` are true under exactly the same models, checked by
+    /// asking the `sat` solver for a model of `-(self <-> other)`. The two need not have the same
+    /// sentence letters: `P` is equivalent to `Pv(Q&-Q)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use propositional_logic_calculator::proof::parse_expression;
+    ///
+    /// let expr = |x| parse_expression(x).unwrap();
+    /// assert!(expr("-(P&Q)").is_equivalent_to(&expr("-Pv-Q")));
+    /// assert!(expr("P>Q").is_equivalent_to(&expr("-Q>-P")));
+    /// assert!(!expr("P>Q").is_equivalent_to(&expr("Q>P")));
+    /// ```
+    pub fn is_equivalent_to(&self, other: &Expression) -> bool {
+        let differ =
+            Expression::Not(Expression::Iff(self.clone().wrap(), other.clone().wrap()).wrap());
+        sat::find_model(&[differ]).is_none()
+    }
+
+    /// A renaming of the sentence letters of this `Expression` into those of `other` that turns
+    /// one into the other, if there is one. Different letters are always renamed to different
+    /// letters, so `P&Q` is not isomorphic to `P&P`. Only letters are renamed: the connectives,
+    /// their order and how they are grouped must be the same.
+    ///
+    /// Writing a sequent as one formula, such as `(A&B)>C` for `A, B ⊢ C`, makes this recognize
+    /// sequents that are the same problem.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// use propositional_logic_calculator::proof::parse_expression;
+    ///
+    /// let mpp = parse_expression("((P>Q)&P)>Q").unwrap();
+    /// let renaming = mpp.is_isomorphic_to(&parse_expression("((A>B)&A)>B").unwrap());
+    /// assert_eq!(
+    ///     renaming,
+    ///     Some(HashMap::from([('P'.into(), 'A'.into()), ('Q'.into(), 'B'.into())]))
+    /// );
+    /// assert_eq!(mpp.is_isomorphic_to(&parse_expression("((A>B)&B)>A").unwrap()), None);
+    /// ```
+    pub fn is_isomorphic_to(&self, other: &Expression) -> Option<HashMap<Symbol, Symbol>> {
+        let mut renaming = HashMap::new();
+        let mut renamed = HashMap::new();
+        self.rename_into(other, &mut renaming, &mut renamed)
+            .then_some(renaming)
+    }
+
+    /// Extends `renaming`, with `renamed` its inverse, so that it turns this `Expression` into
+    /// `other`, returning `false` if it cannot.
+    fn rename_into(
+        &self,
+        other: &Expression,
+        renaming: &mut HashMap<Symbol, Symbol>,
+        renamed: &mut HashMap<Symbol, Symbol>,
+    ) -> bool {
+        match (self, other) {
+            (Expression::Var(from), Expression::Var(to)) => {
+                *renaming.entry(*from).or_insert(*to) == *to
+                    && *renamed.entry(*to).or_insert(*from) == *from
+            }
+            (Expression::Top, Expression::Top) | (Expression::Bottom, Expression::Bottom) => true,
+            (Expression::Not(from), Expression::Not(to)) => from.rename_into(to, renaming, renamed),
+            (Expression::And(a, b), Expression::And(c, d))
+            | (Expression::Or(a, b), Expression::Or(c, d))
+            | (Expression::Implies(a, b), Expression::Implies(c, d))
+            | (Expression::Iff(a, b), Expression::Iff(c, d)) => {
+                a.rename_into(c, renaming, renamed) && b.rename_into(d, renaming, renamed)
+            }
+            _ => false,
+        }
+    }
+
+    /// This `Expression` with every occurrence of the sentence letter `var` replaced by
+    /// `replacement`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use propositional_logic_calculator::proof::parse_expression;
+    ///
+    /// let schema = parse_expression("P>(Q>P)").unwrap();
+    /// let instance = schema.substitute('P', &parse_expression("R&S").unwrap());
+    /// assert_eq!(instance, parse_expression("(R&S)>(Q>(R&S))").unwrap());
+    /// ```
+    pub fn substitute(&self, var: impl Into<Symbol>, replacement: &Expression) -> Expression {
+        self.substitute_all(&HashMap::from([(var.into(), replacement.clone())]))
+    }
+
+    /// This `Expression` with each sentence letter that is a key of `substitutions` replaced by
+    /// its value. The replacements are made all at once, so a letter inside a replacement is not
+    /// replaced again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// use propositional_logic_calculator::proof::parse_expression;
+    ///
+    /// let swapped = parse_expression("P&-Q").unwrap().substitute_all(&HashMap::from([
+    ///     ('P'.into(), parse_expression("Q").unwrap()),
+    ///     ('Q'.into(), parse_expression("P").unwrap()),
+    /// ]));
+    /// assert_eq!(swapped, parse_expression("Q&-P").unwrap());
+    /// ```
+    pub fn substitute_all(&self, substitutions: &HashMap<Symbol, Expression>) -> Expression {
+        Substitution(substitutions).fold(self)
+    }
+
+    /// An equivalent `Expression` with these laws applied throughout, until none applies:
+    ///
+    /// - double negation: `--A` is `A`
+    /// - idempotence: `A&A` and `AvA` are `A`
+    /// - absorption: `A&(AvB)` and `Av(A&B)` are `A`
+    /// - identity and annihilation: `A&⊤`, `Av⊥`, `⊤>A` and `⊤<->A` are `A`; `A&⊥` is `⊥`; `Av⊤`,
+    ///   `⊥>A` and `A>⊤` are `⊤`; `A>⊥` and `⊥<->A` are `-A`; and `-⊤` is `⊥`
+    ///
+    /// The operands of `&` and `v` are compared as written, so `A&(BvA)` is simplified but
+    /// `(A&B)&A` is not; normalizing with `Normalization::FULL` first brings more of these
+    /// together.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use propositional_logic_calculator::proof::parse_expression;
+    ///
+    /// let simplify = |x| parse_expression(x).unwrap().simplify().to_string();
+    /// assert_eq!(simplify("--(P&T)v(Q&F)"), "P");
+    /// assert_eq!(simplify("(P>Q)&((P>Q)vR)"), "(P -> Q)");
+    /// assert_eq!(simplify("(R&R)>F"), "~R");
+    /// ```
+    pub fn simplify(&self) -> Expression {
+        normalize::simplify(self)
+    }
+
+    /// A smallest disjunction of conjunctions of literals that is equivalent to this
+    /// `Expression`: the fewest conjunctions, and among those the fewest literals, found with the
+    /// Quine–McCluskey method. Conjunctions are listed shortest first, with their letters in
+    /// alphabetical order. A contradiction gives `⊥` and a tautology `⊤`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the formula has more than `minimize::MAX_MINIMIZED_ATOMS` sentence letters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use propositional_logic_calculator::proof::parse_expression;
+    ///
+    /// let minimize = |x| parse_expression(x).unwrap().minimize().to_string();
+    /// assert_eq!(minimize("(P&Q)v(P&-Q)"), "P");
+    /// assert_eq!(minimize("-(-Pv-Q)v(P&-Q)v(-P&Q)"), "(P v Q)");
+    /// assert_eq!(minimize("(P>Q)&(Q>R)&(R>P)"), "(((P & Q) & R) v ((~P & ~Q) & ~R))");
+    /// assert_eq!(minimize("Pv-P"), "⊤");
+    /// ```
+    pub fn minimize(&self) -> Expression {
+        minimize::minimize(self)
+    }
+
+    /// How many edits turn this `Expression` into `other`, where an edit adds or removes a
+    /// negation, swaps one binary connective for another, or replaces a sentence letter or
+    /// constant. A larger subformula that has to be replaced outright costs the size of the
+    /// larger of the two. Equal expressions are at distance 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use propositional_logic_calculator::proof::parse_expression;
+    ///
+    /// let goal = parse_expression("P>Q").unwrap();
+    /// assert_eq!(parse_expression("-(P>Q)").unwrap().distance(&goal), 1);
+    /// assert_eq!(parse_expression("P&Q").unwrap().distance(&goal), 1);
+    /// assert_eq!(parse_expression("-P>R").unwrap().distance(&goal), 2);
+    /// ```
+    pub fn distance(&self, other: &Expression) -> usize {
+        if self == other {
+            return 0;
+        }
+        let mut best = match (self, other) {
+            (Expression::Not(left), Expression::Not(right)) => left.distance(right),
+            (
+                Expression::And(a, b)
+                | Expression::Or(a, b)
+                | Expression::Implies(a, b)
+                | Expression::Iff(a, b),
+                Expression::And(c, d)
+                | Expression::Or(c, d)
+                | Expression::Implies(c, d)
+                | Expression::Iff(c, d),
+            ) => {
+                let swapped = std::mem::discriminant(self) != std::mem::discriminant(other);
+                usize::from(swapped) + a.distance(c) + b.distance(d)
+            }
+            _ => self.size().max(other.size()),
+        };
+        if let Expression::Not(inner) = self {
+            best = best.min(1 + inner.distance(other));
+        }
+        if let Expression::Not(inner) = other {
+            best = best.min(1 + self.distance(inner));
+        }
+        best
+    }
+}
+
+/// Replaces each sentence letter that has a replacement, for `Expression::substitute_all`.
+struct Substitution<'a>(&'a HashMap<Symbol, Expression>);
+
+impl Fold for Substitution<'_> {
+    fn fold(&mut self, expression: &Expression) -> Expression {
+        match expression {
+            Expression::Var(var) => self.0.get(var).unwrap_or(expression).clone(),
+            _ => fold_operands(self, expression),
+        }
+    }
+}
+
+/// Collects the sentence letters, for `Expression::letters`.
+struct Letters(BTreeSet<Symbol>);
+
+impl Visitor for Letters {
+    fn visit(&mut self, expression: &Expression) {
+        if let Expression::Var(var) = expression {
+            self.0.insert(*var);
+        }
+        visit_operands(self, expression);
+    }
+}
+
+/// A sentence letter: a letter with an optional subscript, as in `P`, `q` or `P1`.
+///
+/// Symbols are ordered by letter, capitals first, then by subscript, with a letter on its own
+/// before any of its subscripts, so `P` comes before `P1`, `P2` and then `Q`.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{
+///     expression::{Expression, Symbol},
+///     proof::parse_expression,
+/// };
+///
+/// let p1 = Symbol::indexed('P', 1);
+/// assert_eq!(parse_expression("P1").unwrap(), Expression::Var(p1));
+/// assert_eq!((p1.letter(), p1.index()), ('P', Some(1)));
+/// assert_eq!(p1.to_string(), "P1");
+/// assert!(Symbol::new('P') < p1 && p1 < Symbol::new('Q'));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol {
+    letter: char,
+    index: Option<u32>,
+}
+
+impl Symbol {
+    /// The sentence letter `letter`, without a subscript.
+    pub const fn new(letter: char) -> Self {
+        Symbol {
+            letter,
+            index: None,
+        }
+    }
+
+    /// The sentence letter `letter` with the subscript `index`, as in `P1`.
+    pub const fn indexed(letter: char, index: u32) -> Self {
+        Symbol {
+            letter,
+            index: Some(index),
+        }
+    }
+
+    pub fn letter(self) -> char {
+        self.letter
+    }
+
+    /// The subscript, if there is one.
+    pub fn index(self) -> Option<u32> {
+        self.index
+    }
+}
+
+impl From<char> for Symbol {
+    fn from(letter: char) -> Self {
+        Symbol::new(letter)
+    }
+}
+
+impl Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.letter)?;
+        if let Some(index) = self.index {
+            write!(f, "{}", index)?;
+        }
+        Ok(())
+    }
+}
+
+/// Written as the name, so that expressions debug-print readably.
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+/// Builds an `Expression` from a formula written in Rust code, with the grouping worked out at
+/// compile time.
+///
+/// The formula uses the parser's ASCII notation with its precedence: `-`, `~` or `!` for
+/// negation, `&`, `v` or `|`, `>` or `->`, and `<->` or `=`, the constants `T` and `F`, and
+/// letters for sentence letters, optionally with a subscript as in `P1`. Any other name is
+/// rejected when the crate using the macro is compiled.
+///
+/// Long formulas may need a higher `#![recursion_limit]`, since each token costs a few levels of
+/// macro expansion.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{prop, proof::parse_expression};
+///
+/// assert_eq!(prop!(A & (B > C)), parse_expression("A&(B>C)").unwrap());
+/// assert_eq!(prop!(-A v B <-> A -> B), parse_expression("-AvB<->A>B").unwrap());
+/// ```
+#[macro_export]
+macro_rules! prop {
+    // Biconditionals, which group to the right, so the formula is split at the first one.
+    (@iff [$($left:tt)+] <-> $($right:tt)+) => {
+        $crate::prop!(@binary Iff [$crate::prop!(@implies [] $($left)+)] [$crate::prop!(@iff [] $($right)+)])
+    };
+    (@iff [$($left:tt)+] = $($right:tt)+) => {
+        $crate::prop!(@binary Iff [$crate::prop!(@implies [] $($left)+)] [$crate::prop!(@iff [] $($right)+)])
+    };
+    (@iff [$($left:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::prop!(@iff [$($left)* $next] $($rest)*)
+    };
+    (@iff [$($tokens:tt)+]) => {
+        $crate::prop!(@implies [] $($tokens)+)
+    };
+
+    // Conditionals, which also group to the right.
+    (@implies [$($left:tt)+] > $($right:tt)+) => {
+        $crate::prop!(@binary Implies [$crate::prop!(@or {} [] $($left)+)] [$crate::prop!(@implies [] $($right)+)])
+    };
+    (@implies [$($left:tt)+] -> $($right:tt)+) => {
+        $crate::prop!(@binary Implies [$crate::prop!(@or {} [] $($left)+)] [$crate::prop!(@implies [] $($right)+)])
+    };
+    (@implies [$($left:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::prop!(@implies [$($left)* $next] $($rest)*)
+    };
+    (@implies [$($tokens:tt)+]) => {
+        $crate::prop!(@or {} [] $($tokens)+)
+    };
+
+    // Disjunctions, which group to the left, so the formula is split at the last one: the tokens
+    // before it, in braces, and those after it, in brackets, are collected separately.
+    (@or {} [$($after:tt)+] v $($rest:tt)+) => {
+        $crate::prop!(@or {$($after)+} [] $($rest)+)
+    };
+    (@or {} [$($after:tt)+] | $($rest:tt)+) => {
+        $crate::prop!(@or {$($after)+} [] $($rest)+)
+    };
+    (@or {$($before:tt)+} [$($after:tt)+] v $($rest:tt)+) => {
+        $crate::prop!(@or {$($before)+ v $($after)+} [] $($rest)+)
+    };
+    (@or {$($before:tt)+} [$($after:tt)+] | $($rest:tt)+) => {
+        $crate::prop!(@or {$($before)+ v $($after)+} [] $($rest)+)
+    };
+    (@or {$($before:tt)*} [$($after:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::prop!(@or {$($before)*} [$($after)* $next] $($rest)*)
+    };
+    (@or {} [$($tokens:tt)+]) => {
+        $crate::prop!(@and {} [] $($tokens)+)
+    };
+    (@or {$($before:tt)+} [$($after:tt)+]) => {
+        $crate::prop!(@binary Or [$crate::prop!(@or {} [] $($before)+)] [$crate::prop!(@and {} [] $($after)+)])
+    };
+
+    // Conjunctions, which also group to the left.
+    (@and {} [$($after:tt)+] & $($rest:tt)+) => {
+        $crate::prop!(@and {$($after)+} [] $($rest)+)
+    };
+    (@and {$($before:tt)+} [$($after:tt)+] & $($rest:tt)+) => {
+        $crate::prop!(@and {$($before)+ & $($after)+} [] $($rest)+)
+    };
+    (@and {$($before:tt)*} [$($after:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::prop!(@and {$($before)*} [$($after)* $next] $($rest)*)
+    };
+    (@and {} [$($tokens:tt)+]) => {
+        $crate::prop!(@operand $($tokens)+)
+    };
+    (@and {$($before:tt)+} [$($after:tt)+]) => {
+        $crate::prop!(@binary And [$crate::prop!(@and {} [] $($before)+)] [$crate::prop!(@operand $($after)+)])
+    };
+
+    // Negations, constants, sentence letters and bracketed formulas.
+    (@operand - $($operand:tt)+) => {
+        $crate::expression::Expression::Not($crate::prop!(@operand $($operand)+).wrap())
+    };
+    (@operand ~ $($operand:tt)+) => {
+        $crate::expression::Expression::Not($crate::prop!(@operand $($operand)+).wrap())
+    };
+    (@operand ! $($operand:tt)+) => {
+        $crate::expression::Expression::Not($crate::prop!(@operand $($operand)+).wrap())
+    };
+    (@operand T) => {
+        $crate::expression::Expression::Top
+    };
+    (@operand F) => {
+        $crate::expression::Expression::Bottom
+    };
+    (@operand ($($inner:tt)+)) => {
+        $crate::prop!(@iff [] $($inner)+)
+    };
+    (@operand $letter:ident) => {{
+        const LETTER: $crate::expression::Symbol =
+            $crate::expression::sentence_letter(stringify!($letter));
+        $crate::expression::Expression::Var(LETTER)
+    }};
+
+    (@binary $connective:ident [$left:expr] [$right:expr]) => {
+        $crate::expression::Expression::$connective($left.wrap(), $right.wrap())
+    };
+
+    ($($tokens:tt)+) => {
+        $crate::prop!(@iff [] $($tokens)+)
+    };
+}
+
+/// The sentence letter named `name`, for `prop!`. Evaluated in a constant, so that a name that is
+/// not a sentence letter stops compilation.
+#[doc(hidden)]
+pub const fn sentence_letter(name: &str) -> Symbol {
+    const MESSAGE: &str =
+        "prop! sentence letters are letters other than T, F and v, with an optional subscript";
+    let bytes = name.as_bytes();
+    let letter = match bytes[0] {
+        b'T' | b'F' | b'v' => panic!("{}", MESSAGE),
+        letter @ (b'A'..=b'Z' | b'a'..=b'z') => letter as char,
+        _ => panic!("{}", MESSAGE),
+    };
+    if bytes.len() == 1 {
+        return Symbol::new(letter);
+    }
+    let mut index: u32 = 0;
+    let mut i = 1;
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            panic!("{}", MESSAGE);
+        }
+        index = match index.checked_mul(10) {
+            Some(shifted) => match shifted.checked_add((bytes[i] - b'0') as u32) {
+                Some(index) => index,
+                None => panic!("{}", MESSAGE),
+            },
+            None => panic!("{}", MESSAGE),
+        };
+        i += 1;
+    }
+    Symbol::indexed(letter, index)
 }
@@ -0,0 +1,114 @@
+use crate::expression::Expression;
+
+/// A pass that rebuilds an `Expression`, node by node.
+///
+/// By default `fold` keeps each connective and folds its operands, so a pass only overrides
+/// `fold` to handle the nodes it changes and calls `fold_operands` for the rest.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{
+///     expression::Expression,
+///     fold::{fold_operands, Fold},
+///     proof::parse_expression,
+/// };
+///
+/// /// Writes conditionals as disjunctions.
+/// struct MaterialImplication;
+///
+/// impl Fold for MaterialImplication {
+///     fn fold(&mut self, expression: &Expression) -> Expression {
+///         match expression {
+///             Expression::Implies(left, right) => Expression::Or(
+///                 Expression::Not(self.fold(left).wrap()).wrap(),
+///                 self.fold(right).wrap(),
+///             ),
+///             _ => fold_operands(self, expression),
+///         }
+///     }
+/// }
+///
+/// let folded = MaterialImplication.fold(&parse_expression("-(P>Q)&R").unwrap());
+/// assert_eq!(folded, parse_expression("-(-PvQ)&R").unwrap());
+/// ```
+pub trait Fold {
+    /// Rebuilds `expression`.
+    fn fold(&mut self, expression: &Expression) -> Expression {
+        fold_operands(self, expression)
+    }
+}
+
+/// Rebuilds `expression` with the same connective and each operand folded by `folder`. Sentence
+/// letters and constants are returned as they are.
+pub fn fold_operands<F: Fold + ?Sized>(folder: &mut F, expression: &Expression) -> Expression {
+    match expression {
+        Expression::And(left, right) => {
+            Expression::And(folder.fold(left).wrap(), folder.fold(right).wrap())
+        }
+        Expression::Or(left, right) => {
+            Expression::Or(folder.fold(left).wrap(), folder.fold(right).wrap())
+        }
+        Expression::Implies(left, right) => {
+            Expression::Implies(folder.fold(left).wrap(), folder.fold(right).wrap())
+        }
+        Expression::Iff(left, right) => {
+            Expression::Iff(folder.fold(left).wrap(), folder.fold(right).wrap())
+        }
+        Expression::Not(expr) => Expression::Not(folder.fold(expr).wrap()),
+        Expression::Var(_) | Expression::Top | Expression::Bottom => expression.clone(),
+    }
+}
+
+/// A pass that looks at every node of an `Expression` without changing it.
+///
+/// By default `visit` visits the operands of each node from left to right, so a pass only
+/// overrides `visit` to act on the nodes it cares about, calling `visit_operands` to go on into
+/// them.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{
+///     expression::Expression,
+///     fold::{visit_operands, Visitor},
+///     proof::parse_expression,
+/// };
+///
+/// /// Counts the negations.
+/// struct Negations(usize);
+///
+/// impl Visitor for Negations {
+///     fn visit(&mut self, expression: &Expression) {
+///         if let Expression::Not(_) = expression {
+///             self.0 += 1;
+///         }
+///         visit_operands(self, expression);
+///     }
+/// }
+///
+/// let mut negations = Negations(0);
+/// negations.visit(&parse_expression("--P>(Qv-R)").unwrap());
+/// assert_eq!(negations.0, 3);
+/// ```
+pub trait Visitor {
+    /// Visits `expression`.
+    fn visit(&mut self, expression: &Expression) {
+        visit_operands(self, expression)
+    }
+}
+
+/// Visits each operand of `expression` with `visitor`, from left to right.
+pub fn visit_operands<V: Visitor + ?Sized>(visitor: &mut V, expression: &Expression) {
+    match expression {
+        Expression::And(left, right)
+        | Expression::Or(left, right)
+        | Expression::Implies(left, right)
+        | Expression::Iff(left, right) => {
+            visitor.visit(left);
+            visitor.visit(right);
+        }
+        Expression::Not(expr) => visitor.visit(expr),
+        Expression::Var(_) | Expression::Top | Expression::Bottom => {}
+    }
+}
@@ -0,0 +1,169 @@
+use std::fmt::{self, Display};
+
+use enum_iterator::{all, Sequence};
+
+use crate::{
+    expression::{Expression, Symbol},
+    proof::parse_expression,
+};
+
+/// A named argument form from introductory logic, valid or not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Sequence)]
+pub enum ArgumentForm {
+    ModusPonens,
+    ModusTollens,
+    HypotheticalSyllogism,
+    DisjunctiveSyllogism,
+    ConstructiveDilemma,
+    DestructiveDilemma,
+    SimpleDilemma,
+    AffirmingTheConsequent,
+    DenyingTheAntecedent,
+    AffirmingADisjunct,
+}
+
+impl ArgumentForm {
+    /// Whether every argument of this form is valid. The invalid forms are the standard formal
+    /// fallacies.
+    pub fn is_valid(self) -> bool {
+        !matches!(
+            self,
+            ArgumentForm::AffirmingTheConsequent
+                | ArgumentForm::DenyingTheAntecedent
+                | ArgumentForm::AffirmingADisjunct
+        )
+    }
+
+    /// The premises and conclusion of the form, with `A` to `D` standing for any formulas.
+    fn pattern(self) -> (&'static [&'static str], &'static str) {
+        match self {
+            ArgumentForm::ModusPonens => (&["A>B", "A"], "B"),
+            ArgumentForm::ModusTollens => (&["A>B", "-B"], "-A"),
+            ArgumentForm::HypotheticalSyllogism => (&["A>B", "B>C"], "A>C"),
+            ArgumentForm::DisjunctiveSyllogism => (&["AvB", "-A"], "B"),
+            ArgumentForm::ConstructiveDilemma => (&["A>C", "B>D", "AvB"], "CvD"),
+            ArgumentForm::DestructiveDilemma => (&["A>C", "B>D", "-Cv-D"], "-Av-B"),
+            ArgumentForm::SimpleDilemma => (&["A>C", "B>C", "AvB"], "C"),
+            ArgumentForm::AffirmingTheConsequent => (&["A>B", "B"], "A"),
+            ArgumentForm::DenyingTheAntecedent => (&["A>B", "-A"], "-B"),
+            ArgumentForm::AffirmingADisjunct => (&["AvB", "A"], "-B"),
+        }
+    }
+
+    /// Whether the argument from `premises`, in any order, to `conclusion` has this form.
+    fn matches(self, premises: &[Expression], conclusion: &Expression) -> bool {
+        let (pattern_premises, pattern_conclusion) = self.pattern();
+        if pattern_premises.len() != premises.len() {
+            return false;
+        }
+        let pattern_premises = pattern_premises
+            .iter()
+            .map(|p| parse_expression(p).unwrap())
+            .collect::<Vec<_>>();
+        let mut bindings = Vec::new();
+        if !bind(
+            &parse_expression(pattern_conclusion).unwrap(),
+            conclusion,
+            &mut bindings,
+        ) {
+            return false;
+        }
+        let mut used = vec![false; premises.len()];
+        match_premises(&pattern_premises, premises, &mut used, &bindings)
+    }
+}
+
+impl Display for ArgumentForm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ArgumentForm::ModusPonens => "modus ponens",
+            ArgumentForm::ModusTollens => "modus tollens",
+            ArgumentForm::HypotheticalSyllogism => "hypothetical syllogism",
+            ArgumentForm::DisjunctiveSyllogism => "disjunctive syllogism",
+            ArgumentForm::ConstructiveDilemma => "constructive dilemma",
+            ArgumentForm::DestructiveDilemma => "destructive dilemma",
+            ArgumentForm::SimpleDilemma => "simple dilemma",
+            ArgumentForm::AffirmingTheConsequent => "affirming the consequent",
+            ArgumentForm::DenyingTheAntecedent => "denying the antecedent",
+            ArgumentForm::AffirmingADisjunct => "affirming a disjunct",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Finds the named argument forms that the argument from `premises` to `conclusion` is an
+/// instance of. The premises may come in any order. An argument can have more than one form,
+/// as `P>P, P ⊢ P` is both modus ponens and affirming the consequent, and an argument with
+/// none gives an empty list.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{
+///     forms::{argument_forms, ArgumentForm},
+///     proof::parse_expression,
+/// };
+///
+/// let premises = vec![parse_expression("Q").unwrap(), parse_expression("P>Q").unwrap()];
+/// let forms = argument_forms(&premises, &parse_expression("P").unwrap());
+/// assert_eq!(forms, vec![ArgumentForm::AffirmingTheConsequent]);
+/// assert!(!forms[0].is_valid());
+/// ```
+pub fn argument_forms(premises: &[Expression], conclusion: &Expression) -> Vec<ArgumentForm> {
+    all::<ArgumentForm>()
+        .filter(|form| form.matches(premises, conclusion))
+        .collect()
+}
+
+/// Matches the first of `patterns` against each premise not yet `used`, and the rest of
+/// `patterns` against the remaining premises, keeping `bindings` consistent throughout.
+fn match_premises(
+    patterns: &[Expression],
+    premises: &[Expression],
+    used: &mut [bool],
+    bindings: &[(Symbol, Expression)],
+) -> bool {
+    let Some((pattern, rest)) = patterns.split_first() else {
+        return true;
+    };
+    for i in 0..premises.len() {
+        if used[i] {
+            continue;
+        }
+        let mut extended = bindings.to_vec();
+        if bind(pattern, &premises[i], &mut extended) {
+            used[i] = true;
+            if match_premises(rest, premises, used, &extended) {
+                return true;
+            }
+            used[i] = false;
+        }
+    }
+    false
+}
+
+/// Matches `pattern` against `expression`, binding each letter of the pattern to the formula it
+/// stands for. A letter already bound must stand for the same formula again.
+fn bind(
+    pattern: &Expression,
+    expression: &Expression,
+    bindings: &mut Vec<(Symbol, Expression)>,
+) -> bool {
+    match (pattern, expression) {
+        (Expression::Var(letter), _) => match bindings.iter().find(|(bound, _)| bound == letter) {
+            Some((_, bound)) => bound == expression,
+            None => {
+                bindings.push((*letter, expression.clone()));
+                true
+            }
+        },
+        (Expression::Not(p), Expression::Not(e)) => bind(p, e, bindings),
+        (Expression::And(pl, pr), Expression::And(el, er))
+        | (Expression::Or(pl, pr), Expression::Or(el, er))
+        | (Expression::Implies(pl, pr), Expression::Implies(el, er))
+        | (Expression::Iff(pl, pr), Expression::Iff(el, er)) => {
+            bind(pl, el, bindings) && bind(pr, er, bindings)
+        }
+        _ => false,
+    }
+}
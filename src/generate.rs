@@ -0,0 +1,455 @@
+use std::rc::Rc;
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    expression::{Expression, Symbol},
+    sat,
+    semantics::{entails_set, ConclusionMode, Model},
+};
+
+/// Controls the shape of generated formulas and arguments.
+#[derive(Debug, Clone)]
+pub struct GeneratorSettings {
+    /// Number of distinct sentence letters to draw from (at most 24).
+    pub atoms: usize,
+    /// Number of premises in each generated argument.
+    pub premises: usize,
+    /// Maximum connective nesting depth of each generated formula.
+    pub max_depth: usize,
+    /// How often each connective is picked for a formula that is not a single letter.
+    pub connectives: ConnectiveWeights,
+}
+
+impl GeneratorSettings {
+    const DEFAULT_ATOMS: usize = 3;
+    const DEFAULT_PREMISES: usize = 2;
+    const DEFAULT_MAX_DEPTH: usize = 2;
+}
+
+impl Default for GeneratorSettings {
+    fn default() -> Self {
+        Self {
+            atoms: Self::DEFAULT_ATOMS,
+            premises: Self::DEFAULT_PREMISES,
+            max_depth: Self::DEFAULT_MAX_DEPTH,
+            connectives: ConnectiveWeights::default(),
+        }
+    }
+}
+
+/// The relative weights of the connectives in generated formulas. A connective with weight 0
+/// never appears, and if every weight is 0 each formula is a single letter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectiveWeights {
+    pub not: u32,
+    pub and: u32,
+    pub or: u32,
+    pub implies: u32,
+    pub iff: u32,
+}
+
+impl ConnectiveWeights {
+    /// The weights in the order `random_expression` picks from: negation, then `CONNECTIVES`.
+    /// They are widened so that their sum cannot overflow.
+    fn in_order(self) -> [u64; 5] {
+        [self.not, self.and, self.or, self.implies, self.iff].map(u64::from)
+    }
+}
+
+/// Every connective but `<->` is equally likely.
+impl Default for ConnectiveWeights {
+    fn default() -> Self {
+        Self {
+            not: 1,
+            and: 1,
+            or: 1,
+            implies: 1,
+            iff: 0,
+        }
+    }
+}
+
+/// How many arguments `valid_sequent` generates before giving up.
+pub const VALID_SEQUENT_ATTEMPTS: usize = 1000;
+
+/// Generates a random formula over the first `settings.atoms` sentence letters, nested at most
+/// `settings.max_depth` deep, with connectives weighted by `settings.connectives`.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::generate::{formula, ConnectiveWeights, GeneratorSettings};
+///
+/// let settings = GeneratorSettings {
+///     connectives: ConnectiveWeights {
+///         not: 0,
+///         and: 1,
+///         or: 0,
+///         implies: 0,
+///         iff: 0,
+///     },
+///     ..Default::default()
+/// };
+/// let expression = formula(&mut rand::thread_rng(), &settings);
+/// assert!(!expression.to_string().contains(['~', 'v', '>']));
+/// ```
+pub fn formula<R: Rng + ?Sized>(rng: &mut R, settings: &GeneratorSettings) -> Expression {
+    let atoms = atom_names(settings.atoms);
+    random_expression(rng, &atoms, settings.max_depth, settings.connectives)
+}
+
+/// An argument that is guaranteed to be valid.
+#[derive(Debug, Clone)]
+pub struct ValidSequent {
+    pub assumptions: Vec<Expression>,
+    pub conclusion: Expression,
+}
+
+/// Generates a random argument whose conclusion follows from its premises, or `None` if none
+/// was found in `VALID_SEQUENT_ATTEMPTS` tries.
+///
+/// The premises are generated to be true under a random model, as for `invalid_sequent`, so they
+/// are never inconsistent and the argument is not valid merely because of that. A random
+/// conclusion that does not follow is replaced by its negation if that follows, and otherwise a
+/// new argument is tried. Without premises the conclusion has to be a tautology, which a formula
+/// of depth 0 never is.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{
+///     generate::{valid_sequent, GeneratorSettings},
+///     sat::is_valid,
+/// };
+///
+/// let settings = GeneratorSettings::default();
+/// let sequent = valid_sequent(&mut rand::thread_rng(), &settings).unwrap();
+/// assert!(is_valid(&sequent.assumptions, &sequent.conclusion));
+///
+/// let settings = GeneratorSettings {
+///     premises: 0,
+///     max_depth: 0,
+///     ..Default::default()
+/// };
+/// assert!(valid_sequent(&mut rand::thread_rng(), &settings).is_none());
+/// ```
+pub fn valid_sequent<R: Rng + ?Sized>(
+    rng: &mut R,
+    settings: &GeneratorSettings,
+) -> Option<ValidSequent> {
+    let atoms = atom_names(settings.atoms);
+    for _ in 0..VALID_SEQUENT_ATTEMPTS {
+        let model = atoms
+            .iter()
+            .map(|var| (*var, rng.gen_bool(0.5)))
+            .collect::<Model>();
+        let assumptions = (0..settings.premises)
+            .map(|_| expression_with_value(rng, &atoms, settings, &model, true))
+            .collect::<Vec<_>>();
+        let conclusion = random_expression(rng, &atoms, settings.max_depth, settings.connectives);
+        let negated = Expression::Not(conclusion.clone().wrap());
+        for conclusion in [conclusion, negated] {
+            if sat::is_valid(&assumptions, &conclusion) {
+                return Some(ValidSequent {
+                    assumptions,
+                    conclusion,
+                });
+            }
+        }
+    }
+    None
+}
+
+/// An argument that is guaranteed to be invalid, together with the model that certifies it.
+#[derive(Debug, Clone)]
+pub struct InvalidSequent {
+    pub assumptions: Vec<Expression>,
+    pub conclusion: Expression,
+    /// A model under which every assumption is true and the conclusion is false.
+    pub countermodel: Model,
+}
+
+/// Generates a random argument that is not derivable.
+///
+/// The countermodel is chosen first and each formula is then generated so that it takes the
+/// required value under it (a formula with the wrong value is simply negated), so the result is
+/// always invalid without needing a proof search or a truth table.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::generate::{invalid_sequent, GeneratorSettings};
+///
+/// let sequent = invalid_sequent(&mut rand::thread_rng(), &GeneratorSettings::default());
+/// assert!(sequent.assumptions.iter().all(|a| sequent.countermodel.satisfies(a)));
+/// assert_eq!(sequent.countermodel.evaluate(&sequent.conclusion), Some(false));
+/// ```
+pub fn invalid_sequent<R: Rng + ?Sized>(
+    rng: &mut R,
+    settings: &GeneratorSettings,
+) -> InvalidSequent {
+    let atoms = atom_names(settings.atoms);
+    let countermodel = atoms
+        .iter()
+        .map(|var| (*var, rng.gen_bool(0.5)))
+        .collect::<Model>();
+
+    let assumptions = (0..settings.premises)
+        .map(|_| expression_with_value(rng, &atoms, settings, &countermodel, true))
+        .collect();
+    let conclusion = expression_with_value(rng, &atoms, settings, &countermodel, false);
+
+    InvalidSequent {
+        assumptions,
+        conclusion,
+        countermodel,
+    }
+}
+
+/// Controls which variants `mutate` makes and how many it returns.
+#[derive(Debug, Clone)]
+pub struct MutationSettings {
+    /// Number of variants to return, fewer if there are not that many.
+    pub count: usize,
+    /// Replace a binary connective with another.
+    pub flip_connectives: bool,
+    /// Negate a subformula, or remove the negation from one that is already negated.
+    pub negate_subformulas: bool,
+    /// Swap the operands of a binary connective.
+    pub swap_operands: bool,
+    /// The premises of the sequent the mutated expression is the conclusion of, which each
+    /// variant's validity is checked against.
+    pub premises: Vec<Expression>,
+}
+
+impl MutationSettings {
+    const DEFAULT_COUNT: usize = 4;
+}
+
+impl Default for MutationSettings {
+    fn default() -> Self {
+        Self {
+            count: Self::DEFAULT_COUNT,
+            flip_connectives: true,
+            negate_subformulas: true,
+            swap_operands: true,
+            premises: Vec::new(),
+        }
+    }
+}
+
+/// The edit that made a `Mutation` from the original expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MutationKind {
+    FlipConnective,
+    Negate,
+    SwapOperands,
+}
+
+/// A variant of an expression made by one edit, as returned by `mutate`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mutation {
+    pub expression: Expression,
+    pub kind: MutationKind,
+    /// Whether the premises entail the variant, by truth tables.
+    pub valid: bool,
+    /// Whether the sequent with the variant as conclusion is valid exactly when the sequent with
+    /// the original is.
+    pub preserves_validity: bool,
+}
+
+/// Makes up to `settings.count` random variants of `expression`, each one edit away from it: a
+/// connective flipped, a subformula negated or operands swapped, as `settings` allows. Each is
+/// reported with whether it keeps the validity of the sequent from `settings.premises` to
+/// `expression`. No variant is equal to `expression` or to another variant.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{
+///     generate::{mutate, MutationSettings},
+///     proof::parse_expression,
+/// };
+///
+/// let settings = MutationSettings {
+///     premises: vec![parse_expression("P>Q").unwrap(), parse_expression("P").unwrap()],
+///     ..Default::default()
+/// };
+/// let conclusion = parse_expression("Q").unwrap();
+/// let mutations = mutate(&mut rand::thread_rng(), &conclusion, &settings);
+/// // The only variant of a single letter is its negation, which no longer follows
+/// assert_eq!(mutations.len(), 1);
+/// assert_eq!(mutations[0].expression, parse_expression("-Q").unwrap());
+/// assert!(!mutations[0].preserves_validity);
+/// ```
+pub fn mutate<R: Rng + ?Sized>(
+    rng: &mut R,
+    expression: &Expression,
+    settings: &MutationSettings,
+) -> Vec<Mutation> {
+    let mut variants = Vec::new();
+    for (variant, kind) in variants_of(expression, settings) {
+        if variant != *expression && !variants.iter().any(|(other, _)| *other == variant) {
+            variants.push((variant, kind));
+        }
+    }
+    variants.shuffle(rng);
+    variants.truncate(settings.count);
+
+    let is_valid = |conclusion: &Expression| {
+        entails_set(
+            &settings.premises,
+            std::slice::from_ref(conclusion),
+            ConclusionMode::Conjunctive,
+        )
+        .is_valid()
+    };
+    let original = is_valid(expression);
+    variants
+        .into_iter()
+        .map(|(expression, kind)| {
+            let valid = is_valid(&expression);
+            Mutation {
+                expression,
+                kind,
+                valid,
+                preserves_validity: valid == original,
+            }
+        })
+        .collect()
+}
+
+/// Every expression one edit allowed by `settings` away from `expression`, possibly with repeats.
+fn variants_of(
+    expression: &Expression,
+    settings: &MutationSettings,
+) -> Vec<(Expression, MutationKind)> {
+    let mut variants = Vec::new();
+    if settings.negate_subformulas {
+        let negated = match expression {
+            Expression::Not(inner) => inner.as_ref().clone(),
+            _ => Expression::Not(expression.clone().wrap()),
+        };
+        variants.push((negated, MutationKind::Negate));
+    }
+    match expression {
+        Expression::Not(inner) => {
+            for (variant, kind) in variants_of(inner, settings) {
+                variants.push((Expression::Not(variant.wrap()), kind));
+            }
+        }
+        Expression::And(left, right)
+        | Expression::Or(left, right)
+        | Expression::Implies(left, right)
+        | Expression::Iff(left, right) => {
+            let rebuild = |connective: &Expression, left: Rc<Expression>, right: Rc<Expression>| {
+                match connective {
+                    Expression::And(..) => Expression::And(left, right),
+                    Expression::Or(..) => Expression::Or(left, right),
+                    Expression::Implies(..) => Expression::Implies(left, right),
+                    _ => Expression::Iff(left, right),
+                }
+            };
+            if settings.flip_connectives {
+                for connective in CONNECTIVES {
+                    let flipped = connective(left.clone(), right.clone());
+                    if std::mem::discriminant(&flipped) != std::mem::discriminant(expression) {
+                        variants.push((flipped, MutationKind::FlipConnective));
+                    }
+                }
+            }
+            if settings.swap_operands {
+                variants.push((
+                    rebuild(expression, right.clone(), left.clone()),
+                    MutationKind::SwapOperands,
+                ));
+            }
+            for (variant, kind) in variants_of(left, settings) {
+                variants.push((rebuild(expression, variant.wrap(), right.clone()), kind));
+            }
+            for (variant, kind) in variants_of(right, settings) {
+                variants.push((rebuild(expression, left.clone(), variant.wrap()), kind));
+            }
+        }
+        Expression::Var(_) | Expression::Top | Expression::Bottom => (),
+    }
+    variants
+}
+
+/// Builds an expression from a binary connective's operands.
+type Connective = fn(Rc<Expression>, Rc<Expression>) -> Expression;
+
+/// The binary connectives `mutate` flips between.
+const CONNECTIVES: [Connective; 4] = [
+    Expression::And,
+    Expression::Or,
+    Expression::Implies,
+    Expression::Iff,
+];
+
+/// Picks `count` sentence letters, starting from `P` and wrapping around the alphabet. `T` and `F`
+/// are skipped because they are read as constants.
+pub(crate) fn atom_names(count: usize) -> Vec<Symbol> {
+    let letters = ('A'..='Z')
+        .filter(|c| !matches!(c, 'T' | 'F'))
+        .collect::<Vec<_>>();
+    let start = letters.iter().position(|c| *c == 'P').unwrap();
+    (0..count.clamp(1, letters.len()))
+        .map(|i| Symbol::new(letters[(start + i) % letters.len()]))
+        .collect()
+}
+
+/// The sentence letter of the atom called `name` in `atoms`, which pairs names from some other
+/// notation with letters. A new name is given the next letter of `atom_names` and added to
+/// `atoms`. Returns `None` when every letter is taken.
+pub(crate) fn letter_for(atoms: &mut Vec<(String, Symbol)>, name: &str) -> Option<Symbol> {
+    if let Some((_, letter)) = atoms.iter().find(|(atom, _)| atom == name) {
+        return Some(*letter);
+    }
+    let letter = *atom_names(usize::MAX).get(atoms.len())?;
+    atoms.push((name.to_string(), letter));
+    Some(letter)
+}
+
+/// Generates a random expression that evaluates to `value` under `model`.
+fn expression_with_value<R: Rng + ?Sized>(
+    rng: &mut R,
+    atoms: &[Symbol],
+    settings: &GeneratorSettings,
+    model: &Model,
+    value: bool,
+) -> Expression {
+    let expression = random_expression(rng, atoms, settings.max_depth, settings.connectives);
+    match model.evaluate(&expression) {
+        Some(actual) if actual == value => expression,
+        _ => Expression::Not(expression.wrap()),
+    }
+}
+
+fn random_expression<R: Rng + ?Sized>(
+    rng: &mut R,
+    atoms: &[Symbol],
+    depth: usize,
+    weights: ConnectiveWeights,
+) -> Expression {
+    let order = weights.in_order();
+    let total = order.iter().sum::<u64>();
+    if depth == 0 || total == 0 || rng.gen_bool(0.3) {
+        return Expression::Var(atoms[rng.gen_range(0..atoms.len())]);
+    }
+    let left = random_expression(rng, atoms, depth - 1, weights).wrap();
+    let mut pick = rng.gen_range(0..total);
+    let mut index = 0;
+    while pick >= order[index] {
+        pick -= order[index];
+        index += 1;
+    }
+    match index {
+        0 => Expression::Not(left),
+        _ => CONNECTIVES[index - 1](
+            left,
+            random_expression(rng, atoms, depth - 1, weights).wrap(),
+        ),
+    }
+}
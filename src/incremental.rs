@@ -0,0 +1,201 @@
+use std::ops::Range;
+
+use crate::{
+    error::ParserError,
+    expression::Expression,
+    parser::{Parser, Spanned},
+};
+
+/// A formula being edited, kept parsed as it changes, for editors that check the formula on
+/// every keystroke.
+///
+/// `edit` reparses only the smallest part of the formula that the edit cannot affect the grouping
+/// outside of: the sentence letter or constant it falls inside, if it is still one afterwards, or
+/// else the inside of the innermost parentheses around it. The rest of the tree is kept, with its
+/// spans moved to match the new text. When no such part parses, the whole formula is parsed again.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{incremental::Document, proof::parse_expression};
+///
+/// let mut document = Document::parse("P > (Q & R)").unwrap();
+/// // Replace `Q & R` with `Q v -S`
+/// let reparsed = document.edit(5..10, "Q v -S").unwrap();
+/// assert_eq!(reparsed, 4..12);
+/// assert_eq!(document.text(), "P > (Q v -S)");
+/// assert_eq!(*document.expression(), parse_expression("P>(Qv-S)").unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document {
+    text: String,
+    tree: Spanned,
+}
+
+impl Document {
+    /// Parses `text` as a new document.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ParserError` if `text` is not a valid formula.
+    pub fn parse(text: &str) -> Result<Self, ParserError> {
+        Ok(Document {
+            text: text.to_string(),
+            tree: Parser::new(text).parse_spanned()?,
+        })
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn expression(&self) -> &Expression {
+        &self.tree.expression
+    }
+
+    /// The parsed formula with the span of each of its subformulas.
+    pub fn tree(&self) -> &Spanned {
+        &self.tree
+    }
+
+    /// Replaces the characters in `range`, counted from 0 as in `ParserError`, with
+    /// `replacement`, and brings the parsed formula up to date. Returns the span, in the new text,
+    /// of the subformula that was parsed again.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ParserError`, with positions in the new text, if the edited text is not a valid
+    /// formula. The document is then left as it was.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is not within the text.
+    pub fn edit(
+        &mut self,
+        range: Range<usize>,
+        replacement: &str,
+    ) -> Result<Range<usize>, ParserError> {
+        let length = self.text.chars().count();
+        assert!(
+            range.start <= range.end && range.end <= length,
+            "edit {:?} is outside the text of {} characters",
+            range,
+            length
+        );
+        let text = self
+            .text
+            .chars()
+            .take(range.start)
+            .chain(replacement.chars())
+            .chain(self.text.chars().skip(range.end))
+            .collect::<String>();
+        let edit = Edit {
+            delta: replacement.chars().count() as isize - range.len() as isize,
+            range,
+            text: &text,
+        };
+        let reparsed = match edit.apply(&mut self.tree) {
+            Some(reparsed) => reparsed,
+            None => {
+                self.tree = Parser::new(&text).parse_spanned()?;
+                0..text.chars().count()
+            }
+        };
+        self.text = text;
+        Ok(reparsed)
+    }
+}
+
+/// A change to the text of a `Document`.
+struct Edit<'a> {
+    /// The characters replaced, in the old text
+    range: Range<usize>,
+    /// How many more characters the new text has than the old
+    delta: isize,
+    /// The new text
+    text: &'a str,
+}
+
+impl Edit<'_> {
+    /// Brings `node`, whose span contains the edit, up to date by reparsing the least of it that
+    /// can be reparsed alone. Returns the span of what was reparsed, or `None`, leaving `node`
+    /// unchanged, if no part of it could be.
+    fn apply(&self, node: &mut Spanned) -> Option<Range<usize>> {
+        let child = node
+            .children
+            .iter_mut()
+            .position(|child| self.is_within(&child.span));
+        if let Some(i) = child {
+            if let Some(reparsed) = self.apply(&mut node.children[i]) {
+                for later in &mut node.children[i + 1..] {
+                    self.shift(later);
+                }
+                node.span.end = self.moved(node.span.end);
+                node.rebuild();
+                return Some(reparsed);
+            }
+        }
+        self.reparse(node)
+    }
+
+    /// Parses again the part of `node` that can be parsed alone: the inside of its parentheses,
+    /// or a sentence letter or constant that must stay one.
+    fn reparse(&self, node: &mut Spanned) -> Option<Range<usize>> {
+        let region = if node.parenthesized {
+            node.span.start + 1..node.span.end - 1
+        } else if node.children.is_empty() {
+            node.span.clone()
+        } else {
+            return None;
+        };
+        if !self.is_within(&region) {
+            return None;
+        }
+        let start = region.start;
+        let text = self
+            .text
+            .chars()
+            .skip(start)
+            .take(self.moved(region.end) - start)
+            .collect::<String>();
+        let mut parsed = Parser::new(&text).parse_spanned().ok()?;
+        // Anything more than an atom could group differently with what is around it
+        if !node.parenthesized && !parsed.children.is_empty() && !parsed.parenthesized {
+            return None;
+        }
+        offset(&mut parsed, start);
+        if node.parenthesized {
+            parsed.span = node.span.start..self.moved(node.span.end);
+            parsed.parenthesized = true;
+        }
+        *node = parsed;
+        Some(node.span.clone())
+    }
+
+    /// Whether the edit falls within `span` of the old text.
+    fn is_within(&self, span: &Range<usize>) -> bool {
+        span.start <= self.range.start && self.range.end <= span.end
+    }
+
+    /// Where `position`, at or after the edit in the old text, is in the new text.
+    fn moved(&self, position: usize) -> usize {
+        position.checked_add_signed(self.delta).unwrap()
+    }
+
+    /// Moves `node`, which comes after the edit, to its place in the new text.
+    fn shift(&self, node: &mut Spanned) {
+        node.span = self.moved(node.span.start)..self.moved(node.span.end);
+        for child in &mut node.children {
+            self.shift(child);
+        }
+    }
+}
+
+/// Moves `node`, parsed from text that starts `by` characters into the document, to its place in
+/// the document.
+fn offset(node: &mut Spanned, by: usize) {
+    node.span = node.span.start + by..node.span.end + by;
+    for child in &mut node.children {
+        offset(child, by);
+    }
+}
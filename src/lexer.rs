@@ -0,0 +1,222 @@
+use std::{iter::Peekable, str::CharIndices};
+
+use crate::{error::ParserError, expression::Symbol};
+
+/// What a token of a formula is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// A sentence letter: `A` to `Z` apart from `T` and `F`, or `a` to `z` apart from `v`,
+    /// optionally followed by a subscript, as in `P1`.
+    Atom(Symbol),
+    /// Verum, written `T` or `⊤`
+    Top,
+    /// Falsum, written `F` or `⊥`
+    Bottom,
+    /// Negation, written `-`, `~`, `!` or `¬`
+    Not,
+    /// Conjunction, written `&` or `∧`
+    And,
+    /// Disjunction, written `v`, `|` or `∨`
+    Or,
+    /// The conditional, written `>`, `->` or `→`
+    Implies,
+    /// The biconditional, written `<->`, `=` or `↔`
+    Iff,
+    /// Exclusive or, written `^`, `⊕` or `⊻`
+    Xor,
+    /// Not both, written `↑` or `⊼`
+    Nand,
+    /// Neither, written `↓` or `⊽`
+    Nor,
+    Open,
+    Close,
+    /// The name of an atom that is not a single capital, such as `p`, `rain` or `v`, when atoms
+    /// are read as names. The name is the token's text.
+    Name,
+}
+
+/// How a `Lexer` reads letters, and so whether `v` is disjunction or can be part of a name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AtomMode {
+    /// Each letter, with any digits after it, is a sentence letter and `v` is disjunction. A `v`
+    /// followed by a digit or `_`, as in `v1`, is reported as `ParserError::DisjunctionInName`.
+    #[default]
+    Letters,
+    /// Any run of letters, digits and `_` starting with a letter is the name of an atom, so `v`,
+    /// `rain` and `P1` are all atoms. `T` and `F` alone are still the constants. Disjunction must
+    /// be written `|` or `∨`, and a `v` where an operator should be is reported by the parser as
+    /// `ParserError::AtomNamedV`.
+    Names,
+}
+
+/// A token of a formula, with where it was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub kind: TokenKind,
+    /// The token as it was written
+    pub text: &'a str,
+    /// Where the token starts, counted in characters from 0, as in `ParserError`
+    pub position: usize,
+    /// Where the token starts, counted in bytes, for slicing the input
+    pub offset: usize,
+}
+
+/// Splits a formula into tokens, skipping spaces. This is the first stage of `Parser`.
+///
+/// A character that starts no token is reported as an error in place of a token, and lexing goes
+/// on after it, so every token of the input can still be seen.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::lexer::{Lexer, TokenKind};
+///
+/// let kinds = Lexer::new("-P -> Q")
+///     .map(|token| token.unwrap().kind)
+///     .collect::<Vec<_>>();
+/// assert_eq!(
+///     kinds,
+///     vec![
+///         TokenKind::Not,
+///         TokenKind::Atom('P'.into()),
+///         TokenKind::Implies,
+///         TokenKind::Atom('Q'.into()),
+///     ]
+/// );
+/// ```
+pub struct Lexer<'a> {
+    input: &'a str,
+    // How letters are read.
+    mode: AtomMode,
+    // Stream of characters from the input string, with their byte offsets.
+    chars: Peekable<CharIndices<'a>>,
+    // How many characters have been consumed, which is the position of the next one.
+    position: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Lexer {
+            input,
+            mode: AtomMode::Letters,
+            chars: input.char_indices().peekable(),
+            position: 0,
+        }
+    }
+
+    /// Makes the lexer read letters according to `mode`, which is `AtomMode::Letters` by
+    /// default.
+    pub fn with_mode(mut self, mode: AtomMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Whether the `v` at byte `offset` looks like part of a name: it is followed by a digit or
+    /// `_`, as in `v1`, which no operand can start with. Since every letter is a sentence letter
+    /// of its own, a `v` between letters, as in `pvq` or `p1vq1`, is always disjunction.
+    fn is_in_name(&self, offset: usize) -> bool {
+        self.input[offset + 1..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_digit() || c == '_')
+    }
+
+    /// The sentence letter `letter` with any digits after it read as its subscript.
+    fn atom(&mut self, letter: char) -> Result<Symbol, ParserError> {
+        let position = self.position - 1;
+        let mut digits = String::new();
+        while let Some((_, digit)) = self.chars.next_if(|(_, c)| c.is_ascii_digit()) {
+            digits.push(digit);
+            self.position += 1;
+        }
+        if digits.is_empty() {
+            return Ok(Symbol::new(letter));
+        }
+        digits
+            .parse()
+            .map(|index| Symbol::indexed(letter, index))
+            .map_err(|_| ParserError::InvalidExpression(letter, position))
+    }
+
+    /// Consumes the next character if it is `expected`.
+    fn accept(&mut self, expected: char) -> bool {
+        let accepted = self.chars.next_if(|(_, c)| *c == expected).is_some();
+        self.position += usize::from(accepted);
+        accepted
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Result<Token<'a>, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.chars.next_if(|(_, c)| *c == ' ').is_some() {
+            self.position += 1;
+        }
+        let (offset, c) = self.chars.next()?;
+        let position = self.position;
+        self.position += 1;
+        let kind = match c {
+            _ if self.mode == AtomMode::Names && c.is_ascii_alphabetic() => {
+                let mut length = 1;
+                while self
+                    .chars
+                    .next_if(|(_, c)| c.is_ascii_alphanumeric() || *c == '_')
+                    .is_some()
+                {
+                    length += 1;
+                }
+                self.position += length - 1;
+                match c {
+                    'T' if length == 1 => TokenKind::Top,
+                    'F' if length == 1 => TokenKind::Bottom,
+                    'A'..='Z' if length == 1 => TokenKind::Atom(Symbol::new(c)),
+                    _ => TokenKind::Name,
+                }
+            }
+            'v' if self.is_in_name(offset) => {
+                return Some(Err(ParserError::DisjunctionInName(position)))
+            }
+            'T' | '⊤' => TokenKind::Top,
+            'F' | '⊥' => TokenKind::Bottom,
+            'A'..='Z' | 'a'..='u' | 'w'..='z' => match self.atom(c) {
+                Ok(atom) => TokenKind::Atom(atom),
+                Err(err) => return Some(Err(err)),
+            },
+            // `->` is the only token starting with `-` apart from the negation itself
+            '-' if self.accept('>') => TokenKind::Implies,
+            '-' | '~' | '!' | '¬' => TokenKind::Not,
+            '&' | '∧' => TokenKind::And,
+            'v' | '|' | '∨' => TokenKind::Or,
+            '>' | '→' => TokenKind::Implies,
+            '=' if self.accept('>') => return Some(Err(unsupported("=>", ">", position))),
+            '=' | '↔' => TokenKind::Iff,
+            '^' | '⊕' | '⊻' => TokenKind::Xor,
+            '↑' | '⊼' => TokenKind::Nand,
+            '↓' | '⊽' => TokenKind::Nor,
+            // and `<->` the only one starting with `<`
+            '<' if self.accept('-') && self.accept('>') => TokenKind::Iff,
+            '<' if self.accept('=') && self.accept('>') => {
+                return Some(Err(unsupported("<=>", "<->", position)))
+            }
+            '<' => return Some(Err(ParserError::InvalidOperator('<', position))),
+            '+' => return Some(Err(unsupported("+", "v", position))),
+            '*' => return Some(Err(unsupported("*", "&", position))),
+            '(' => TokenKind::Open,
+            ')' => TokenKind::Close,
+            _ => return Some(Err(ParserError::InvalidExpression(c, position))),
+        };
+        let end = self.chars.peek().map_or(self.input.len(), |(end, _)| *end);
+        Some(Ok(Token {
+            kind,
+            text: &self.input[offset..end],
+            position,
+            offset,
+        }))
+    }
+}
+
+/// The error for `found`, an operator from another notation, where `suggestion` should be.
+fn unsupported(found: &str, suggestion: &'static str, position: usize) -> ParserError {
+    ParserError::UnsupportedOperator(found.to_string(), suggestion, position)
+}
@@ -1,6 +1,33 @@
+pub mod arena;
+pub mod block;
+pub mod cnf;
+pub mod enumerate;
 pub mod error;
+pub mod explain;
 pub mod expression;
+pub mod fold;
+pub mod forms;
+pub mod generate;
+pub mod incremental;
+pub mod lexer;
 pub mod lines;
+pub mod metadata;
+pub mod minimize;
+pub mod normalize;
 pub mod parser;
+/// Candidate line generation used by the search. Not part of the supported API, see `prelude`.
+#[doc(hidden)]
 pub mod possible;
+pub mod prelude;
+pub mod problems;
 pub mod proof;
+pub mod render;
+pub mod sat;
+pub mod semantics;
+pub mod skeleton;
+pub mod smtlib;
+#[cfg(feature = "test-support")]
+pub mod snapshot;
+pub mod template;
+pub mod tptp;
+pub mod trace;
@@ -1,16 +1,37 @@
-use std::fmt::{self, Display};
+use std::{
+    fmt::{self, Display},
+    time::Duration,
+};
 
 use enum_iterator::Sequence;
 
 use crate::expression::Expression;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Line {
     pub assumption_lines: Vec<usize>,
     pub line_number: usize,
     pub expression: Expression,
     pub rule: Rule,
     pub deduction_lines: Vec<usize>,
+    pub provenance: Provenance,
+    /// Free-text note shown after the line when the proof is displayed
+    pub comment: Option<String>,
+    /// Name such as `P1` or `Given` that citations can use in place of the line number
+    pub label: Option<String>,
+}
+
+/// Records how the search came to produce a line. Premises, and lines built by hand, have the
+/// default provenance.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Provenance {
+    /// Value of the proof's iteration counter (shared with sub proof searches) when the line was
+    /// produced
+    pub iteration: Option<usize>,
+    /// Time from the start of the top-level search until the line was produced
+    pub elapsed: Option<Duration>,
+    /// How many sub proof searches deep the line was found, `0` being the top-level search
+    pub depth: usize,
 }
 
 impl Line {
@@ -27,6 +48,9 @@ impl Line {
             expression,
             rule,
             deduction_lines,
+            provenance: Provenance::default(),
+            comment: None,
+            label: None,
         }
     }
 
@@ -41,7 +65,9 @@ impl PartialEq<Expression> for Line {
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Sequence)]
+/// Rules are ordered as they are declared here, which is the order in which the search tries the
+/// candidates they produce.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Sequence)]
 pub enum Rule {
     Assumption,
     ModusPonens,
@@ -55,6 +81,12 @@ pub enum Rule {
     OrElimination,
     OrEliminationAssumption,
     ReductioAdAbsurdium,
+    ReductioAdAbsurdiumAssumption,
+    BiconditionalIntroduction,
+    BiconditionalElimination,
+    VerumIntroduction,
+    FalsumIntroduction,
+    FalsumElimination,
 }
 
 impl Display for Rule {
@@ -72,6 +104,25 @@ impl Display for Rule {
             Rule::OrElimination => write!(f, "vE"),
             Rule::OrEliminationAssumption => write!(f, "A(vE)"),
             Rule::ReductioAdAbsurdium => write!(f, "RAA"),
+            Rule::ReductioAdAbsurdiumAssumption => write!(f, "A(RAA)"),
+            Rule::BiconditionalIntroduction => write!(f, "<->I"),
+            Rule::BiconditionalElimination => write!(f, "<->E"),
+            Rule::VerumIntroduction => write!(f, "⊤I"),
+            Rule::FalsumIntroduction => write!(f, "⊥I"),
+            Rule::FalsumElimination => write!(f, "⊥E"),
+        }
+    }
+}
+
+impl Rule {
+    /// The rule that discharges an assumption made with this rule, or `None` if this rule does
+    /// not open a sub proof.
+    pub fn closing_rule(&self) -> Option<Rule> {
+        match self {
+            Rule::ConditionalProofAssumption => Some(Rule::ConditionalProof),
+            Rule::OrEliminationAssumption => Some(Rule::OrElimination),
+            Rule::ReductioAdAbsurdiumAssumption => Some(Rule::ReductioAdAbsurdium),
+            _ => None,
         }
     }
 }
@@ -1,35 +1,110 @@
+use std::{fs, path::Path};
+
 use anyhow::Context;
-use propositional_logic_calculator::proof::{parse_expression, Proof, SearchSettings};
+use propositional_logic_calculator::{
+    expression::Expression,
+    proof::{parse_argument, Proof, SearchSettings},
+    semantics::{entails, Model},
+};
 
 fn main() -> anyhow::Result<()> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match args.as_slice() {
+        [] => prove(),
+        [command, path] if command == "screen" => screen(Path::new(path)),
+        _ => anyhow::bail!("Usage: propositional_logic_calculator [screen <file>]"),
+    }
+}
+
+/// Reads one argument from standard input and prints a proof of it.
+fn prove() -> anyhow::Result<()> {
     println!("Enter the propositional logic statement: ");
     let input = get_input();
     // Assumptions sepereated by commas, e.g. A,B->C,BvC,D&E
-    // Conclusion is seperated by a '/' e.g. A,B->C,BvC,D&E/A
-    let (assumptions_str, conclusion_str) = input
-        .split_once('/')
-        .context("Need a '/' to delimit assumptions and conclusion")?;
-
-    let assumptions = assumptions_str
-        .split(',')
-        .map(parse_expression)
-        .collect::<Result<_, _>>()?;
-    let conclusion = parse_expression(conclusion_str)?;
-
-    let mut proof = Proof::with_settings(
-        assumptions,
-        conclusion,
-        SearchSettings {
-            max_line_length: 20,
-            iterations: 100_000,
-        },
-    );
-
-    proof.search().context("Did not find proof")?;
+    // Conclusion is seperated by a '/', '⊢', '|-', '∴' or 'therefore' e.g. A,B->C,BvC,D&E/A
+    let (assumptions, conclusion) =
+        parse_argument(&input).map_err(|err| anyhow::anyhow!("{}\n{}", err, err.caret(&input)))?;
+
+    // There is no proof to search for when a truth table shows the argument is invalid
+    if let Some(model) = entails(&assumptions, &conclusion).countermodel() {
+        match conclusion {
+            Expression::Bottom => anyhow::bail!(
+                "The assumptions are consistent, so they cannot be refuted. Model: {}",
+                model
+            ),
+            _ => anyhow::bail!(
+                "The argument is invalid, so it has no proof. Countermodel: {}",
+                model
+            ),
+        }
+    }
+
+    // Conclusion '#' (or '⊥') asks to show the assumptions are inconsistent, e.g. A,B,-A/#
+    let settings = SearchSettings {
+        max_line_length: 20,
+        iterations: 100_000,
+        ..Default::default()
+    };
+    let mut proof = match conclusion {
+        Expression::Bottom => Proof::refutation_with_settings(assumptions, settings),
+        conclusion => Proof::with_settings(assumptions, conclusion, settings),
+    };
+
+    proof.record_near_misses(3);
+    if let Err(err) = proof.search() {
+        for failure in proof.sub_proof_failures() {
+            eprintln!("{}", failure);
+        }
+        for near_miss in proof.near_misses() {
+            eprintln!("{}", near_miss);
+        }
+        return Err(err).context("Did not find proof");
+    }
     println!("{}", proof);
     Ok(())
 }
 
+/// Sorts the arguments in the file at `path`, one per line, into valid and invalid ones with
+/// truth tables alone, printing a countermodel for each invalid one and whether the conclusion of
+/// each argument is a tautology, a contradiction or contingent. Blank lines and lines starting with
+/// `#` are skipped, and lines that do not parse are reported and skipped. Search
+/// settings after a `;`, as in a problem file, are ignored since nothing is searched for.
+fn screen(path: &Path) -> anyhow::Result<()> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("Could not read {}", path.display()))?;
+    let mut valid: Vec<(&str, String)> = Vec::new();
+    let mut invalid: Vec<(&str, String, Model)> = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let argument = line.split_once(';').map_or(line, |(argument, _)| argument);
+        let (premises, conclusion) = match parse_argument(argument) {
+            Ok(argument) => argument,
+            Err(err) => {
+                eprintln!("Line {}: {}\n{}", number + 1, err, err.caret(argument));
+                continue;
+            }
+        };
+        // Each argument is labelled with what its conclusion is on its own
+        let label = format!("  (conclusion {})", conclusion.classify());
+        match entails(&premises, &conclusion).countermodel() {
+            None => valid.push((line, label)),
+            Some(model) => invalid.push((line, label, model.clone())),
+        }
+    }
+    println!("Valid ({}):", valid.len());
+    for (line, label) in valid {
+        println!("  {}{}", line, label);
+    }
+    println!("Invalid ({}):", invalid.len());
+    for (line, label, model) in invalid {
+        println!("  {}{}  countermodel: {}", line, label, model);
+    }
+    Ok(())
+}
+
 fn get_input() -> String {
     let mut input = String::new();
     std::io::stdin().read_line(&mut input).unwrap();
@@ -0,0 +1,139 @@
+use crate::{proof::SearchSettings, trace::Trace};
+
+/// Details recorded with an exported proof, so that a graded proof can be traced back to whoever
+/// made it and the configuration that found or checked it.
+///
+/// Set it on `NotationConfig::metadata` (or with `Proof::set_metadata`) and the LaTeX export
+/// writes it as `%` comments before the table, the HTML export as `data-` attributes of the
+/// `<table>`, and the Markdown export as HTML comments before the table. `Metadata::read` gets it
+/// back from any of them.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{
+///     metadata::Metadata,
+///     proof::{parse_expression, Proof},
+///     render::{Latex, ProofRenderer},
+/// };
+///
+/// let mut proof = Proof::new(vec![parse_expression("P&Q").unwrap()], parse_expression("Q").unwrap());
+/// proof.search().unwrap();
+/// let metadata = Metadata {
+///     author: Some("A. Student".to_string()),
+///     ..Metadata::for_settings(proof.settings())
+/// };
+/// proof.set_metadata(Some(metadata.clone()));
+/// let latex = Latex.render(&proof, proof.notation());
+/// assert!(latex.starts_with("% author: A. Student\n"));
+/// assert_eq!(Metadata::read(&latex), Some(metadata));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metadata {
+    pub author: Option<String>,
+    pub course: Option<String>,
+    /// The date, in whatever form the course uses
+    pub date: Option<String>,
+    /// The version of this crate that produced or checked the proof
+    pub engine_version: Option<String>,
+    /// `settings_hash` of the settings the proof was searched with
+    pub settings_hash: Option<u64>,
+}
+
+impl Metadata {
+    /// Metadata naming this version of the crate and the hash of `settings`, to which the
+    /// author, course and date can be added.
+    pub fn for_settings(settings: &SearchSettings) -> Self {
+        Metadata {
+            engine_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            settings_hash: Some(settings_hash(settings)),
+            ..Default::default()
+        }
+    }
+
+    /// The fields that are set, by the names they are exported under. Line breaks in values are
+    /// replaced with spaces so that each field stays on one line.
+    pub(crate) fn fields(&self) -> Vec<(&'static str, String)> {
+        let text = [
+            ("author", &self.author),
+            ("course", &self.course),
+            ("date", &self.date),
+            ("engine-version", &self.engine_version),
+        ];
+        let mut fields = text
+            .into_iter()
+            .filter_map(|(name, value)| Some((name, value.as_ref()?.replace(['\r', '\n'], " "))))
+            .collect::<Vec<_>>();
+        if let Some(hash) = self.settings_hash {
+            fields.push(("settings-hash", format!("{:016x}", hash)));
+        }
+        fields
+    }
+
+    /// Sets the field exported as `name` to `value`. Unknown names are ignored, so exports from
+    /// later versions with more fields can still be read.
+    fn set(&mut self, name: &str, value: String) {
+        match name {
+            "author" => self.author = Some(value),
+            "course" => self.course = Some(value),
+            "date" => self.date = Some(value),
+            "engine-version" => self.engine_version = Some(value),
+            "settings-hash" => self.settings_hash = u64::from_str_radix(&value, 16).ok(),
+            _ => {}
+        }
+    }
+
+    /// Reads the metadata back from a proof exported as LaTeX, HTML or Markdown. Returns `None`
+    /// if the export has none.
+    pub fn read(exported: &str) -> Option<Metadata> {
+        let mut metadata = Metadata::default();
+        if let Some(table) = exported.find("<table") {
+            let tag = &exported[table..table + exported[table..].find('>')?];
+            let mut rest = tag;
+            while let Some(start) = rest.find(" data-") {
+                rest = &rest[start + " data-".len()..];
+                let (name, value) = rest.split_once("=\"")?;
+                let (value, after) = value.split_once('"')?;
+                metadata.set(name, unescape_attribute(value));
+                rest = after;
+            }
+        }
+        for line in exported.lines() {
+            let field = line
+                .strip_prefix("% ")
+                .or_else(|| line.strip_prefix("<!-- ")?.strip_suffix(" -->"));
+            match field.and_then(|field| field.split_once(": ")) {
+                Some((name, value)) => metadata.set(name, value.to_string()),
+                None => break,
+            }
+        }
+        (metadata != Metadata::default()).then_some(metadata)
+    }
+}
+
+/// A hash of `settings` that stays the same between runs, platforms and versions of Rust, so that
+/// it can be compared with one recorded in an old export. It is the 64-bit FNV-1a hash of the
+/// settings line of a `Trace`.
+pub fn settings_hash(settings: &SearchSettings) -> u64 {
+    let text = Trace::new(settings.clone()).to_string();
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Escapes `value` for a double-quoted HTML attribute.
+pub(crate) fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn unescape_attribute(value: &str) -> String {
+    value
+        .replace("&gt;", ">")
+        .replace("&lt;", "<")
+        .replace("&quot;", "\"")
+        .replace("&amp;", "&")
+}
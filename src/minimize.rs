@@ -0,0 +1,194 @@
+use std::cmp::Reverse;
+
+use crate::{
+    expression::{Expression, Symbol},
+    semantics::{all_models, collect_atoms},
+};
+
+/// Formulas with more sentence letters than this are too large to minimize, since every row of
+/// their truth table is looked at.
+pub const MAX_MINIMIZED_ATOMS: usize = 16;
+
+/// A product of literals: the letters whose bit is set in `mask` are left out, and each other
+/// letter is negated if its bit in `bits` is clear. Bit `i` is the `i`th letter, counting from
+/// the last.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Implicant {
+    bits: u32,
+    mask: u32,
+}
+
+impl Implicant {
+    fn covers(self, minterm: u32) -> bool {
+        minterm & !self.mask == self.bits
+    }
+
+    fn literals(self, atoms: usize) -> u32 {
+        atoms as u32 - self.mask.count_ones()
+    }
+}
+
+/// Finds a smallest sum of products equivalent to `expression`, as described on
+/// `Expression::minimize`.
+pub(crate) fn minimize(expression: &Expression) -> Expression {
+    let atoms = collect_atoms([expression]);
+    assert!(
+        atoms.len() <= MAX_MINIMIZED_ATOMS,
+        "too many sentence letters to minimize"
+    );
+    // Rows are numbered with the first letter as the most significant bit and true as 1
+    let rows = 1u32 << atoms.len();
+    let minterms = all_models(&atoms)
+        .enumerate()
+        .filter(|(_, model)| model.satisfies(expression))
+        .map(|(row, _)| rows - 1 - row as u32)
+        .collect::<Vec<_>>();
+    if minterms.is_empty() {
+        return Expression::Bottom;
+    }
+    if minterms.len() == rows as usize {
+        return Expression::Top;
+    }
+    let primes = prime_implicants(&minterms);
+    let mut cover = smallest_cover(&primes, &minterms, atoms.len());
+    // Shortest first, then those with earlier letters, then those with more of them positive
+    cover.sort_by_key(|implicant| {
+        (
+            implicant.literals(atoms.len()),
+            Reverse(!implicant.mask & (rows - 1)),
+            Reverse(implicant.bits),
+        )
+    });
+    cover
+        .into_iter()
+        .map(|implicant| product(implicant, &atoms))
+        .reduce(|left, right| Expression::Or(left.wrap(), right.wrap()))
+        .unwrap()
+}
+
+/// The implicants that cannot be widened, found by repeatedly merging pairs that differ in one
+/// letter, starting from the minterms.
+fn prime_implicants(minterms: &[u32]) -> Vec<Implicant> {
+    let mut current = minterms
+        .iter()
+        .map(|&bits| Implicant { bits, mask: 0 })
+        .collect::<Vec<_>>();
+    let mut primes = Vec::new();
+    while !current.is_empty() {
+        let mut merged = vec![false; current.len()];
+        let mut next = Vec::new();
+        for i in 0..current.len() {
+            for j in i + 1..current.len() {
+                let (a, b) = (current[i], current[j]);
+                let difference = a.bits ^ b.bits;
+                if a.mask == b.mask && difference.count_ones() == 1 {
+                    merged[i] = true;
+                    merged[j] = true;
+                    next.push(Implicant {
+                        bits: a.bits & b.bits,
+                        mask: a.mask | difference,
+                    });
+                }
+            }
+        }
+        primes.extend(
+            current
+                .iter()
+                .zip(&merged)
+                .filter(|(_, merged)| !**merged)
+                .map(|(implicant, _)| *implicant),
+        );
+        next.sort();
+        next.dedup();
+        current = next;
+    }
+    primes
+}
+
+/// The fewest prime implicants that cover every minterm, with the fewest literals among covers
+/// of that size. Implicants that are the only cover of some minterm are taken first, and the rest
+/// of the cover is searched for exhaustively.
+fn smallest_cover(primes: &[Implicant], minterms: &[u32], atoms: usize) -> Vec<Implicant> {
+    let mut chosen = Vec::new();
+    let mut uncovered = minterms.to_vec();
+    loop {
+        let essential = uncovered.iter().find_map(|&minterm| {
+            let mut covering = primes.iter().filter(|prime| prime.covers(minterm));
+            match (covering.next(), covering.next()) {
+                (Some(prime), None) => Some(*prime),
+                _ => None,
+            }
+        });
+        let Some(essential) = essential else {
+            break;
+        };
+        chosen.push(essential);
+        uncovered.retain(|&minterm| !essential.covers(minterm));
+    }
+    let mut best = None;
+    search_cover(primes, &uncovered, &mut Vec::new(), &mut best, atoms);
+    chosen.extend(best.unwrap_or_default());
+    chosen
+}
+
+/// Extends `partial` to covers of `uncovered`, keeping in `best` the smallest found.
+fn search_cover(
+    primes: &[Implicant],
+    uncovered: &[u32],
+    partial: &mut Vec<Implicant>,
+    best: &mut Option<Vec<Implicant>>,
+    atoms: usize,
+) {
+    let cost = |cover: &[Implicant]| {
+        (
+            cover.len(),
+            cover
+                .iter()
+                .map(|implicant| implicant.literals(atoms))
+                .sum::<u32>(),
+        )
+    };
+    if let Some(best) = best {
+        if partial.len() >= best.len() && (!uncovered.is_empty() || cost(partial) >= cost(best)) {
+            return;
+        }
+    }
+    // The minterm with the fewest implicants covering it decides what to try
+    let Some(&minterm) = uncovered
+        .iter()
+        .min_by_key(|&&minterm| primes.iter().filter(|prime| prime.covers(minterm)).count())
+    else {
+        *best = Some(partial.clone());
+        return;
+    };
+    for prime in primes.iter().filter(|prime| prime.covers(minterm)) {
+        let rest = uncovered
+            .iter()
+            .copied()
+            .filter(|&minterm| !prime.covers(minterm))
+            .collect::<Vec<_>>();
+        partial.push(*prime);
+        search_cover(primes, &rest, partial, best, atoms);
+        partial.pop();
+    }
+}
+
+/// The conjunction of the literals of `implicant`, in the order of `atoms`.
+fn product(implicant: Implicant, atoms: &[Symbol]) -> Expression {
+    atoms
+        .iter()
+        .enumerate()
+        .filter_map(|(i, var)| {
+            let bit = 1 << (atoms.len() - 1 - i);
+            if implicant.mask & bit != 0 {
+                return None;
+            }
+            let var = Expression::Var(*var);
+            Some(match implicant.bits & bit {
+                0 => Expression::Not(var.wrap()),
+                _ => var,
+            })
+        })
+        .reduce(|left, right| Expression::And(left.wrap(), right.wrap()))
+        .unwrap()
+}
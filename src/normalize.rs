@@ -0,0 +1,205 @@
+use crate::expression::Expression;
+
+/// Rewrites that bring a formula into a canonical form, so that formulas which differ only in
+/// these ways compare equal. Set on a `Parser` with `Parser::normalizing` to apply them to
+/// everything it parses.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{normalize::Normalization, parser::Parser};
+///
+/// let a = Parser::new("--(Q&(R&P))").normalizing(Normalization::FULL).parse().unwrap();
+/// let b = Parser::new("(P&Q)&R").normalizing(Normalization::FULL).parse().unwrap();
+/// assert_eq!(a, b);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Normalization {
+    /// Replace `--A` with `A`.
+    pub double_negation: bool,
+    /// Regroup chains of `&` and of `v` to the left, as in `(A&B)&C`, however they were bracketed.
+    pub flatten: bool,
+    /// Put the operands of `&`, `v` and `<->` in a fixed order. Within a flattened chain, all the
+    /// operands of the chain are put in order.
+    pub sort_operands: bool,
+}
+
+impl Normalization {
+    /// Leaves formulas exactly as they were written.
+    pub const NONE: Normalization = Normalization {
+        double_negation: false,
+        flatten: false,
+        sort_operands: false,
+    };
+
+    /// Applies every rewrite.
+    pub const FULL: Normalization = Normalization {
+        double_negation: true,
+        flatten: true,
+        sort_operands: true,
+    };
+
+    /// Returns `expression` with the selected rewrites applied throughout.
+    pub fn apply(&self, expression: &Expression) -> Expression {
+        match expression {
+            Expression::Not(inner) => {
+                let inner = self.apply(inner);
+                match inner {
+                    Expression::Not(doubled) if self.double_negation => doubled.as_ref().clone(),
+                    inner => Expression::Not(inner.wrap()),
+                }
+            }
+            Expression::And(..) | Expression::Or(..) if self.flatten => {
+                let mut operands = Vec::new();
+                self.collect_chain(expression, expression, &mut operands);
+                if self.sort_operands {
+                    sort(&mut operands);
+                }
+                let rebuild = |left: Expression, right: Expression| match expression {
+                    Expression::And(..) => Expression::And(left.wrap(), right.wrap()),
+                    _ => Expression::Or(left.wrap(), right.wrap()),
+                };
+                let mut operands = operands.into_iter();
+                let first = operands.next().unwrap();
+                operands.fold(first, rebuild)
+            }
+            Expression::And(left, right) => {
+                let (left, right) = self.apply_pair(left, right, true);
+                Expression::And(left.wrap(), right.wrap())
+            }
+            Expression::Or(left, right) => {
+                let (left, right) = self.apply_pair(left, right, true);
+                Expression::Or(left.wrap(), right.wrap())
+            }
+            Expression::Iff(left, right) => {
+                let (left, right) = self.apply_pair(left, right, true);
+                Expression::Iff(left.wrap(), right.wrap())
+            }
+            Expression::Implies(left, right) => {
+                let (left, right) = self.apply_pair(left, right, false);
+                Expression::Implies(left.wrap(), right.wrap())
+            }
+            Expression::Var(_) | Expression::Top | Expression::Bottom => expression.clone(),
+        }
+    }
+
+    /// Normalizes both operands, putting them in order if the operator is `commutative`.
+    fn apply_pair(
+        &self,
+        left: &Expression,
+        right: &Expression,
+        commutative: bool,
+    ) -> (Expression, Expression) {
+        let mut pair = [self.apply(left), self.apply(right)];
+        if commutative && self.sort_operands {
+            sort(&mut pair);
+        }
+        let [left, right] = pair;
+        (left, right)
+    }
+
+    /// Collects the normalized operands of the chain of the same connective as `chain` that
+    /// `expression` is part of. An operand that normalizes to the same connective, such as
+    /// `--(A&B)`, joins the chain.
+    fn collect_chain(
+        &self,
+        chain: &Expression,
+        expression: &Expression,
+        operands: &mut Vec<Expression>,
+    ) {
+        match (chain, expression) {
+            (Expression::And(..), Expression::And(left, right))
+            | (Expression::Or(..), Expression::Or(left, right)) => {
+                self.collect_chain(chain, left, operands);
+                self.collect_chain(chain, right, operands);
+            }
+            _ => {
+                let operand = self.apply(expression);
+                let joins = matches!(
+                    (chain, &operand),
+                    (Expression::And(..), Expression::And(..))
+                        | (Expression::Or(..), Expression::Or(..))
+                );
+                if joins {
+                    self.collect_chain(chain, &operand, operands);
+                } else {
+                    operands.push(operand);
+                }
+            }
+        }
+    }
+}
+
+/// Simplifies `expression` as described on `Expression::simplify`, rewriting until nothing changes.
+pub(crate) fn simplify(expression: &Expression) -> Expression {
+    let mut current = simplify_once(expression);
+    loop {
+        let next = simplify_once(&current);
+        if next == current {
+            return current;
+        }
+        current = next;
+    }
+}
+
+/// Simplifies the operands of `expression`, then `expression` itself.
+fn simplify_once(expression: &Expression) -> Expression {
+    use Expression::{Bottom, Top};
+    match expression {
+        Expression::Not(inner) => negate(simplify_once(inner)),
+        Expression::And(left, right) => match (simplify_once(left), simplify_once(right)) {
+            (Bottom, _) | (_, Bottom) => Bottom,
+            (Top, x) | (x, Top) => x,
+            (left, right) if left == right => left,
+            (left, right) if absorbs(&left, &right, false) => left,
+            (left, right) if absorbs(&right, &left, false) => right,
+            (left, right) => Expression::And(left.wrap(), right.wrap()),
+        },
+        Expression::Or(left, right) => match (simplify_once(left), simplify_once(right)) {
+            (Top, _) | (_, Top) => Top,
+            (Bottom, x) | (x, Bottom) => x,
+            (left, right) if left == right => left,
+            (left, right) if absorbs(&left, &right, true) => left,
+            (left, right) if absorbs(&right, &left, true) => right,
+            (left, right) => Expression::Or(left.wrap(), right.wrap()),
+        },
+        Expression::Implies(left, right) => match (simplify_once(left), simplify_once(right)) {
+            (Bottom, _) | (_, Top) => Top,
+            (Top, x) => x,
+            (x, Bottom) => negate(x),
+            (left, right) => Expression::Implies(left.wrap(), right.wrap()),
+        },
+        Expression::Iff(left, right) => match (simplify_once(left), simplify_once(right)) {
+            (Top, x) | (x, Top) => x,
+            (Bottom, x) | (x, Bottom) => negate(x),
+            (left, right) => Expression::Iff(left.wrap(), right.wrap()),
+        },
+        Expression::Var(_) | Top | Bottom => expression.clone(),
+    }
+}
+
+/// The negation of `expression`, with double negations and negated constants removed.
+fn negate(expression: Expression) -> Expression {
+    match expression {
+        Expression::Not(inner) => inner.as_ref().clone(),
+        Expression::Top => Expression::Bottom,
+        Expression::Bottom => Expression::Top,
+        expression => Expression::Not(expression.wrap()),
+    }
+}
+
+/// Whether `other` is a conjunction (or, with `in_or`, a disjunction) with `absorber` as an
+/// operand, so that `absorber v other` (or `absorber & other`) is just `absorber`.
+fn absorbs(absorber: &Expression, other: &Expression, in_or: bool) -> bool {
+    match (other, in_or) {
+        (Expression::And(left, right), true) | (Expression::Or(left, right), false) => {
+            **left == *absorber || **right == *absorber
+        }
+        _ => false,
+    }
+}
+
+/// Puts `operands` in a fixed order, that of their written form.
+fn sort(operands: &mut [Expression]) {
+    operands.sort_by_cached_key(|operand| operand.to_string());
+}
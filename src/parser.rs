@@ -1,12 +1,176 @@
-use std::{iter::Peekable, str::Chars};
+use std::{iter::Peekable, ops::Range};
 
-use crate::{error::ParserError, expression::Expression};
+use crate::{
+    arena::{ExprArena, ExprId, Node},
+    error::ParserError,
+    expression::{Expression, Symbol},
+    generate::atom_names,
+    lexer::{AtomMode, Lexer, Token, TokenKind},
+    normalize::Normalization,
+};
 
 /// The `Parser` struct is responsible for parsing logical expressions represented as strings into an abstract syntax tree (AST).
-/// It works with basic logical operators and handles nested expressions.
+/// It is a precedence climbing (Pratt) parser over the tokens of a `Lexer`: each binary operator
+/// has a binding power that decides how tightly it holds its operands.
 pub struct Parser<'a> {
-    // Stream of characters from the input string to be parsed.
-    chars: Peekable<Chars<'a>>,
+    // The input string being parsed.
+    input: &'a str,
+    // Stream of tokens from the input string to be parsed.
+    tokens: Peekable<Lexer<'a>>,
+    // How many characters the input has, which is the position of its end.
+    length: usize,
+    // How many opening parentheses are waiting for their closing one.
+    depth: usize,
+    // How deeply the subformula being parsed is nested, counted against `options.max_depth`.
+    nesting: usize,
+    // Rewrites applied to each parsed expression.
+    normalization: Normalization,
+    // How much the input may leave to precedence.
+    options: ParserOptions,
+    // The operator at the top of the last expression parsed without parentheses, if any.
+    top_operator: Option<BinaryOperator>,
+    // Each atom name read so far with the sentence letter that stands for it.
+    names: Vec<(String, Symbol)>,
+}
+
+/// How forgiving a `Parser` is about the way an expression is written. Set with
+/// `Parser::with_options`.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::parser::{Parser, ParserOptions};
+///
+/// assert!(Parser::new("A&BvC").parse().is_ok());
+/// assert!(Parser::new("A&BvC").with_options(ParserOptions::STRICT).parse().is_err());
+/// assert!(Parser::new("(A&B)vC").with_options(ParserOptions::STRICT).parse().is_ok());
+///
+/// let options = ParserOptions { max_depth: 2, ..ParserOptions::LENIENT };
+/// assert!(Parser::new("(-A)vB").with_options(options).parse().is_ok());
+/// assert!(Parser::new("(--A)vB").with_options(options).parse().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParserOptions {
+    /// Reject expressions that rely on precedence or associativity to group different binary
+    /// connectives, as in `A&BvC`, or conditionals, as in `A>B>C`. Chains of `&`, of `v` and of
+    /// `<->`, which mean the same however they are grouped, are still accepted.
+    pub strict: bool,
+    /// How letters are read. With `AtomMode::Names`, each name that is not a single capital is
+    /// given a sentence letter the input does not use otherwise, and `Parser::names` tells which.
+    pub atoms: AtomMode,
+    /// How deeply subformulas may be nested, through parentheses, negations or the right
+    /// operands of binary operators, before parsing stops with `ParserError::TooDeeplyNested`.
+    /// Parsing recurses once for each level, so this keeps adversarial input from overflowing
+    /// the stack.
+    pub max_depth: usize,
+}
+
+impl ParserOptions {
+    pub const DEFAULT_MAX_DEPTH: usize = 256;
+
+    /// Groups unbracketed connectives by precedence, as described on `Parser::parse`.
+    pub const LENIENT: ParserOptions = ParserOptions {
+        strict: false,
+        atoms: AtomMode::Letters,
+        max_depth: Self::DEFAULT_MAX_DEPTH,
+    };
+
+    /// Requires parentheses wherever the grouping would otherwise come from precedence.
+    pub const STRICT: ParserOptions = ParserOptions {
+        strict: true,
+        atoms: AtomMode::Letters,
+        max_depth: Self::DEFAULT_MAX_DEPTH,
+    };
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        Self::LENIENT
+    }
+}
+
+/// The binary connectives, from the loosest binding to the tightest. Exclusive or, NAND and NOR
+/// are not connectives of `Expression`, and are rewritten into the ones that are as they are
+/// parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinaryOperator {
+    Iff,
+    Implies,
+    Or,
+    Xor,
+    Nor,
+    And,
+    Nand,
+}
+
+impl BinaryOperator {
+    /// The operator a token of `kind` stands for, if it is one.
+    fn from_kind(kind: TokenKind) -> Option<Self> {
+        match kind {
+            TokenKind::Iff => Some(BinaryOperator::Iff),
+            TokenKind::Implies => Some(BinaryOperator::Implies),
+            TokenKind::Or => Some(BinaryOperator::Or),
+            TokenKind::And => Some(BinaryOperator::And),
+            TokenKind::Xor => Some(BinaryOperator::Xor),
+            TokenKind::Nand => Some(BinaryOperator::Nand),
+            TokenKind::Nor => Some(BinaryOperator::Nor),
+            _ => None,
+        }
+    }
+
+    /// How strongly the operator binds the operand on its left and the one on its right. An
+    /// operator is right-associative when its right power is the lower of the two.
+    fn binding_power(self) -> (u8, u8) {
+        match self {
+            BinaryOperator::Iff => (2, 1),
+            BinaryOperator::Implies => (4, 3),
+            BinaryOperator::Or | BinaryOperator::Xor | BinaryOperator::Nor => (5, 6),
+            BinaryOperator::And | BinaryOperator::Nand => (7, 8),
+        }
+    }
+
+    /// Whether a chain of the operator means the same however it is grouped.
+    fn is_associative(self) -> bool {
+        matches!(
+            self,
+            BinaryOperator::Iff | BinaryOperator::Or | BinaryOperator::Xor | BinaryOperator::And
+        )
+    }
+
+    /// Adds the expression the operator makes of `left` and `right` to `arena`.
+    fn intern(self, arena: &mut ExprArena, left: ExprId, right: ExprId) -> ExprId {
+        match self {
+            BinaryOperator::Iff => arena.insert(Node::Iff(left, right)),
+            BinaryOperator::Implies => arena.insert(Node::Implies(left, right)),
+            BinaryOperator::Or => arena.insert(Node::Or(left, right)),
+            BinaryOperator::And => arena.insert(Node::And(left, right)),
+            BinaryOperator::Xor => {
+                let iff = arena.insert(Node::Iff(left, right));
+                arena.insert(Node::Not(iff))
+            }
+            BinaryOperator::Nand => {
+                let and = arena.insert(Node::And(left, right));
+                arena.insert(Node::Not(and))
+            }
+            BinaryOperator::Nor => {
+                let or = arena.insert(Node::Or(left, right));
+                arena.insert(Node::Not(or))
+            }
+        }
+    }
+
+    fn apply(self, left: Expression, right: Expression) -> Expression {
+        let (left, right) = (left.wrap(), right.wrap());
+        match self {
+            BinaryOperator::Iff => Expression::Iff(left, right),
+            BinaryOperator::Implies => Expression::Implies(left, right),
+            BinaryOperator::Or => Expression::Or(left, right),
+            BinaryOperator::And => Expression::And(left, right),
+            BinaryOperator::Xor => Expression::Not(Expression::Iff(left, right).wrap()),
+            BinaryOperator::Nand => Expression::Not(Expression::And(left, right).wrap()),
+            BinaryOperator::Nor => Expression::Not(Expression::Or(left, right).wrap()),
+        }
+    }
 }
 
 impl<'a> Parser<'a> {
@@ -17,16 +181,83 @@ impl<'a> Parser<'a> {
     /// * `input`: A string slice representing the logical expression to be parsed.
     pub fn new(input: &'a str) -> Self {
         Parser {
-            chars: input.chars().peekable(),
+            input,
+            tokens: Lexer::new(input).peekable(),
+            length: input.chars().count(),
+            depth: 0,
+            nesting: 0,
+            normalization: Normalization::NONE,
+            options: ParserOptions::LENIENT,
+            top_operator: None,
+            names: Vec::new(),
         }
     }
 
+    /// Makes `parse` follow `options`, which are lenient by default.
+    pub fn with_options(mut self, options: ParserOptions) -> Self {
+        self.options = options;
+        self.tokens = Lexer::new(self.input).with_mode(options.atoms).peekable();
+        self
+    }
+
+    /// The atom names read so far, when atoms are read as names, each with the sentence letter
+    /// that stands for it in the parsed `Expression`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use propositional_logic_calculator::{
+    ///     lexer::AtomMode,
+    ///     parser::{Parser, ParserOptions},
+    ///     proof::parse_expression,
+    /// };
+    ///
+    /// let options = ParserOptions { atoms: AtomMode::Names, ..ParserOptions::LENIENT };
+    /// let mut parser = Parser::new("rain | v -> P").with_options(options);
+    /// assert_eq!(parser.parse().unwrap(), parse_expression("Q v R > P").unwrap());
+    /// assert_eq!(
+    ///     parser.names(),
+    ///     [("rain".to_string(), 'Q'.into()), ("v".to_string(), 'R'.into())]
+    /// );
+    /// ```
+    pub fn names(&self) -> &[(String, Symbol)] {
+        &self.names
+    }
+
+    /// Makes `parse` bring what it parses into canonical form with `normalization`. The text as
+    /// written stays available from `source`, for display.
+    pub fn normalizing(mut self, normalization: Normalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    /// The input exactly as it was given, before any normalization.
+    pub fn source(&self) -> &'a str {
+        self.input
+    }
+
     /// Parses a logical expression into an `Expression` enum.
     ///
     /// The function processes a string slice representing a logical expression
     /// and constructs a corresponding abstract syntax tree (AST) represented by the `Expression` enum.
-    /// It supports basic logical operators such as AND ('&'), OR ('|' or 'v'), IMPLIES ('>'), and NOT ('-').
-    /// The function handles nested expressions and respects the standard precedence of logical operators.
+    /// It supports basic logical operators such as AND ('&'), OR ('|' or 'v'), IMPLIES ('>' or '->'),
+    /// IF AND ONLY IF ('<->' or '='), and NOT ('-', '~' or '!'), and the constants verum ('T' or '⊤') and
+    /// falsum ('F' or '⊥').
+    ///
+    /// # Precedence
+    ///
+    /// From the tightest binding to the loosest: NOT, AND, OR, IMPLIES, IF AND ONLY IF. AND and OR
+    /// group to the left, IMPLIES and IF AND ONLY IF to the right, so `A&BvC` is `(A&B)vC` and
+    /// `A>B>C` is `A>(B>C)`. NAND binds like AND, and XOR and NOR like OR. Parentheses override
+    /// this as usual.
+    ///
+    /// ```
+    /// use propositional_logic_calculator::proof::parse_expression;
+    ///
+    /// assert_eq!(parse_expression("A&BvC").unwrap(), parse_expression("(A&B)vC").unwrap());
+    /// assert_eq!(parse_expression("-A>BvC").unwrap(), parse_expression("(-A)>(BvC)").unwrap());
+    /// assert_eq!(parse_expression("A>B>C").unwrap(), parse_expression("A>(B>C)").unwrap());
+    /// ```
     ///
     /// # Arguments
     ///
@@ -34,9 +265,9 @@ impl<'a> Parser<'a> {
     ///
     /// # Returns
     ///
-    /// This function returns a `Result<Expression, String>`. On successful parsing, it returns `Ok(Expression)`,
+    /// This function returns a `Result<Expression, ParserError>`. On successful parsing, it returns `Ok(Expression)`,
     /// where `Expression` is the root of the constructed AST. On failure (due to syntax errors, invalid characters,
-    /// unmatched parentheses, etc.), it returns an `Err(String)` with a descriptive error message.
+    /// unmatched parentheses, etc.), it returns an `Err(ParserError)` describing the problem.
     ///
     /// # Errors
     ///
@@ -48,167 +279,697 @@ impl<'a> Parser<'a> {
     /// # Note
     ///
     /// The parser assumes that the input expression is a well-formed logical expression
-    /// composed of uppercase alphabetic characters (A-Z) for variables, and the symbols
-    /// '&', '|', 'v', '>', and '-' for logical operators. Spaces in the input are ignored.
+    /// composed of letters (A-Z apart from T and F, and a-z apart from v) for variables, each
+    /// optionally followed by a subscript as in 'P1', the
+    /// symbols '&', '|', 'v', '>', '->', '<->', '=', '-', '~' and '!' for logical operators, and 'T', 'F',
+    /// '⊤' and '⊥' for constants. The Unicode connectives '∧', '∨', '→', '↔' and '¬' are accepted as
+    /// aliases of the ASCII operators. Spaces in the input are ignored.
+    ///
+    /// Exclusive or ('^', '⊕' or '⊻'), NAND ('↑' or '⊼') and NOR ('↓' or '⊽') are also accepted, for
+    /// formulas taken from circuits. They are rewritten as they are parsed, so `A^B` gives
+    /// `~(A <-> B)`, `A↑B` gives `~(A & B)` and `A↓B` gives `~(A v B)`.
     pub fn parse(&mut self) -> Result<Expression, ParserError> {
-        let mut stack = Vec::new();
-
-        while let Some(c) = self.chars.next() {
-            match c {
-                '(' => self.handle_parenthesis(&mut stack)?,
-                'A'..='Z' => self.handle_variable(&mut stack, c)?,
-                '-' => self.handle_negation(&mut stack)?,
-                '&' | 'v' | '>' | '|' => self.handle_binary_operator(&mut stack, c)?,
-                ' ' => (),
-                _ => {
-                    return Err(ParserError::InvalidExpression(c));
+        let mut arena = ExprArena::new();
+        let id = self.parse_root(&mut arena)?.id;
+        let expression = Expression::clone(&arena.shared(id));
+        if self.normalization == Normalization::NONE {
+            return Ok(expression);
+        }
+        Ok(self.normalization.apply(&expression))
+    }
+
+    /// Parses the input like `parse`, also recording where each subformula is written. The result
+    /// is not normalized, since normalizing would move subformulas away from their text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use propositional_logic_calculator::parser::Parser;
+    ///
+    /// let spanned = Parser::new("-P & (Q v R)").parse_spanned().unwrap();
+    /// assert_eq!(spanned.span, 0..12);
+    /// assert_eq!(spanned.children[0].span, 0..2);
+    /// assert_eq!(spanned.children[1].span, 5..12);
+    /// assert!(spanned.children[1].parenthesized);
+    /// ```
+    pub fn parse_spanned(&mut self) -> Result<Spanned, ParserError> {
+        let mut arena = ExprArena::new();
+        let parsed = self.parse_root(&mut arena)?;
+        Ok(parsed.spanned(&mut arena))
+    }
+
+    /// Parses the whole input into `arena`, without normalizing it.
+    fn parse_root(&mut self, arena: &mut ExprArena) -> Result<Parsed, ParserError> {
+        let parsed = self.parse_expression(arena, 0)?;
+        match self.peek()? {
+            None => Ok(parsed),
+            // Anything left over is either a stray `)` or an operand missing its operator
+            Some(token) => Err(unexpected(token)),
+        }
+    }
+
+    /// Parses the input like `parse`, but when it is not a valid formula, goes on past each
+    /// problem to report every one it can find, in the order they occur, so that they can all be
+    /// shown at once. The problems found are characters that start no token, operators missing
+    /// an operand, operands missing an operator, and unmatched parentheses.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use propositional_logic_calculator::{error::ParserError, parser::Parser};
+    ///
+    /// let errors = Parser::new("(P & $) v").parse_recovering().unwrap_err();
+    /// assert!(matches!(
+    ///     errors.as_slice(),
+    ///     [
+    ///         ParserError::InvalidExpression('$', 5),
+    ///         ParserError::MissingRightOperand('&', 3),
+    ///         ParserError::MissingRightOperand('v', 8),
+    ///     ]
+    /// ));
+    /// ```
+    pub fn parse_recovering(&mut self) -> Result<Expression, Vec<ParserError>> {
+        let diagnostics = self.diagnose();
+        if !diagnostics.is_empty() {
+            return Err(diagnostics);
+        }
+        // Anything the scan cannot see, such as ambiguous grouping in strict parsing
+        self.parse().map_err(|err| vec![err])
+    }
+
+    /// Scans the whole input for problems, without building an expression. After each problem
+    /// the scan carries on as if the missing piece were there.
+    fn diagnose(&self) -> Vec<ParserError> {
+        let mut diagnostics = Vec::new();
+        let mut opens: Vec<Token> = Vec::new();
+        // The last token read, and whether an operand should come next
+        let mut last: Option<Token> = None;
+        let mut expect_operand = true;
+        // The error for reaching a `)` or the end, at position `at`, where an operand should be
+        let missing_operand = |last: Option<Token>, at: usize| match last {
+            Some(token) if token.kind == TokenKind::Not => {
+                ParserError::ExpectedExpressionAfterNegation(token.position)
+            }
+            Some(token) if BinaryOperator::from_kind(token.kind).is_some() => {
+                let c = token.text.chars().next().unwrap();
+                ParserError::MissingRightOperand(c, token.position)
+            }
+            _ => ParserError::EmptyExpression(at),
+        };
+        for token in Lexer::new(self.input).with_mode(self.options.atoms) {
+            let token = match token {
+                Ok(token) => token,
+                Err(err) => {
+                    // Carry on as if the operator suggested had been written
+                    if matches!(err, ParserError::UnsupportedOperator(..)) {
+                        expect_operand = true;
+                    }
+                    diagnostics.push(err);
+                    continue;
+                }
+            };
+            match token.kind {
+                TokenKind::Not | TokenKind::Open if !expect_operand => {
+                    diagnostics.push(match token.text {
+                        "-" => ParserError::InvalidOperator('-', token.position),
+                        _ => unexpected(token),
+                    });
+                }
+                TokenKind::Name if !expect_operand && token.text == "v" => {
+                    diagnostics.push(ParserError::AtomNamedV(token.position));
+                }
+                TokenKind::Atom(_) | TokenKind::Name | TokenKind::Top | TokenKind::Bottom
+                    if !expect_operand =>
+                {
+                    diagnostics.push(unexpected(token));
+                }
+                TokenKind::Close => {
+                    if expect_operand {
+                        diagnostics.push(missing_operand(last, token.position));
+                    }
+                    if opens.pop().is_none() {
+                        diagnostics.push(unexpected(token));
+                    }
+                }
+                _ if BinaryOperator::from_kind(token.kind).is_some() && expect_operand => {
+                    diagnostics.push(match last {
+                        Some(not) if not.kind == TokenKind::Not => {
+                            ParserError::ExpectedExpressionAfterNegation(not.position)
+                        }
+                        _ => ParserError::ExpectedLeftOperand(token.position),
+                    });
                 }
+                _ => {}
             }
+            if token.kind == TokenKind::Open {
+                opens.push(token);
+            }
+            expect_operand = !matches!(
+                token.kind,
+                TokenKind::Atom(_)
+                    | TokenKind::Name
+                    | TokenKind::Top
+                    | TokenKind::Bottom
+                    | TokenKind::Close
+            );
+            last = Some(token);
+        }
+        // An unclosed `(` at the end is reported below
+        if expect_operand && last.map(|token| token.kind) != Some(TokenKind::Open) {
+            diagnostics.push(missing_operand(last, self.length));
+        }
+        for (depth, open) in opens.iter().enumerate() {
+            let rest = self.input[open.offset + open.text.len()..].to_string();
+            diagnostics.push(ParserError::UnmatchedParentheses(
+                rest,
+                depth + 1,
+                open.position,
+            ));
         }
+        diagnostics
+    }
 
-        stack.pop().ok_or(ParserError::EmptyExpression)
+    /// Parses the input like `parse`, storing the result in `arena` so that subformulas it shares
+    /// with everything else parsed into the arena are stored once. Each subformula is added to the
+    /// arena as soon as it is read, without building an `Expression` first. With normalization,
+    /// the subformulas as written are kept in the arena too. Use `ExprArena::shared` to get an
+    /// `Expression` back that keeps this sharing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use propositional_logic_calculator::{arena::ExprArena, parser::Parser};
+    ///
+    /// let mut arena = ExprArena::new();
+    /// let premise = Parser::new("P>Q").parse_interned(&mut arena).unwrap();
+    /// let conclusion = Parser::new("(P>Q)&P").parse_interned(&mut arena).unwrap();
+    /// assert_ne!(premise, conclusion);
+    /// // `P`, `Q`, `P > Q` and the conjunction
+    /// assert_eq!(arena.len(), 4);
+    /// ```
+    pub fn parse_interned(&mut self, arena: &mut ExprArena) -> Result<ExprId, ParserError> {
+        let id = self.parse_root(arena)?.id;
+        if self.normalization == Normalization::NONE {
+            return Ok(id);
+        }
+        let normalized = self.normalization.apply(&arena.shared(id));
+        Ok(arena.intern(&normalized))
     }
 
-    /// Handles the opening parenthesis by parsing the content within brackets.
-    ///
-    /// # Arguments
-    ///
-    /// * `stack`: A mutable reference to a vector of `Expression` objects representing the current state of the parser.
+    /// Parses an operand followed by any binary operators that bind at least as tightly as
+    /// `min_power`, together with their right operands.
     ///
     /// # Errors
     ///
-    /// Returns a `ParserError` if the bracketed content is not a valid expression or if parentheses are unmatched.
-    fn handle_parenthesis(&mut self, stack: &mut Vec<Expression>) -> Result<(), ParserError> {
-        let bracket = self.extract_bracket_contents()?;
-        stack.push(Parser::new(&bracket).parse()?);
-        Ok(())
+    /// Returns a `ParserError` if an operand is missing or invalid, or in strict parsing if the
+    /// grouping of the operators is left to precedence.
+    fn parse_expression(
+        &mut self,
+        arena: &mut ExprArena,
+        min_power: u8,
+    ) -> Result<Parsed, ParserError> {
+        let mut left = self.parse_operand(arena)?;
+        let mut top = None;
+        while let Some(token) = self.peek()? {
+            let Some(operator) = BinaryOperator::from_kind(token.kind) else {
+                if token.kind == TokenKind::Name && token.text == "v" {
+                    return Err(ParserError::AtomNamedV(token.position));
+                }
+                // A `-` between operands can only be a `->` that is missing its `>`
+                if token.text == "-" {
+                    return Err(ParserError::InvalidOperator('-', token.position));
+                }
+                break;
+            };
+            let (left_power, right_power) = operator.binding_power();
+            if left_power < min_power {
+                break;
+            }
+            self.tokens.next();
+            if self.peek()?.is_none() {
+                return Err(ParserError::EmptyExpression(self.length));
+            }
+            let position = self.peek()?.map_or(self.length, |token| token.position);
+            self.descend(position)?;
+            let right = self.parse_expression(arena, right_power)?;
+            self.nesting -= 1;
+            if self.options.strict && self.is_ambiguous(operator, top) {
+                let c = token.text.chars().next().unwrap();
+                return Err(ParserError::AmbiguousGrouping(c, token.position));
+            }
+            let id = operator.intern(arena, left.id, right.id);
+            left = Parsed::node(id, Connective::Binary(operator), vec![left, right]);
+            top = Some(operator);
+        }
+        self.top_operator = top;
+        Ok(left)
     }
 
-    /// Handles a variable character by adding it to the parser stack as an `Expression::Var`.
-    ///
-    /// # Arguments
-    ///
-    /// * `stack`: Mutable reference to the parser stack.
-    /// * `c`: The character representing the variable.
-    ///
-    /// # Returns
-    ///
-    /// Returns `Ok(())` if successfully handled, otherwise a `ParserError`
-    fn handle_variable(&mut self, stack: &mut Vec<Expression>, c: char) -> Result<(), ParserError> {
-        stack.push(Expression::Var(c));
-        Ok(())
+    /// Whether `operator` is grouped with an unbracketed operator beside it only by precedence or
+    /// associativity. `left` is the top operator of its left operand, and that of its right
+    /// operand is the last one recorded.
+    fn is_ambiguous(&self, operator: BinaryOperator, left: Option<BinaryOperator>) -> bool {
+        [left, self.top_operator]
+            .into_iter()
+            .flatten()
+            .any(|other| other != operator || !operator.is_associative())
     }
 
-    /// Handles negation in an expression.
-    ///
-    /// # Arguments
-    ///
-    /// * `stack`: Mutable reference to the parser stack.
+    /// Parses a variable, a constant, a negation or a bracketed expression.
     ///
     /// # Errors
     ///
-    /// Returns `ParserError` if the subsequent expression after negation is invalid.
-    fn handle_negation(&mut self, stack: &mut Vec<Expression>) -> Result<(), ParserError> {
-        stack.push(self.parse_negation()?);
-        Ok(())
+    /// Returns a `ParserError` if there is no operand or it is not a valid one.
+    fn parse_operand(&mut self, arena: &mut ExprArena) -> Result<Parsed, ParserError> {
+        let Some(token) = self.next()? else {
+            return Err(ParserError::EmptyExpression(self.length));
+        };
+        let node = match token.kind {
+            TokenKind::Atom(c) => Node::Var(c),
+            TokenKind::Name => Node::Var(self.letter_for(token)?),
+            TokenKind::Top => Node::Top,
+            TokenKind::Bottom => Node::Bottom,
+            TokenKind::Not => return self.parse_negation(arena, token.position),
+            TokenKind::Open => return self.parse_parenthesis(arena, token),
+            TokenKind::Close => return Err(unexpected(token)),
+            _ => return Err(ParserError::ExpectedLeftOperand(token.position)),
+        };
+        Ok(Parsed {
+            id: arena.insert(node),
+            span: token.position..token.position + token.text.chars().count(),
+            parenthesized: false,
+            children: Vec::new(),
+            connective: Connective::Leaf,
+        })
     }
 
-    /// Handles binary operators by constructing the appropriate binary expression.
-    ///
-    /// # Arguments
-    ///
-    /// * `stack`: Mutable reference to the parser stack.
-    /// * `operator`: The character representing the binary operator.
+    /// Parses the operand of the negation sign at `position`, which binds tighter than any binary
+    /// operator.
     ///
     /// # Errors
     ///
-    /// Returns `ParserError` for invalid binary expressions or missing operands.
-    fn handle_binary_operator(
+    /// Returns a `ParserError` if the negation is not followed by a valid expression.
+    fn parse_negation(
         &mut self,
-        stack: &mut Vec<Expression>,
-        operator: char,
-    ) -> Result<(), ParserError> {
-        let left = stack.pop().ok_or(ParserError::ExpectedLeftOperand)?;
-        let operation_result = self.parse_binary_operation(operator, left)?;
-        stack.push(operation_result);
-        Ok(())
+        arena: &mut ExprArena,
+        position: usize,
+    ) -> Result<Parsed, ParserError> {
+        if self.peek()?.is_none() {
+            return Err(ParserError::ExpectedExpressionAfterNegation(position));
+        }
+        self.descend(position)?;
+        let operand = self.parse_operand(arena)?;
+        let id = arena.insert(Node::Not(operand.id));
+        let mut negation = Parsed::node(id, Connective::Not, vec![operand]);
+        self.nesting -= 1;
+        negation.span.start = position;
+        Ok(negation)
     }
 
-    /// Parses a negation operation and returns the corresponding `Expression`.
+    /// Parses the expression after the opening parenthesis `open` up to its closing one.
     ///
     /// # Errors
     ///
-    /// Returns a `ParserError` if the negation is not followed by a valid expression.
-    fn parse_negation(&mut self) -> Result<Expression, ParserError> {
-        let next = self
-            .chars
-            .next()
-            .ok_or(ParserError::ExpectedExpressionAfterNegation)?;
-        let right = if next == '(' {
-            let bracket = self.extract_bracket_contents()?;
-            Parser::new(&bracket).parse()?
-        } else {
-            Expression::Var(next)
-        };
-
-        Ok(Expression::Not(right.wrap()))
+    /// Returns a `ParserError` if the bracketed content is not a valid expression or if parentheses are unmatched.
+    fn parse_parenthesis(
+        &mut self,
+        arena: &mut ExprArena,
+        open: Token<'a>,
+    ) -> Result<Parsed, ParserError> {
+        self.depth += 1;
+        self.descend(open.position)?;
+        let expression = self.parse_expression(arena, 0);
+        if let Err(ParserError::EmptyExpression(_)) = expression {
+            return Err(self.unmatched(open));
+        }
+        let mut expression = expression?;
+        match self.next()? {
+            Some(token) if token.kind == TokenKind::Close => {
+                self.depth -= 1;
+                self.nesting -= 1;
+                expression.span = open.position..token.position + 1;
+                expression.parenthesized = true;
+                Ok(expression)
+            }
+            Some(token) => Err(unexpected(token)),
+            None => Err(self.unmatched(open)),
+        }
     }
 
-    /// Parses a binary operation (AND, OR, IMPLIES) and returns the corresponding `Expression`.
+    /// Goes one level deeper into the input, at `position`.
     ///
-    /// # Arguments
+    /// # Errors
     ///
-    /// * `operator`: Character representing the binary operator.
-    /// * `left`: The left operand of the binary operation.
+    /// Returns `ParserError::TooDeeplyNested` if that is deeper than `max_depth`.
+    fn descend(&mut self, position: usize) -> Result<(), ParserError> {
+        self.nesting += 1;
+        if self.nesting > self.options.max_depth {
+            return Err(ParserError::TooDeeplyNested(
+                self.options.max_depth,
+                position,
+            ));
+        }
+        Ok(())
+    }
+
+    /// The sentence letter for the atom name `token`. A new name is given the first letter that
+    /// neither stands for an earlier name nor is written as an atom anywhere in the input.
     ///
     /// # Errors
     ///
-    /// Returns a `ParserError` for invalid operators or if the right operand is missing.
-    fn parse_binary_operation(
-        &mut self,
-        operator: char,
-        left: Expression,
-    ) -> Result<Expression, ParserError> {
-        self.consume_whitespace();
-        let right = self.parse()?;
-
-        let expr = match operator {
-            '&' => Expression::And(left.wrap(), right.wrap()),
-            'v' | '|' => Expression::Or(left.wrap(), right.wrap()),
-            '>' => Expression::Implies(left.wrap(), right.wrap()),
-            _ => return Err(ParserError::InvalidOperator(operator)),
-        };
-        Ok(expr)
+    /// Returns `ParserError::TooManyNames` if every letter is taken.
+    fn letter_for(&mut self, token: Token) -> Result<Symbol, ParserError> {
+        if let Some((_, letter)) = self.names.iter().find(|(name, _)| name == token.text) {
+            return Ok(*letter);
+        }
+        let written = Lexer::new(self.input)
+            .with_mode(AtomMode::Names)
+            .filter_map(|token| match token {
+                Ok(Token {
+                    kind: TokenKind::Atom(letter),
+                    ..
+                }) => Some(letter),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let letter = atom_names(usize::MAX)
+            .into_iter()
+            .find(|c| !written.contains(c) && !self.names.iter().any(|(_, l)| l == c))
+            .ok_or_else(|| ParserError::TooManyNames(token.text.to_string(), token.position))?;
+        self.names.push((token.text.to_string(), letter));
+        Ok(letter)
+    }
+
+    /// Consumes the next token, or returns the error for a character that starts none.
+    fn next(&mut self) -> Result<Option<Token<'a>>, ParserError> {
+        self.tokens.next().transpose()
+    }
+
+    /// Returns the next token without consuming it, or the error for a character that starts
+    /// none.
+    fn peek(&mut self) -> Result<Option<Token<'a>>, ParserError> {
+        self.tokens.peek().cloned().transpose()
     }
 
-    /// Consumes and ignores any whitespace characters in the current parsing context.
-    fn consume_whitespace(&mut self) {
-        while let Some(&' ') = self.chars.peek() {
-            self.chars.next();
+    /// The error for the parenthesis `open`, followed by the rest of the text, that is never
+    /// closed.
+    fn unmatched(&self, open: Token) -> ParserError {
+        let rest = self.input[open.offset + open.text.len()..].to_string();
+        ParserError::UnmatchedParentheses(rest, self.depth, open.position)
+    }
+}
+
+/// A parsed subformula with where it is written, as given by `Parser::parse_spanned`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned {
+    pub expression: Expression,
+    /// Where the subformula is written, counted in characters from 0 as in `ParserError`,
+    /// including any parentheses around it
+    pub span: Range<usize>,
+    /// Whether the span starts and ends with parentheses around the subformula
+    pub parenthesized: bool,
+    /// The operands, in the order they are written
+    pub children: Vec<Spanned>,
+    /// How `expression` is built from the operands
+    connective: Connective,
+}
+
+/// How the expression of a `Spanned` is built from its operands. Exclusive or, NAND and NOR are
+/// kept apart from the connectives they are rewritten into, so that the expression can be built
+/// again when an operand changes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Connective {
+    Leaf,
+    Not,
+    Binary(BinaryOperator),
+}
+
+/// A subformula as the parser reads it: the id of its expression in the arena being parsed into,
+/// with what `Spanned` records about it.
+struct Parsed {
+    id: ExprId,
+    span: Range<usize>,
+    parenthesized: bool,
+    children: Vec<Parsed>,
+    connective: Connective,
+}
+
+impl Parsed {
+    /// The subformula `id`, made by `connective` from `children`, spanning them all.
+    fn node(id: ExprId, connective: Connective, children: Vec<Parsed>) -> Self {
+        let span = children[0].span.start..children[children.len() - 1].span.end;
+        Parsed {
+            id,
+            span,
+            parenthesized: false,
+            children,
+            connective,
         }
     }
 
-    /// Extracts the contents within a pair of matching parentheses.
-    ///
-    /// # Errors
-    ///
-    /// Returns a `ParserError` if the parentheses are unmatched.
-    fn extract_bracket_contents(&mut self) -> Result<String, ParserError> {
-        let mut bracket = String::new();
-        let mut bracket_count = 1;
+    /// This subformula with the `Expression` of each node built from `arena`, sharing repeated
+    /// subformulas.
+    fn spanned(self, arena: &mut ExprArena) -> Spanned {
+        Spanned {
+            expression: Expression::clone(&arena.shared(self.id)),
+            span: self.span,
+            parenthesized: self.parenthesized,
+            children: self
+                .children
+                .into_iter()
+                .map(|child| child.spanned(arena))
+                .collect(),
+            connective: self.connective,
+        }
+    }
+}
 
-        for c in self.chars.by_ref() {
-            match c {
-                '(' => bracket_count += 1,
-                ')' => bracket_count -= 1,
-                _ => bracket.push(c),
+impl Spanned {
+    /// Builds the expression again from those of the operands, after one of them has changed.
+    pub(crate) fn rebuild(&mut self) {
+        let operand = |i: usize| self.children[i].expression.clone();
+        match self.connective {
+            Connective::Leaf => {}
+            Connective::Not => self.expression = Expression::Not(operand(0).wrap()),
+            Connective::Binary(operator) => {
+                self.expression = operator.apply(operand(0), operand(1));
             }
+        }
+    }
+}
 
-            if bracket_count == 0 {
-                return Ok(bracket);
-            }
+/// How formulas in Polish (prefix) notation, read by `parse_polish` and written by
+/// `Expression::to_polish`, write their connectives.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PolishStyle {
+    /// The symbols of the infix notation, separated by spaces, as in `-> & P Q R`
+    #[default]
+    Symbols,
+    /// Łukasiewicz's letters `N`, `K`, `A`, `C` and `E` for negation, conjunction, disjunction,
+    /// the conditional and the biconditional, as in `CKPQR` or `CKpqr`. These five letters
+    /// cannot also be sentence letters.
+    Letters,
+}
+
+impl PolishStyle {
+    /// Łukasiewicz's letter for each connective, with the token it stands for.
+    pub(crate) const LETTERS: [(char, TokenKind); 5] = [
+        ('N', TokenKind::Not),
+        ('K', TokenKind::And),
+        ('A', TokenKind::Or),
+        ('C', TokenKind::Implies),
+        ('E', TokenKind::Iff),
+    ];
+}
+
+/// Parses a formula written in Polish notation, where each connective comes before its operands
+/// and no parentheses are needed, so `-> & P Q R` (or `CKPQR` in the `Letters` style) is
+/// `(P & Q) -> R`. In the `Symbols` style every symbol the infix parser accepts may be used.
+/// Tokens only need separating by spaces where they would otherwise run together, as two
+/// lowercase letters or a letter and a digit do.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{
+///     parser::{parse_polish, PolishStyle},
+///     proof::parse_expression,
+/// };
+///
+/// let expected = parse_expression("(P&Q)>-R").unwrap();
+/// assert_eq!(parse_polish("> & P Q - R", PolishStyle::Symbols).unwrap(), expected);
+/// assert_eq!(parse_polish("CKPQNR", PolishStyle::Letters).unwrap(), expected);
+/// ```
+///
+/// # Errors
+///
+/// Returns a `ParserError` for a connective without enough operands, for anything left over once
+/// the formula is complete, for parentheses, for anything the lexer rejects and for connectives
+/// nested deeper than `ParserOptions::DEFAULT_MAX_DEPTH`.
+pub fn parse_polish(input: &str, style: PolishStyle) -> Result<Expression, ParserError> {
+    let mut tokens = Lexer::new(input);
+    let empty = ParserError::EmptyExpression(input.chars().count());
+    let expression = polish_operand(&mut tokens, style, empty, 0)?;
+    match tokens.next() {
+        None => Ok(expression),
+        Some(Ok(token)) => Err(unexpected(token)),
+        Some(Err(err)) => Err(err),
+    }
+}
+
+/// Reads one operand in Polish notation from `tokens`, returning `missing` if there is none.
+/// The operand is the operand of `depth` connectives, and reading it recurses once for each, so
+/// past `ParserOptions::DEFAULT_MAX_DEPTH` it is `ParserError::TooDeeplyNested` instead.
+fn polish_operand(
+    tokens: &mut Lexer,
+    style: PolishStyle,
+    missing: ParserError,
+    depth: usize,
+) -> Result<Expression, ParserError> {
+    let token = match tokens.next() {
+        Some(token) => token?,
+        None => return Err(missing),
+    };
+    if depth > ParserOptions::DEFAULT_MAX_DEPTH {
+        return Err(ParserError::TooDeeplyNested(
+            ParserOptions::DEFAULT_MAX_DEPTH,
+            token.position,
+        ));
+    }
+    let kind = match (style, token.kind) {
+        (PolishStyle::Letters, TokenKind::Atom(letter)) => PolishStyle::LETTERS
+            .iter()
+            .find(|(connective, _)| Symbol::new(*connective) == letter)
+            .map_or(token.kind, |(_, kind)| *kind),
+        (_, kind) => kind,
+    };
+    match kind {
+        TokenKind::Not => {
+            let missing = ParserError::ExpectedExpressionAfterNegation(token.position);
+            Ok(Expression::Not(
+                polish_operand(tokens, style, missing, depth + 1)?.wrap(),
+            ))
         }
+        TokenKind::Atom(letter) => Ok(Expression::Var(letter)),
+        TokenKind::Top => Ok(Expression::Top),
+        TokenKind::Bottom => Ok(Expression::Bottom),
+        kind => {
+            let operator = BinaryOperator::from_kind(kind).ok_or_else(|| unexpected(token))?;
+            let c = token.text.chars().next().unwrap();
+            let left = polish_operand(
+                tokens,
+                style,
+                ParserError::ExpectedLeftOperand(token.position),
+                depth + 1,
+            )?;
+            let right = polish_operand(
+                tokens,
+                style,
+                ParserError::MissingRightOperand(c, token.position),
+                depth + 1,
+            )?;
+            Ok(operator.apply(left, right))
+        }
+    }
+}
+
+/// The error for `token` where an operator or the end of the input should be.
+fn unexpected(token: Token) -> ParserError {
+    ParserError::InvalidExpression(token.text.chars().next().unwrap(), token.position)
+}
 
-        Err(ParserError::UnmatchedParentheses(bracket, bracket_count))
+/// The LaTeX commands understood by `parse_latex`, with the symbol each one is read as. `\left`
+/// and `\right`, which only size the parentheses after them, are read as nothing.
+const LATEX_COMMANDS: [(&str, Option<char>); 19] = [
+    ("land", Some('∧')),
+    ("wedge", Some('∧')),
+    ("lor", Some('∨')),
+    ("vee", Some('∨')),
+    ("lnot", Some('¬')),
+    ("neg", Some('¬')),
+    ("rightarrow", Some('→')),
+    ("to", Some('→')),
+    ("implies", Some('→')),
+    ("leftrightarrow", Some('↔')),
+    ("iff", Some('↔')),
+    ("oplus", Some('⊕')),
+    ("veebar", Some('⊻')),
+    ("uparrow", Some('↑')),
+    ("downarrow", Some('↓')),
+    ("top", Some('⊤')),
+    ("bot", Some('⊥')),
+    ("left", None),
+    ("right", None),
+];
+
+/// Parses a formula written in LaTeX, such as `(P \land Q) \rightarrow \lnot R`, into the
+/// same `Expression` that `parse_expression` gives for the formula written with ASCII operators.
+///
+/// The connectives may be written as `\land` or `\wedge`, `\lor` or `\vee`, `\lnot` or
+/// `\neg`, `\rightarrow`, `\to` or `\implies`, `\leftrightarrow` or `\iff`, `\oplus` or
+/// `\veebar`, `\uparrow` and `\downarrow`, and the constants as `\top` and `\bot`. Braces group
+/// like parentheses, `\left` and `\right` are ignored, and so are the `$` signs around inline
+/// math. A subscript, as in `P_1` or `P_{12}`, makes an indexed sentence letter. Everything the
+/// plain parser accepts is accepted too.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{parser::parse_latex, proof::parse_expression};
+///
+/// assert_eq!(
+///     parse_latex("$(P \\land Q) \\rightarrow \\lnot R$").unwrap(),
+///     parse_expression("(P&Q)>-R").unwrap()
+/// );
+/// ```
+///
+/// # Errors
+///
+/// Returns a `ParserError` for an unknown command, reported as an invalid `\`, or for anything
+/// the plain parser rejects. Positions count characters of the LaTeX input.
+pub fn parse_latex(input: &str) -> Result<Expression, ParserError> {
+    // The input with each command replaced by its symbol, and where each character of it came from
+    let mut translated = String::new();
+    let mut origins = Vec::new();
+    let mut chars = input.chars().enumerate().peekable();
+    while let Some((position, c)) = chars.next() {
+        let symbol = match c {
+            '$' => None,
+            '{' => Some('('),
+            '}' => Some(')'),
+            // A subscript, `_1` or `_{12}`, is written straight after the letter
+            '_' => {
+                let braced = chars.next_if(|(_, c)| *c == '{').is_some();
+                let mut digits = 0;
+                while let Some((position, digit)) = chars.next_if(|(_, c)| c.is_ascii_digit()) {
+                    translated.push(digit);
+                    origins.push(position);
+                    digits += 1;
+                }
+                if digits == 0 || braced && chars.next_if(|(_, c)| *c == '}').is_none() {
+                    return Err(ParserError::InvalidExpression('_', position));
+                }
+                None
+            }
+            '\\' => {
+                let mut name = String::new();
+                while let Some((_, letter)) = chars.next_if(|(_, c)| c.is_ascii_alphabetic()) {
+                    name.push(letter);
+                }
+                match LATEX_COMMANDS.iter().find(|(command, _)| *command == name) {
+                    Some((_, symbol)) => *symbol,
+                    None => return Err(ParserError::InvalidExpression('\\', position)),
+                }
+            }
+            _ => Some(c),
+        };
+        if let Some(symbol) = symbol {
+            translated.push(symbol);
+            origins.push(position);
+        }
     }
+    let length = input.chars().count();
+    Parser::new(&translated)
+        .parse()
+        .map_err(|err| err.moved(|position| origins.get(position).copied().unwrap_or(length)))
 }
@@ -1,21 +1,32 @@
-use rand::Rng;
-
+#[cfg(feature = "check-soundness")]
+use crate::semantics::{entails_set, ConclusionMode};
 use crate::{
-    expression::Expression,
+    arena::ExprId,
+    expression::{Expression, Symbol},
     lines::{Line, Rule},
-    proof::{Proof, SearchNode, SearchSettings},
+    proof::{is_contradiction, OrIntroduction, Proof, SearchNode},
+    sat,
+    semantics::collect_atoms,
 };
 
-const INNER_SEARCH_SETTINGS: SearchSettings = SearchSettings {
-    max_line_length: 15,
-    iterations: 50000,
-};
+/// Reductio searches accept any contradiction, which is usually only a few steps away, so they
+/// get this fraction of a normal sub proof budget and a failing one is cut off early.
+const REDUCTIO_SHARE: f64 = 0.1;
+
+/// How many sub proofs (of any kind) may be open inside each other at once. Every level runs a
+/// full inner search from each node of the level above, so this keeps the search finite.
+const MAX_OPEN_SUBPROOFS: usize = 2;
+
+/// Candidate lines are only checked against a truth table when there are at most this many
+/// sentence letters, beyond which the table takes too long even for a soundness check.
+#[cfg(feature = "check-soundness")]
+const MAX_CHECKED_ATOMS: usize = 12;
 
 #[derive(Debug, Clone)]
 pub struct PossibleFinder {
     node: Box<SearchNode>,
     possibles: Vec<Possible>,
-    vars: Vec<char>,
+    vars: Vec<Symbol>,
 }
 
 impl PossibleFinder {
@@ -36,21 +47,30 @@ impl PossibleFinder {
         self.node.lines.is_empty()
     }
 
-    fn find_vars(&self) -> Vec<char> {
-        let mut found_vars = Vec::new();
-        for line in self.node.lines.iter() {
-            find_vars_for_expression(&line.expression, &mut found_vars);
-        }
-        find_vars_for_expression(&self.node.conclusion, &mut found_vars);
-        found_vars
+    fn find_vars(&self) -> Vec<Symbol> {
+        collect_atoms(
+            self.node
+                .lines
+                .iter()
+                .map(|line| &line.expression)
+                .chain([&self.node.conclusion]),
+        )
     }
 
     pub fn possibles(&self) -> &Vec<Possible> {
         &self.possibles
     }
 
+    /// Generates the candidates for this node.
+    ///
+    /// The candidates are ordered by the rule that produces their last line, in the order `Rule`
+    /// declares them, then by the line numbers that line cites. Candidates that tie on both keep
+    /// the order in which they were generated. The search expands candidates in this order, so a
+    /// sequent always produces the same proof. When there are more than `max_candidates`, only
+    /// the most promising are kept, still in this order. Candidates deriving a formula larger
+    /// than `max_formula_size` other than the goal are dropped.
     pub fn find(&mut self) {
-        self.vars = self.find_vars();
+        self.vars = self.node.settings.atoms.atoms(&self.find_vars());
         self.possible_mp();
         self.possible_mt();
         self.possible_and_e();
@@ -59,16 +79,140 @@ impl PossibleFinder {
         self.possible_or_i_with_vars();
         self.possible_dn_remove();
         self.possible_dn_add();
+        self.possible_iff_i();
+        self.possible_iff_e();
+        self.possible_top_i();
+        self.possible_bottom_i();
+        self.possible_bottom_e();
         self.possible_or_e();
         self.possible_cp();
+        self.possible_raa();
+        #[cfg(feature = "check-soundness")]
+        self.check_soundness();
+        if let Some(max) = self.node.settings.max_formula_size {
+            let node = &self.node;
+            self.possibles.retain(|x| {
+                let last = &x.lines.last().unwrap().expression;
+                last.size() <= max || node.is_goal(last)
+            });
+        }
         // Check if an expression already exists in the proof (it is useless to add it again)
-        self.possibles.retain(|x| {
-            !self
-                .node
+        let mut arena = self.node.arena.borrow_mut();
+        let existing = &self.node.line_ids;
+        self.possibles.retain_mut(|x| {
+            x.ids = x
                 .lines
                 .iter()
-                .any(|y| x.lines.iter().any(|z| z.expression == y.expression))
+                .map(|line| arena.intern(&line.expression))
+                .collect();
+            if x.ids.iter().any(|id| existing.contains(id)) {
+                return false;
+            }
+            // Keep the arena's copies, so that equal subformulas share one allocation across the
+            // search and comparing them stops at the pointer
+            for (line, id) in x.lines.iter_mut().zip(&x.ids) {
+                line.expression = Expression::clone(&arena.shared(*id));
+            }
+            true
         });
+        drop(arena);
+        self.possibles.sort_by(|a, b| {
+            let (a, b) = (a.lines.last().unwrap(), b.lines.last().unwrap());
+            (&a.rule, &a.deduction_lines).cmp(&(&b.rule, &b.deduction_lines))
+        });
+        if let Some(max) = self.node.settings.max_candidates {
+            self.cap(max);
+        }
+    }
+
+    /// Keeps the `max` most promising candidates: first those that reach the goal or appear in
+    /// it, then those with the smallest formulas. Ties are broken by the existing order.
+    fn cap(&mut self, max: usize) {
+        if self.possibles.len() <= max {
+            return;
+        }
+        let goal_parts = self.node.conclusion.list_expressions();
+        let mut ranked = self
+            .possibles
+            .iter()
+            .enumerate()
+            .map(|(i, possible)| {
+                let expression = &possible.lines.last().unwrap().expression;
+                let relevant = self.node.is_goal(expression) || goal_parts.contains(expression);
+                (!relevant, expression.size(), i)
+            })
+            .collect::<Vec<_>>();
+        ranked.sort();
+        let mut kept = ranked[..max].iter().map(|(_, _, i)| *i).collect::<Vec<_>>();
+        kept.sort();
+        let dropped = self.possibles.len() - max;
+        let mut i = 0;
+        self.possibles.retain(|_| {
+            i += 1;
+            kept.binary_search(&(i - 1)).is_ok()
+        });
+
+        let mut stats = self.node.stats.get();
+        stats.capped_nodes += 1;
+        stats.dropped_candidates += dropped;
+        self.node.stats.set(stats);
+    }
+
+    /// Panics if a line of any candidate does not follow from what it rests on: each line must be
+    /// entailed by the lines it depends on, and a line that discharges nothing must also be
+    /// entailed by the lines it cites. Lines with more than `MAX_CHECKED_ATOMS` letters are not
+    /// checked.
+    #[cfg(feature = "check-soundness")]
+    fn check_soundness(&self) {
+        for possible in &self.possibles {
+            let all = self
+                .node
+                .lines
+                .iter()
+                .chain(possible.lines.iter())
+                .collect::<Vec<&Line>>();
+            let expressions = |numbers: &[usize]| {
+                numbers
+                    .iter()
+                    .map(|n| {
+                        all.iter()
+                            .find(|line| line.line_number == *n)
+                            .unwrap_or_else(|| panic!("a candidate cites missing line {}", n))
+                            .expression
+                            .clone()
+                    })
+                    .collect::<Vec<Expression>>()
+            };
+            for line in &possible.lines {
+                let conclusion = [line.expression.clone()];
+                let mut checks = vec![expressions(&line.assumption_lines)];
+                let discharges = matches!(
+                    line.rule,
+                    Rule::ConditionalProof | Rule::OrElimination | Rule::ReductioAdAbsurdium
+                );
+                let assumes = matches!(
+                    line.rule,
+                    Rule::Assumption
+                        | Rule::ConditionalProofAssumption
+                        | Rule::OrEliminationAssumption
+                        | Rule::ReductioAdAbsurdiumAssumption
+                );
+                if !discharges && !assumes {
+                    checks.push(expressions(&line.deduction_lines));
+                }
+                for premises in checks {
+                    if collect_atoms(premises.iter().chain(&conclusion)).len() > MAX_CHECKED_ATOMS {
+                        continue;
+                    }
+                    assert!(
+                        entails_set(&premises, &conclusion, ConclusionMode::Conjunctive).is_valid(),
+                        "unsound candidate: {} does not follow from {:?}",
+                        line,
+                        premises.iter().map(|p| p.to_string()).collect::<Vec<_>>(),
+                    );
+                }
+            }
+        }
     }
 
     fn add_possible(&mut self, possible: Possible) {
@@ -209,6 +353,118 @@ impl PossibleFinder {
         }
     }
 
+    fn possible_iff_i(&mut self) {
+        for ab in self.clone().iterate_lines_in_dimension(2) {
+            // If the lines are conditionals going both ways between the same two sides
+            if let (Expression::Implies(a, b), Expression::Implies(c, d)) =
+                (&ab[0].expression, &ab[1].expression)
+            {
+                if a != d || b != c {
+                    continue;
+                }
+                let deductions = vec![ab[0].line_number, ab[1].line_number];
+                let assumptions = self.assumption_line_nums(deductions.clone());
+                let possible = Possible::new_single(Line::new(
+                    assumptions,
+                    self.len(),
+                    Expression::Iff(a.clone(), b.clone()),
+                    Rule::BiconditionalIntroduction,
+                    deductions,
+                ));
+                self.add_possible(possible);
+            }
+        }
+    }
+
+    fn possible_iff_e(&mut self) {
+        for line in self.clone().node.lines.iter() {
+            // If the line is a biconditional, either direction of it follows
+            if let Expression::Iff(left, right) = &line.expression {
+                let deductions = vec![line.line_number];
+                let assumptions = self.assumption_line_nums(deductions.clone());
+                let line = Line::new(
+                    assumptions,
+                    self.len(),
+                    Expression::Implies(left.clone(), right.clone()),
+                    Rule::BiconditionalElimination,
+                    deductions,
+                );
+                let mut line2 = line.clone();
+                line2.expression = Expression::Implies(right.clone(), left.clone());
+                let possibles = vec![Possible::new_single(line), Possible::new_single(line2)];
+                self.possibles.extend(possibles);
+            }
+        }
+    }
+
+    fn possible_top_i(&mut self) {
+        // Verum holds without any assumptions, but is only worth writing down if the goal uses it
+        if !self
+            .node
+            .conclusion
+            .list_expressions()
+            .contains(&Expression::Top)
+        {
+            return;
+        }
+        let possible = Possible::new_single(Line::new(
+            vec![],
+            self.len(),
+            Expression::Top,
+            Rule::VerumIntroduction,
+            vec![],
+        ));
+        self.add_possible(possible);
+    }
+
+    fn possible_bottom_i(&mut self) {
+        // Contradictions are usually left as `X & -X`, so falsum is only introduced when the goal
+        // uses it
+        if !self
+            .node
+            .conclusion
+            .list_expressions()
+            .contains(&Expression::Bottom)
+        {
+            return;
+        }
+        for ab in self.clone().iterate_lines_in_dimension(2) {
+            // If the second line is the negation of the first
+            if !ab[1].matches_expression(&Expression::Not(ab[0].expression.clone().wrap())) {
+                continue;
+            }
+            let deductions = vec![ab[0].line_number, ab[1].line_number];
+            let assumptions = self.assumption_line_nums(deductions.clone());
+            let possible = Possible::new_single(Line::new(
+                assumptions,
+                self.len(),
+                Expression::Bottom,
+                Rule::FalsumIntroduction,
+                deductions,
+            ));
+            self.add_possible(possible);
+        }
+    }
+
+    fn possible_bottom_e(&mut self) {
+        for line in self.clone().node.lines.iter() {
+            // Anything follows from falsum, so go straight to the goal
+            if line.expression != Expression::Bottom {
+                continue;
+            }
+            let deductions = vec![line.line_number];
+            let assumptions = self.assumption_line_nums(deductions.clone());
+            let possible = Possible::new_single(Line::new(
+                assumptions,
+                self.len(),
+                self.node.conclusion.clone(),
+                Rule::FalsumElimination,
+                deductions,
+            ));
+            self.add_possible(possible);
+        }
+    }
+
     fn possible_or_i(&mut self) {
         for ab in self.clone().iterate_lines_in_dimension(2) {
             let deductions = vec![ab[0].line_number, ab[1].line_number];
@@ -228,21 +484,44 @@ impl PossibleFinder {
     }
 
     fn possible_or_i_with_vars(&mut self) {
+        let mut disjuncts = match self.node.settings.or_introduction {
+            OrIntroduction::Off => return,
+            // Subformulas using a letter outside the universe would bring it into the proof
+            OrIntroduction::ConclusionSubformulas => {
+                let mut subformulas = self.node.conclusion.list_expressions();
+                subformulas.retain(|subformula| {
+                    subformula
+                        .letters()
+                        .iter()
+                        .all(|atom| self.vars.contains(atom))
+                });
+                subformulas
+            }
+            OrIntroduction::AllVariables => self.vars.iter().map(|c| Expression::Var(*c)).collect(),
+        };
+        // Repeated subformulas would only produce the same candidates again
+        let mut i = 0;
+        while i < disjuncts.len() {
+            if disjuncts[..i].contains(&disjuncts[i]) {
+                disjuncts.remove(i);
+            } else {
+                i += 1;
+            }
+        }
         for line in self.node.lines.iter() {
-            let vars = self.vars.clone();
-            for c in vars {
+            for disjunct in &disjuncts {
                 let deductions = vec![line.line_number];
                 let assumptions = self.assumption_line_nums(deductions.clone());
                 let poss_1 = Line::new(
                     assumptions,
                     self.len(),
-                    Expression::Or(line.expression.clone().wrap(), Expression::Var(c).wrap()),
+                    Expression::Or(line.expression.clone().wrap(), disjunct.clone().wrap()),
                     Rule::OrIntroduction,
                     deductions,
                 );
                 let mut poss_2 = poss_1.clone();
                 poss_2.expression =
-                    Expression::Or(Expression::Var(c).wrap(), line.expression.clone().wrap());
+                    Expression::Or(disjunct.clone().wrap(), line.expression.clone().wrap());
                 let possibles = vec![Possible::new_single(poss_1), Possible::new_single(poss_2)];
                 self.possibles.extend(possibles);
             }
@@ -250,84 +529,84 @@ impl PossibleFinder {
     }
 
     fn possible_or_e(&mut self) {
-        // If the a line already contains an orEliminationAssumption and not orElimination, we can't add another one or we'll end up in an infinite loop
-        for line in self.clone().node.lines.iter() {
-            if line.rule == Rule::OrEliminationAssumption {
-                // Here we've encountered a new sub-proof, we need to check whether this subproof ends in an orElimination
-                // If it doesn't it means were still in the middle of a subproof and we can't add another orEliminationAssumption
-                // If it does it means we've reached the end of the subproof and we can add another orEliminationAssumption
-                let line_num = line.line_number;
-                let mut found = false;
-                for l in self.clone().node.lines.iter().skip(line_num) {
-                    if l.rule == Rule::OrElimination && l.deduction_lines.contains(&line_num) {
-                        found = true;
-                        break;
-                    }
-                }
-                if found {
-                    continue;
-                }
-                return;
-            }
+        let open_subproofs = self.open_subproofs();
+        if open_subproofs.len() >= MAX_OPEN_SUBPROOFS {
+            return;
         }
         for line in self.clone().node.lines.iter() {
-            // If the line is an or expression
-            if let Expression::Or(left, right) = &line.expression {
-                let mut a_lines = self.node.lines.clone();
-                let line = Line::new(
-                    vec![line.line_number],
-                    self.len(),
-                    left.as_ref().clone(),
-                    Rule::OrEliminationAssumption,
-                    vec![line.line_number],
-                );
-                a_lines.push(line.clone());
-                // Try to contruct a proof for the conclusion using the new assumption (a)
-                let a_deduction_lines = match self.search_sub_proof(a_lines, None) {
-                    Ok(lines) => lines,
-                    Err(_) => continue,
-                };
-                let mut line_b = line.clone();
-                line_b.expression = right.as_ref().clone();
-                let mut b_lines = self.node.lines.clone();
-                b_lines.push(line_b);
-                // Try to contruct a proof for the conclusion using the new assumption (b)
-                let b_deduction_lines = match self.search_sub_proof(b_lines, None) {
-                    Ok(lines) => lines,
-                    Err(_) => continue,
-                };
-                // Add the lines from this proof
-                let mut resulting_lines = Vec::new();
-                for l in a_deduction_lines.clone() {
-                    resulting_lines.push(l.clone());
+            // A disjunction that was just introduced with vI can only give back cases we already
+            // have, and splitting a disjunction again inside one of its own cases would never end
+            if line.rule == Rule::OrIntroduction
+                || open_subproofs.iter().any(|open| {
+                    open.rule == Rule::OrEliminationAssumption
+                        && open.deduction_lines.contains(&line.line_number)
+                })
+            {
+                continue;
+            }
+            // If the line is an or expression, split into one case per disjunct. Nested
+            // disjunctions such as `A v (B v C)` are flattened so that every disjunct gets its own
+            // case in a single application instead of needing nested applications of vE.
+            if let Expression::Or(_, _) = &line.expression {
+                // Every case has to reach the conclusion, so give up unless they all can
+                if !disjuncts(&line.expression)
+                    .iter()
+                    .all(|disjunct| self.may_follow(disjunct, &self.node.conclusion))
+                {
+                    continue;
                 }
-                // Add the lines from the second proof
-                for l in b_deduction_lines.clone() {
-                    let mut l = l.clone();
-                    for d in l.deduction_lines.iter_mut() {
-                        if *d >= self.len() {
-                            *d += a_deduction_lines.len();
+                let mut resulting_lines: Vec<Line> = Vec::new();
+                // The disjunction's assumptions plus those each case needed besides its own
+                let mut assumptions = line.assumption_lines.clone();
+                let mut all_cases_proven = true;
+                for disjunct in disjuncts(&line.expression) {
+                    let assumption = Line::new(
+                        vec![self.len()],
+                        self.len(),
+                        disjunct,
+                        Rule::OrEliminationAssumption,
+                        vec![line.line_number],
+                    );
+                    let mut case_lines = self.node.lines.clone();
+                    case_lines.push(assumption);
+                    // Try to construct a proof for the conclusion using the assumption for this case
+                    let case_deduction_lines = match self.search_sub_proof(case_lines, None) {
+                        Ok(lines) => lines,
+                        Err(_) => {
+                            all_cases_proven = false;
+                            break;
+                        }
+                    };
+                    // Each case was searched as if it started right after the existing lines, so
+                    // move it down past the cases that come before it
+                    let offset = resulting_lines.len();
+                    let case_deduction_lines =
+                        shift_lines(case_deduction_lines, self.len(), offset);
+                    let case_assumption = self.len() + offset;
+                    let case_conclusion = self
+                        .node
+                        .lines
+                        .iter()
+                        .chain(case_deduction_lines.iter())
+                        .rev()
+                        .find(|l| l.matches_expression(&self.node.conclusion))
+                        .unwrap();
+                    for a in discharge(case_conclusion, case_assumption) {
+                        if !assumptions.contains(&a) {
+                            assumptions.push(a);
                         }
                     }
-                    l.line_number += a_deduction_lines.len();
-                    resulting_lines.push(l);
+                    resulting_lines.extend(case_deduction_lines);
+                }
+                if !all_cases_proven {
+                    continue;
                 }
                 let mut deductions = vec![line.line_number];
                 // Add all the lines from the proofs
                 for l in resulting_lines.clone() {
                     deductions.push(l.line_number);
                 }
-                // To get the assumptions we can just input the deductions into the assumption_line_nums function
-                // We need to look at all of the new lines and get their assumptions
-                let mut assumptions = self.assumption_line_nums(deductions.clone());
-                // Add the assumption from the first proof
-                for l in resulting_lines.clone() {
-                    for a in l.assumption_lines {
-                        if !assumptions.contains(&a) {
-                            assumptions.push(a);
-                        }
-                    }
-                }
+                assumptions.sort();
 
                 let final_line = Line::new(
                     assumptions,
@@ -345,92 +624,229 @@ impl PossibleFinder {
 
     fn possible_cp(&mut self) {
         // If the conclusion is an implies we might need to use a conditional proof
-        if let Expression::Implies(left, right) = &self.node.conclusion {
-            for line in self.clone().node.lines.iter() {
-                if line.rule == Rule::ConditionalProofAssumption {
-                    // Here we've encountered a new sub-proof, we need to check whether this subproof ends in an orElimination
-                    // If it doesn't it means were still in the middle of a subproof and we can't add another orEliminationAssumption
-                    // If it does it means we've reached the end of the subproof and we can add another orEliminationAssumption
-                    let line_num = line.line_number;
-                    let mut found = false;
-                    for l in self.clone().node.lines.iter().skip(line_num) {
-                        if l.rule == Rule::ConditionalProof && l.deduction_lines.contains(&line_num)
-                        {
-                            found = true;
-                            break;
-                        }
-                    }
-                    if found {
-                        continue;
-                    }
-                    return;
-                }
-            }
-            // First we need to assume the left side
-            let mut lines = self.node.lines.clone();
-            let assumption = Line::new(
-                vec![self.len()],
-                self.len(),
-                left.as_ref().clone(),
-                Rule::ConditionalProofAssumption,
-                vec![],
-            );
-            lines.push(assumption);
-            // Then we need to construct a proof for the right side using the assumption
-            let deduction_lines = match self.search_sub_proof(lines, Some(right.as_ref().clone())) {
-                Ok(lines) => lines,
-                Err(_) => return,
-            };
-            let rand = rand::thread_rng().gen_range(0..10000);
-            if rand < 5 {
-                for l in deduction_lines.clone() {
-                    println!("{}", l);
+        match self.node.conclusion.clone() {
+            Expression::Implies(left, right) => self.conditional_proof(&left, &right),
+            // The directions of a biconditional are proved as conditionals one at a time, ready
+            // for <->I. Proving both from every node would repeat the same inner searches.
+            Expression::Iff(left, right) => {
+                let missing = [(left.clone(), right.clone()), (right, left)]
+                    .into_iter()
+                    .find(|(a, b)| {
+                        let conditional = Expression::Implies(a.clone(), b.clone());
+                        !self
+                            .node
+                            .lines
+                            .iter()
+                            .any(|l| l.matches_expression(&conditional))
+                    });
+                if let Some((a, b)) = missing {
+                    self.conditional_proof(&a, &b);
                 }
             }
+            _ => (),
+        }
+    }
 
-            let mut deduction_line_nums = vec![self.len()];
-            for l in deduction_lines.clone() {
-                deduction_line_nums.push(l.line_number);
-            }
-            let mut assumptions = Vec::new();
-            for l in deduction_lines.clone() {
-                for a in l.assumption_lines {
-                    if !assumptions.contains(&a) {
-                        assumptions.push(a);
-                    }
-                }
+    /// Tries to prove `left -> right` by assuming `left` and searching for `right`.
+    fn conditional_proof(&mut self, left: &Expression, right: &Expression) {
+        if self.open_subproofs().len() >= MAX_OPEN_SUBPROOFS || !self.may_follow(left, right) {
+            return;
+        }
+        // First we need to assume the left side
+        let mut lines = self.node.lines.clone();
+        let assumption = Line::new(
+            vec![self.len()],
+            self.len(),
+            left.clone(),
+            Rule::ConditionalProofAssumption,
+            vec![],
+        );
+        lines.push(assumption);
+        // Then we need to construct a proof for the right side using the assumption
+        let deduction_lines = match self.search_sub_proof(lines, Some(right.clone())) {
+            Ok(lines) => lines,
+            Err(_) => return,
+        };
+
+        let mut deduction_line_nums = Vec::new();
+        for l in deduction_lines.clone() {
+            deduction_line_nums.push(l.line_number);
+        }
+        // The right side may already have been available before the assumption was made
+        let consequent = self
+            .node
+            .lines
+            .iter()
+            .chain(deduction_lines.iter())
+            .rev()
+            .find(|l| l.matches_expression(right))
+            .unwrap();
+        if !deduction_line_nums.contains(&consequent.line_number) {
+            deduction_line_nums.push(consequent.line_number);
+        }
+        let final_line = Line::new(
+            discharge(consequent, self.len()),
+            deduction_lines.last().unwrap().line_number + 1,
+            Expression::Implies(left.clone().wrap(), right.clone().wrap()),
+            Rule::ConditionalProof,
+            deduction_line_nums,
+        );
+        let mut deduction_lines = deduction_lines.clone();
+        deduction_lines.push(final_line);
+        let possible = Possible::new(deduction_lines);
+        self.add_possible(possible);
+    }
+
+    fn possible_raa(&mut self) {
+        // Reductio is only tried at the start of a (sub) proof, straight after the premises or the
+        // assumption that opened it. Trying it after every step would repeat the same inner
+        // searches with more and more irrelevant lines.
+        let starts_proof = self.node.lines.last().is_some_and(|line| {
+            matches!(
+                line.rule,
+                Rule::Assumption
+                    | Rule::ConditionalProofAssumption
+                    | Rule::OrEliminationAssumption
+                    | Rule::ReductioAdAbsurdiumAssumption
+            )
+        });
+        if !starts_proof || self.open_subproofs().len() >= MAX_OPEN_SUBPROOFS {
+            return;
+        }
+        // Reductio is a last resort, so skip it when another rule already reaches the goal
+        let reached = self.possibles.iter().any(|possible| {
+            possible
+                .lines
+                .last()
+                .is_some_and(|line| line.matches_expression(&self.node.conclusion))
+        });
+        if reached {
+            return;
+        }
+        // To prove -A we assume A. A sentence letter G is proved by assuming -G and finishing
+        // with double negation; other goals are better served by their own introduction rules.
+        let (assumed, negated_goal) = match &self.node.conclusion {
+            Expression::Not(inner) => (inner.as_ref().clone(), self.node.conclusion.clone()),
+            goal @ Expression::Var(_) => {
+                let assumed = Expression::Not(goal.clone().wrap());
+                (assumed.clone(), Expression::Not(assumed.wrap()))
             }
-            let final_line = Line::new(
-                assumptions,
-                deduction_lines.last().unwrap().line_number + 1,
+            _ => return,
+        };
+        // There is no point looking for a contradiction that does not exist
+        let mut context = self
+            .node
+            .lines
+            .iter()
+            .map(|line| line.expression.clone())
+            .collect::<Vec<Expression>>();
+        context.push(assumed.clone());
+        if sat::find_model(&context).is_some() {
+            return;
+        }
+        let assumption = Line::new(
+            vec![self.len()],
+            self.len(),
+            assumed.clone(),
+            Rule::ReductioAdAbsurdiumAssumption,
+            vec![],
+        );
+        let mut lines = self.node.lines.clone();
+        lines.push(assumption);
+
+        // Any contradiction will do, so the sub proof stops at the first `X & -X` it derives
+        let mut proof = Proof::sub_proof(
+            &self.node,
+            Expression::And(
+                assumed.clone().wrap(),
+                Expression::Not(assumed.wrap()).wrap(),
+            ),
+            lines.clone(),
+            self.node.settings.subproof_share * REDUCTIO_SHARE,
+        )
+        .seeking_contradiction();
+        if proof.search().is_err() {
+            return;
+        }
+        let mut deduction_lines = proof.lines.split_off(self.len());
+        let contradiction_line = lines
+            .iter()
+            .chain(deduction_lines.iter())
+            .rev()
+            .find(|l| is_contradiction(&l.expression))
+            .unwrap()
+            .clone();
+        let raa_line = Line::new(
+            discharge(&contradiction_line, self.len()),
+            self.len() + deduction_lines.len(),
+            negated_goal,
+            Rule::ReductioAdAbsurdium,
+            vec![self.len(), contradiction_line.line_number],
+        );
+        deduction_lines.push(raa_line.clone());
+        if !raa_line.matches_expression(&self.node.conclusion) {
+            deduction_lines.push(Line::new(
+                raa_line.assumption_lines.clone(),
+                raa_line.line_number + 1,
                 self.node.conclusion.clone(),
-                Rule::ConditionalProof,
-                deduction_line_nums,
-            );
-            if rand < 5 {
-                println!("Final Line: \n{}", final_line);
-            }
-            let mut deduction_lines = deduction_lines.clone();
-            deduction_lines.push(final_line);
-            let possible = Possible::new(deduction_lines);
-            self.add_possible(possible);
+                Rule::DoubleNegation,
+                vec![raa_line.line_number],
+            ));
         }
+        self.add_possible(Possible::new(deduction_lines));
+    }
+
+    /// Returns `false` if `goal` cannot follow from the lines so far together with `assumption`,
+    /// in which case a sub proof of it is bound to fail. Lines inside closed sub proofs are
+    /// counted as well, which can only make the check more lenient.
+    fn may_follow(&self, assumption: &Expression, goal: &Expression) -> bool {
+        let mut context = self
+            .node
+            .lines
+            .iter()
+            .map(|line| line.expression.clone())
+            .collect::<Vec<Expression>>();
+        context.push(assumption.clone());
+        sat::is_valid(&context, goal)
+    }
+
+    /// Returns the assumption lines of sub proofs that have been opened but not yet discharged.
+    fn open_subproofs(&self) -> Vec<Line> {
+        self.node
+            .lines
+            .iter()
+            .filter(|line| {
+                let Some(closing_rule) = line.rule.closing_rule() else {
+                    return false;
+                };
+                !self.node.lines.iter().skip(line.line_number).any(|l| {
+                    l.rule == closing_rule && l.deduction_lines.contains(&line.line_number)
+                })
+            })
+            .cloned()
+            .collect()
     }
 
     fn search_sub_proof(
-        &self,
+        &mut self,
         lines: Vec<Line>,
         conclusion: Option<Expression>,
     ) -> Result<Vec<Line>, ()> {
-        let mut proof = Proof::new_raw(
-            self.node.assumptions().clone(),
+        let mut proof = Proof::sub_proof(
+            &self.node,
             conclusion.unwrap_or(self.node.conclusion.clone()),
             lines,
-            INNER_SEARCH_SETTINGS,
-        );
+            self.node.settings.subproof_share,
+        )
+        .harvesting_lemmas(self.len());
         let result = proof.search();
+        // Whatever the sub proof derived without its own assumption holds out here as well
+        for lemma in proof.take_lemmas() {
+            self.add_possible(Possible::new(lemma));
+        }
         match result {
-            Ok(_) => Ok(proof.get_deduction_lines()),
+            // Only the lines after the ones we started with belong to the sub proof
+            Ok(_) => Ok(proof.lines.split_off(self.len())),
             Err(_) => Err(()),
         }
     }
@@ -438,7 +854,8 @@ impl PossibleFinder {
     fn iterate_lines_in_dimension(&self, dimension: usize) -> impl Iterator<Item = Vec<&Line>> {
         // Create an array to store the current indices for each dimension.
         let mut indices = vec![0; dimension];
-        let mut is_done = false;
+        // With no lines there is nothing to combine
+        let mut is_done = self.node.lines.is_empty();
 
         std::iter::from_fn(move || {
             if is_done {
@@ -473,45 +890,78 @@ impl PossibleFinder {
 #[derive(Debug, Clone)]
 pub struct Possible {
     pub lines: Vec<Line>,
+    /// The ids of the expressions of `lines` in the search's arena, filled in by
+    /// `PossibleFinder::find`
+    pub(crate) ids: Vec<ExprId>,
 }
 
 impl Possible {
     pub fn new(lines: Vec<Line>) -> Self {
-        Possible { lines }
+        Possible {
+            lines,
+            ids: Vec::new(),
+        }
     }
 
     pub fn new_single(line: Line) -> Self {
-        Possible { lines: vec![line] }
+        Possible::new(vec![line])
     }
 }
 
-fn find_vars_for_expression(expression: &Expression, vars: &mut Vec<char>) {
-    let expressions = expression.list_expressions();
-    for expression in expressions {
-        if let Expression::Var(var) = expression {
-            if !vars.contains(&var) {
-                vars.push(var);
-            }
+/// The assumptions `line` depends on once the sub proof opened at line `assumption` is closed.
+fn discharge(line: &Line, assumption: usize) -> Vec<usize> {
+    line.assumption_lines
+        .iter()
+        .copied()
+        .filter(|a| *a != assumption)
+        .collect()
+}
+
+/// Flattens nested disjunctions into their disjuncts, left to right.
+fn disjuncts(expression: &Expression) -> Vec<Expression> {
+    match expression {
+        Expression::Or(left, right) => {
+            let mut result = disjuncts(left);
+            result.extend(disjuncts(right));
+            result
         }
+        _ => vec![expression.clone()],
     }
-    vars.sort();
-    vars.dedup();
+}
+
+/// Moves `lines` that were numbered from `start` down by `offset`, updating every reference to
+/// lines at or after `start` so citations stay consistent.
+fn shift_lines(lines: Vec<Line>, start: usize, offset: usize) -> Vec<Line> {
+    let shift = |n: &mut usize| {
+        if *n >= start {
+            *n += offset;
+        }
+    };
+    lines
+        .into_iter()
+        .map(|mut line| {
+            shift(&mut line.line_number);
+            line.deduction_lines.iter_mut().for_each(shift);
+            line.assumption_lines.iter_mut().for_each(shift);
+            line
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use crate::expression::Expression;
 
-    use super::find_vars_for_expression;
-
     #[test]
     fn check_vars() {
-        let expression = Expression::Or(Expression::Var('Q').wrap(), Expression::Var('P').wrap());
-        let expression_2 = Expression::Or(Expression::Var('P').wrap(), Expression::Var('Q').wrap());
-        let mut vars = Vec::new();
-        let mut vars_2 = Vec::new();
-        find_vars_for_expression(&expression, &mut vars);
-        find_vars_for_expression(&expression_2, &mut vars_2);
-        assert_eq!(vars, vars_2);
+        let expression = Expression::Or(
+            Expression::Var('Q'.into()).wrap(),
+            Expression::Var('P'.into()).wrap(),
+        );
+        let expression_2 = Expression::Or(
+            Expression::Var('P'.into()).wrap(),
+            Expression::Var('Q'.into()).wrap(),
+        );
+        assert_eq!(expression.letters(), expression_2.letters());
     }
 }
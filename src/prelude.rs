@@ -0,0 +1,41 @@
+//! The supported public API, for glob import with `use propositional_logic_calculator::prelude::*`.
+//!
+//! Everything here is meant to stay stable between releases. The search internals, such as
+//! `SearchNode` and the `possible` module, are public only so that the search can be inspected
+//! and tested step by step, and change whenever the search does.
+//!
+//! # Examples
+//!
+//! ```
+//! use propositional_logic_calculator::prelude::*;
+//!
+//! let mut proof = Proof::new(
+//!     vec![parse_expression("P>Q").unwrap(), parse_expression("P").unwrap()],
+//!     parse_expression("Q").unwrap(),
+//! );
+//! proof.search().unwrap();
+//! let output = Lemmon.render(&proof, &NotationConfig::default());
+//! assert!(output.contains("MPP"));
+//! ```
+
+pub use crate::{
+    block::{to_blocks, to_lines, ProofBlock},
+    cnf::{Clause, Literal, Tseitin},
+    error::{
+        ArgumentError, EvalError, Inconsistent, ParserError, ProofError, SmtLibError, TptpError,
+    },
+    expression::Expression,
+    fold::{fold_operands, visit_operands, Fold, Visitor},
+    lexer::{AtomMode, Lexer, Token, TokenKind},
+    lines::{Line, Rule},
+    normalize::Normalization,
+    parser::{parse_latex, parse_polish, Parser, ParserOptions, PolishStyle},
+    proof::{
+        parse_argument, parse_expression, AtomUniverse, IterationUnit, NearMiss, OrIntroduction,
+        Proof, ProofSearch, SearchSettings, SearchState, SearchStats,
+    },
+    render::{Fitch, Html, Latex, Lemmon, Markdown, NotationConfig, ProofRenderer, Terminal},
+    semantics::{count_models, entails, entails_set, Classification, ConclusionMode, Model},
+    smtlib::parse_smtlib,
+    tptp::TptpProblem,
+};
@@ -0,0 +1,146 @@
+use std::{fs, io, path::Path};
+
+use crate::{
+    error::ProblemError,
+    expression::Expression,
+    proof::{parse_argument, IterationUnit, OrIntroduction, Proof, SearchSettings},
+};
+
+/// One problem of a problem file, with the settings to search for its proof with.
+///
+/// A problem file has one argument per line, written as `parse_argument` reads it. Blank lines
+/// and lines starting with `#` are skipped. An argument may be followed by `;` and settings for
+/// its search, each written as `name=value` and separated by spaces, where the names are those
+/// of the fields of `SearchSettings`: `max_line_length`, `iterations`, `iteration_unit`
+/// (`expansions`, `candidates` or `rules`), `subproof_share`, `or_introduction` (`off`,
+/// `conclusion` or `all`), `max_candidates` and `max_formula_size`.
+/// Settings that are not given keep their defaults.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{problems::parse_problems, proof::parse_expression};
+///
+/// let problems = parse_problems(
+///     "# Chapter 1
+///      P>Q, P / Q
+///      PvQ, -P / Q ; iterations=2000 max_line_length=8",
+/// )
+/// .collect::<Result<Vec<_>, _>>()
+/// .unwrap();
+/// assert_eq!(problems[0].line, 2);
+/// assert_eq!(problems[1].conclusion, parse_expression("Q").unwrap());
+/// assert_eq!(problems[1].settings.iterations, 2000);
+///
+/// let mut proof = problems[0].proof();
+/// assert!(proof.search().is_ok());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Problem {
+    /// The line of the file the problem is on, counting from 1
+    pub line: usize,
+    pub premises: Vec<Expression>,
+    /// The conclusion, or `⊥` for a problem that asks for the premises to be shown inconsistent
+    pub conclusion: Expression,
+    pub settings: SearchSettings,
+}
+
+impl Problem {
+    /// A proof of the problem, ready to search with its settings. A problem with the conclusion
+    /// `⊥`, written `#`, gives a refutation of its premises.
+    pub fn proof(&self) -> Proof {
+        match self.conclusion {
+            Expression::Bottom => {
+                Proof::refutation_with_settings(self.premises.clone(), self.settings.clone())
+            }
+            _ => Proof::with_settings(
+                self.premises.clone(),
+                self.conclusion.clone(),
+                self.settings.clone(),
+            ),
+        }
+    }
+}
+
+/// Reads the problems in the text of a problem file, in the format described on `Problem`, one at
+/// a time. A line that cannot be read gives an error in its place, and the problems after it are
+/// still read.
+pub fn parse_problems(input: &str) -> impl Iterator<Item = Result<Problem, ProblemError>> + '_ {
+    input
+        .lines()
+        .enumerate()
+        .filter_map(|(i, text)| parse_problem(text, i + 1))
+}
+
+/// Reads the problems in the problem file at `path`, as `parse_problems` does.
+pub(crate) fn load_problems(
+    path: &Path,
+) -> io::Result<impl Iterator<Item = Result<Problem, ProblemError>>> {
+    let lines = fs::read_to_string(path)?
+        .lines()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    Ok(lines
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, text)| parse_problem(&text, i + 1)))
+}
+
+/// Reads the problem on line `line`, or returns `None` if the line is blank or a comment.
+fn parse_problem(text: &str, line: usize) -> Option<Result<Problem, ProblemError>> {
+    let text = text.trim();
+    if text.is_empty() || text.starts_with('#') {
+        return None;
+    }
+    let (argument, settings) = text.split_once(';').unwrap_or((text, ""));
+    Some(parse_settings(settings, line).and_then(|settings| {
+        let (premises, conclusion) =
+            parse_argument(argument).map_err(|err| ProblemError::Argument(err, line))?;
+        Ok(Problem {
+            line,
+            premises,
+            conclusion,
+            settings,
+        })
+    }))
+}
+
+/// Reads the settings written after the `;` of line `line`.
+fn parse_settings(text: &str, line: usize) -> Result<SearchSettings, ProblemError> {
+    let mut settings = SearchSettings::default();
+    for setting in text.split_whitespace() {
+        let (name, value) = setting
+            .split_once('=')
+            .ok_or_else(|| ProblemError::InvalidSetting(setting.to_string(), line))?;
+        let invalid = || ProblemError::InvalidSetting(setting.to_string(), line);
+        match name {
+            "max_line_length" => settings.max_line_length = value.parse().map_err(|_| invalid())?,
+            "iterations" => settings.iterations = value.parse().map_err(|_| invalid())?,
+            "iteration_unit" => {
+                settings.iteration_unit = match value {
+                    "expansions" => IterationUnit::Expansions,
+                    "candidates" => IterationUnit::Candidates,
+                    "rules" => IterationUnit::RuleApplications,
+                    _ => return Err(invalid()),
+                }
+            }
+            "subproof_share" => settings.subproof_share = value.parse().map_err(|_| invalid())?,
+            "or_introduction" => {
+                settings.or_introduction = match value {
+                    "off" => OrIntroduction::Off,
+                    "conclusion" => OrIntroduction::ConclusionSubformulas,
+                    "all" => OrIntroduction::AllVariables,
+                    _ => return Err(invalid()),
+                }
+            }
+            "max_candidates" => {
+                settings.max_candidates = Some(value.parse().map_err(|_| invalid())?)
+            }
+            "max_formula_size" => {
+                settings.max_formula_size = Some(value.parse().map_err(|_| invalid())?)
+            }
+            _ => return Err(ProblemError::UnknownSetting(name.to_string(), line)),
+        }
+    }
+    Ok(settings)
+}
@@ -1,26 +1,264 @@
 use crate::{
-    error::{ParserError, ProofError},
-    lines::{Line, Rule},
-    possible::PossibleFinder,
+    arena::{ExprArena, ExprId},
+    error::{ArgumentError, ParserError, ProblemError, ProofError, TraceDivergence},
+    lines::{Line, Provenance, Rule},
+    metadata::Metadata,
+    possible::{Possible, PossibleFinder},
+    problems::{self, Problem},
+    render::{subproof_depths, Citations, NotationConfig},
+    trace::{Trace, TraceEvent},
 };
 use std::{
-    cell::RefCell,
-    collections::VecDeque,
+    cell::{Cell, RefCell},
+    collections::{HashSet, VecDeque},
     fmt::{self, Display},
+    io,
+    path::Path,
     rc::Rc,
+    time::Instant,
 };
 
-use crate::{expression::Expression, parser::Parser};
+use crate::{
+    expression::{Expression, Symbol},
+    parser::Parser,
+};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct SearchSettings {
     pub max_line_length: usize,
+    /// Total iterations for the search, including those spent in sub proof searches, counted in
+    /// `iteration_unit`
     pub iterations: usize,
+    /// What one iteration of the budget is
+    pub iteration_unit: IterationUnit,
+    /// Fraction of the remaining iterations that a single sub proof search may use
+    pub subproof_share: f64,
+    /// Which disjuncts vI may add to a single line
+    pub or_introduction: OrIntroduction,
+    /// The sentence letters the search may bring into a proof
+    pub atoms: AtomUniverse,
+    /// Most candidates expanded from each node. The most promising are kept: those that reach
+    /// or appear in the goal, then the smallest.
+    pub max_candidates: Option<usize>,
+    /// Largest formula, as counted by `Expression::size`, that a candidate line may derive. The
+    /// goal is derived whatever its size.
+    pub max_formula_size: Option<usize>,
+}
+
+/// The work that `SearchSettings::iterations` counts, each kept in `SearchStats` whichever is
+/// chosen. Sub proof searches count theirs towards the same budget.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IterationUnit {
+    /// Each node of the search tree expanded
+    #[default]
+    Expansions,
+    /// Each candidate produced by expanding a node
+    Candidates,
+    /// Each line of those candidates, which is one application of a rule
+    RuleApplications,
+}
+
+/// Counters kept over a search, including its sub proof searches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    /// Nodes of the search tree expanded
+    pub expansions: usize,
+    /// Candidates produced by expanding nodes, after `max_candidates` dropped any
+    pub candidates: usize,
+    /// Lines of those candidates, each derived by one rule
+    pub rule_applications: usize,
+    /// Nodes that produced more than `max_candidates` candidates
+    pub capped_nodes: usize,
+    /// Candidates left unexplored because of `max_candidates`
+    pub dropped_candidates: usize,
+}
+
+/// A sub proof (CP, vE or RAA) whose search did not reach its goal, counted over every attempt
+/// with the same assumption and goal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubProofFailure {
+    /// The rule of the assumption that opened the sub proof
+    pub rule: Rule,
+    pub assumption: Expression,
+    /// The goal searched for, or `None` for a reductio, which accepts any contradiction
+    pub goal: Option<Expression>,
+    /// How many sub proofs it was nested in, 1 for one opened by the top-level search
+    pub depth: usize,
+    pub attempts: usize,
+    /// Iterations used by all the attempts together, including their own sub proofs
+    pub iterations: usize,
+}
+
+impl Display for SubProofFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Assuming {} ({}) ", self.assumption, self.rule)?;
+        match &self.goal {
+            Some(goal) => write!(f, "did not lead to {}", goal)?,
+            None => write!(f, "did not lead to a contradiction")?,
+        }
+        write!(
+            f,
+            " in {} attempt(s) using {} iteration(s)",
+            self.attempts, self.iterations
+        )
+    }
+}
+
+/// A line that the top-level search derived on the way to a proof it did not find, kept because
+/// its expression came close to the conclusion. Recorded once `Proof::record_near_misses` is
+/// called.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NearMiss {
+    /// The lines of the branch the line was derived on, ending with the line itself
+    pub lines: Vec<Line>,
+    /// `Expression::distance` from the line's expression to the conclusion
+    pub distance: usize,
+}
+
+impl NearMiss {
+    /// The line that came close to the conclusion.
+    pub fn line(&self) -> &Line {
+        self.lines.last().unwrap()
+    }
+}
+
+impl Display for NearMiss {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let line = self.line();
+        write!(
+            f,
+            "Line {} derived {} ({}), {} edit(s) from the conclusion",
+            self.lines.len(),
+            line.expression,
+            line.rule,
+            self.distance
+        )
+    }
+}
+
+/// The closest lines found so far, nearest first, with at most `limit` of them.
+#[derive(Debug, Clone)]
+struct NearMisses {
+    limit: usize,
+    misses: Vec<NearMiss>,
+}
+
+impl NearMisses {
+    /// Keeps the last of `lines` if it is among the closest to `goal` seen so far. `lines` is only
+    /// called when it is, so the branch is not copied for every candidate.
+    fn offer(&mut self, goal: &Expression, last: &Expression, lines: impl FnOnce() -> Vec<Line>) {
+        if self.limit == 0 {
+            return;
+        }
+        let distance = last.distance(goal);
+        if self.misses.len() == self.limit && self.misses.last().unwrap().distance <= distance {
+            return;
+        }
+        // The search is breadth first, so the branch already kept for this expression is no longer
+        // than this one
+        if self
+            .misses
+            .iter()
+            .any(|miss| &miss.line().expression == last)
+        {
+            return;
+        }
+        let index = self
+            .misses
+            .partition_point(|miss| miss.distance <= distance);
+        self.misses.insert(
+            index,
+            NearMiss {
+                lines: lines(),
+                distance,
+            },
+        );
+        self.misses.truncate(self.limit);
+    }
+}
+
+/// What tells two proofs of the same sequent apart for `Proof::exclude`: the rules used on the
+/// lines the last line depends on, counted with repeats, and how the last line is derived. Lines
+/// that nothing leads from to the last line are ignored, so padding a proof with them does not
+/// make it a different proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofShape {
+    rules: Vec<Rule>,
+    last: Option<(Rule, Vec<usize>)>,
+}
+
+impl ProofShape {
+    pub fn of(lines: &[Line]) -> Self {
+        let mut used = vec![false; lines.len()];
+        let mut pending = lines.len().checked_sub(1).into_iter().collect::<Vec<_>>();
+        while let Some(n) = pending.pop() {
+            if used[n] {
+                continue;
+            }
+            used[n] = true;
+            pending.extend(&lines[n].deduction_lines);
+        }
+        let mut rules = lines
+            .iter()
+            .zip(&used)
+            .filter(|(_, used)| **used)
+            .map(|(line, _)| line.rule.clone())
+            .collect::<Vec<_>>();
+        rules.sort();
+        // Citations are counted among the used lines only, as if the others were taken out
+        let renumber = |n: &usize| used[..*n].iter().filter(|used| **used).count();
+        let last = lines.last().map(|line| {
+            let citations = line.deduction_lines.iter().map(renumber).collect();
+            (line.rule.clone(), citations)
+        });
+        ProofShape { rules, last }
+    }
+}
+
+/// The disjuncts that vI may join to a line on its own, as in `X v D` or `D v X` from `X`. This
+/// does not affect vI joining two existing lines.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OrIntroduction {
+    /// Never join a disjunct to a single line
+    Off,
+    /// Only subformulas of the goal being searched for
+    #[default]
+    ConclusionSubformulas,
+    /// Every sentence letter in the problem
+    AllVariables,
+}
+
+/// The sentence letters the search may introduce, as vI does when it joins a disjunct to a line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum AtomUniverse {
+    /// Only the sentence letters that occur in the sequent
+    #[default]
+    Sequent,
+    /// Only these sentence letters, whether or not the sequent uses them
+    Only(Vec<Symbol>),
+    /// The sentence letters of the sequent and these
+    Extended(Vec<Symbol>),
+}
+
+impl AtomUniverse {
+    /// The letters that may be introduced in a proof whose sequent uses `sequent`, sorted and
+    /// without duplicates.
+    pub fn atoms(&self, sequent: &[Symbol]) -> Vec<Symbol> {
+        let mut atoms = match self {
+            AtomUniverse::Sequent => sequent.to_vec(),
+            AtomUniverse::Only(atoms) => atoms.clone(),
+            AtomUniverse::Extended(atoms) => sequent.iter().chain(atoms).copied().collect(),
+        };
+        atoms.sort();
+        atoms.dedup();
+        atoms
+    }
 }
 
 impl SearchSettings {
     const DEFAULT_MAX_LINE_LENGTH: usize = 15;
     const DEFAULT_ITERATIONS: usize = 50000;
+    const DEFAULT_SUBPROOF_SHARE: f64 = 0.5;
 }
 
 impl Default for SearchSettings {
@@ -28,6 +266,12 @@ impl Default for SearchSettings {
         Self {
             max_line_length: Self::DEFAULT_MAX_LINE_LENGTH,
             iterations: Self::DEFAULT_ITERATIONS,
+            iteration_unit: IterationUnit::default(),
+            subproof_share: Self::DEFAULT_SUBPROOF_SHARE,
+            or_introduction: OrIntroduction::default(),
+            atoms: AtomUniverse::default(),
+            max_candidates: None,
+            max_formula_size: None,
         }
     }
 }
@@ -38,7 +282,34 @@ pub struct Proof {
     conclusion: Expression,
     pub(crate) lines: Vec<Line>,
     settings: Rc<SearchSettings>,
-    iterations: usize,
+    /// Shared with the searches of any sub proofs so they count against the same budget
+    iterations: Rc<Cell<usize>>,
+    stats: Rc<Cell<SearchStats>>,
+    /// Accept any contradiction (`X & -X`) in place of the conclusion, as a reductio does
+    contradiction: bool,
+    lemmas: Option<Lemmas>,
+    /// How many sub proof searches this one is nested in
+    depth: usize,
+    /// When the top-level search started, if this is a sub proof of it
+    started: Option<Instant>,
+    /// How the proof is displayed
+    notation: NotationConfig,
+    /// Shared with the searches of any sub proofs so they record into the same trace
+    trace: Option<Rc<RefCell<Trace>>>,
+    /// Shapes of earlier proofs that later searches must not find again
+    excluded: Vec<ProofShape>,
+    /// Shared with the searches of any sub proofs so they all report their failures here
+    failures: Rc<RefCell<Vec<SubProofFailure>>>,
+    /// Lines of the top-level search closest to the conclusion, if they are being recorded
+    near_misses: Option<NearMisses>,
+}
+
+/// Lines that a sub proof search derived without using any line from `start` onwards, each kept
+/// with the lines it was derived from so the search above can reuse it.
+#[derive(Debug, Clone)]
+pub(crate) struct Lemmas {
+    start: usize,
+    derivations: Vec<Vec<Line>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -64,6 +335,8 @@ impl Display for SearchState {
     }
 }
 
+/// A state of the search tree. Not part of the supported API, see `prelude`.
+#[doc(hidden)]
 #[derive(Debug, Clone)]
 pub struct SearchNode {
     pub parent: Option<Rc<RefCell<Self>>>,
@@ -71,6 +344,25 @@ pub struct SearchNode {
     pub lines: Vec<Line>,
     pub conclusion: Expression,
     pub settings: Rc<SearchSettings>,
+    pub contradiction: bool,
+    pub(crate) iterations: Rc<Cell<usize>>,
+    pub(crate) stats: Rc<Cell<SearchStats>>,
+    /// Value of `iterations` at which this node's search gives up
+    pub(crate) iteration_limit: usize,
+    /// How many sub proof searches this node's search is nested in
+    pub(crate) depth: usize,
+    /// When the top-level search started
+    pub(crate) started: Instant,
+    /// Interned copies of the expressions seen by this node's search
+    pub(crate) arena: Rc<RefCell<ExprArena>>,
+    /// The ids in `arena` of the expressions of `lines`, extended along with them
+    pub(crate) line_ids: HashSet<ExprId>,
+    /// The id in `arena` of `conclusion`
+    pub(crate) goal: ExprId,
+    pub(crate) trace: Option<Rc<RefCell<Trace>>>,
+    /// Shapes of proofs this node's search skips over when it reaches the goal
+    pub(crate) excluded: Rc<Vec<ProofShape>>,
+    pub(crate) failures: Rc<RefCell<Vec<SubProofFailure>>>,
 }
 
 impl Proof {
@@ -85,10 +377,68 @@ impl Proof {
             conclusion,
             lines,
             settings: Rc::new(settings),
-            iterations: 0,
+            iterations: Rc::new(Cell::new(0)),
+            stats: Rc::new(Cell::new(SearchStats::default())),
+            contradiction: false,
+            lemmas: None,
+            depth: 0,
+            started: None,
+            notation: NotationConfig::default(),
+            trace: None,
+            excluded: Vec::new(),
+            failures: Rc::new(RefCell::new(Vec::new())),
+            near_misses: None,
         }
     }
 
+    /// Makes the search keep the lines it derives that do not use any line from `start` onwards,
+    /// whether or not it finds the conclusion. These are returned by `take_lemmas`.
+    pub(crate) fn harvesting_lemmas(mut self, start: usize) -> Self {
+        self.lemmas = Some(Lemmas {
+            start,
+            derivations: Vec::new(),
+        });
+        self
+    }
+
+    /// The derivations of the lemmas found by the last search, numbered to follow on from line
+    /// `start`.
+    pub(crate) fn take_lemmas(&mut self) -> Vec<Vec<Line>> {
+        self.lemmas
+            .as_mut()
+            .map(|lemmas| std::mem::take(&mut lemmas.derivations))
+            .unwrap_or_default()
+    }
+
+    /// Creates the proof searched for a sub proof of `node`. Its iterations are counted against
+    /// the budget of `node`'s search, of which it may use `share` of what is left.
+    pub(crate) fn sub_proof(
+        node: &SearchNode,
+        conclusion: Expression,
+        lines: Vec<Line>,
+        share: f64,
+    ) -> Self {
+        let remaining = node.iteration_limit.saturating_sub(node.iterations.get());
+        let settings = SearchSettings {
+            iterations: (remaining as f64 * share) as usize,
+            ..node.settings.as_ref().clone()
+        };
+        let mut proof = Proof::new_raw(node.assumptions(), conclusion, lines, settings);
+        proof.iterations = node.iterations.clone();
+        proof.stats = node.stats.clone();
+        proof.depth = node.depth + 1;
+        proof.started = Some(node.started);
+        proof.trace = node.trace.clone();
+        proof.failures = node.failures.clone();
+        proof
+    }
+
+    /// Makes the search stop at the first contradiction it derives rather than the conclusion.
+    pub(crate) fn seeking_contradiction(mut self) -> Self {
+        self.contradiction = true;
+        self
+    }
+
     pub fn new(assumptions: Vec<Expression>, conclusion: Expression) -> Self {
         Self::with_settings(assumptions, conclusion, SearchSettings::default())
     }
@@ -113,104 +463,348 @@ impl Proof {
         )
     }
 
+    /// Reads the problems in the problem file at `path`, in the format described on `Problem`,
+    /// one at a time. A line that cannot be read gives an error in its place.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the file cannot be read.
+    pub fn load_problems(
+        path: impl AsRef<Path>,
+    ) -> io::Result<impl Iterator<Item = Result<Problem, ProblemError>>> {
+        problems::load_problems(path.as_ref())
+    }
+
+    /// Creates a proof that `assumptions` are inconsistent, whose search stops at the first
+    /// contradiction (`X & -X` or `⊥`) it derives. Its conclusion is `⊥`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use propositional_logic_calculator::proof::{parse_argument, Proof};
+    ///
+    /// let (premises, _) = parse_argument("P>Q, P, -Q / #").unwrap();
+    /// let mut proof = Proof::refutation(premises);
+    /// proof.search().unwrap();
+    /// assert!(proof.is_refutation());
+    /// ```
+    pub fn refutation(assumptions: Vec<Expression>) -> Self {
+        Self::refutation_with_settings(assumptions, SearchSettings::default())
+    }
+
+    /// Creates a proof that `assumptions` are inconsistent, as `refutation` does, searched with
+    /// `settings`.
+    pub fn refutation_with_settings(
+        assumptions: Vec<Expression>,
+        settings: SearchSettings,
+    ) -> Self {
+        Self::with_settings(assumptions, Expression::Bottom, settings).seeking_contradiction()
+    }
+
+    /// Returns `true` if this proof shows its assumptions are inconsistent rather than proving a
+    /// conclusion, as one made by `refutation` does.
+    pub fn is_refutation(&self) -> bool {
+        self.contradiction
+    }
+
     pub fn search(&mut self) -> Result<(), ProofError> {
+        let mut search = self.start_search();
+        loop {
+            match search.step() {
+                SearchState::Searching => (),
+                SearchState::FinishedProof => return Ok(()),
+                state => return Err(ProofError::SearchError(state)),
+            }
+        }
+    }
+
+    /// Starts a search that the caller drives one step at a time with `ProofSearch::step`,
+    /// rather than running it to the end as `search` does. The proof's lines are replaced once
+    /// the search finds the conclusion.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use propositional_logic_calculator::proof::{parse_expression, Proof, SearchState};
+    ///
+    /// let mut proof = Proof::new(
+    ///     vec![parse_expression("P&Q").unwrap()],
+    ///     parse_expression("Q&P").unwrap(),
+    /// );
+    /// let mut search = proof.start_search();
+    /// let mut steps = 0;
+    /// while search.step() == SearchState::Searching {
+    ///     steps += 1;
+    /// }
+    /// assert_eq!(search.state(), SearchState::FinishedProof);
+    /// assert!(steps > 0);
+    /// ```
+    pub fn start_search(&mut self) -> ProofSearch<'_> {
         let head = SearchNode::new(
             self.lines.clone(),
             self.conclusion.clone(),
             self.settings.clone(),
         );
-        self.iterations = 0;
-        let result = search(head.clone(), self);
-        match result {
-            Ok(result) => {
-                self.lines = result;
-                Ok(())
+        {
+            let mut head = head.borrow_mut();
+            head.contradiction = self.contradiction;
+            head.iterations = self.iterations.clone();
+            head.stats = self.stats.clone();
+            head.iteration_limit = self.iterations.get() + self.settings.iterations;
+            head.depth = self.depth;
+            head.started = self.started.unwrap_or_else(Instant::now);
+            head.trace = self.trace.clone();
+            head.excluded = Rc::new(self.excluded.clone());
+            head.failures = self.failures.clone();
+        }
+        if let Some(near_misses) = self.near_misses.as_mut() {
+            for end in 1..=self.lines.len() {
+                near_misses.offer(&self.conclusion, &self.lines[end - 1].expression, || {
+                    self.lines[..end].to_vec()
+                });
             }
-            Err(err) => Err(err),
+        }
+        ProofSearch {
+            // The last line a sub proof starts with is the assumption that opened it
+            opened_by: self.lines.last().cloned(),
+            iterations_before: self.iterations.get(),
+            queue: VecDeque::from([head.clone()]),
+            head,
+            state: SearchState::Searching,
+            proof: self,
         }
     }
 
-    /// Get all lines that are not assumptions
-    pub fn get_deduction_lines(&self) -> Vec<Line> {
-        self.lines
+    /// Adds a failed attempt at this sub proof, opened by `assumption`, to the shared failures.
+    fn record_failure(&self, assumption: Line, iterations: usize) {
+        let goal = (!self.contradiction).then(|| self.conclusion.clone());
+        let mut failures = self.failures.borrow_mut();
+        let existing = failures.iter_mut().find(|failure| {
+            failure.rule == assumption.rule
+                && failure.assumption == assumption.expression
+                && failure.goal == goal
+                && failure.depth == self.depth
+        });
+        match existing {
+            Some(failure) => {
+                failure.attempts += 1;
+                failure.iterations += iterations;
+            }
+            None => failures.push(SubProofFailure {
+                rule: assumption.rule,
+                assumption: assumption.expression,
+                goal,
+                depth: self.depth,
+                attempts: 1,
+                iterations,
+            }),
+        }
+    }
+
+    /// The sub proofs that the searches so far tried and could not finish, in the order they
+    /// first failed. When a search fails, these show which subgoals it got stuck on.
+    pub fn sub_proof_failures(&self) -> Vec<SubProofFailure> {
+        self.failures.borrow().clone()
+    }
+
+    /// Makes later searches keep the `count` lines they derive that come closest to the
+    /// conclusion, by `Expression::distance`, along with the branch each was derived on. The
+    /// assumptions count as lines too. Only lines of this proof's own search are kept, not those
+    /// of its sub proofs, which search for other goals. The lines are returned by `near_misses`.
+    pub fn record_near_misses(&mut self, count: usize) {
+        self.near_misses = Some(NearMisses {
+            limit: count,
+            misses: Vec::new(),
+        });
+    }
+
+    /// The lines closest to the conclusion over the searches since `record_near_misses` was
+    /// called, nearest first. When a search fails, these show how close it came.
+    pub fn near_misses(&self) -> Vec<NearMiss> {
+        self.near_misses
+            .as_ref()
+            .map(|near_misses| near_misses.misses.clone())
+            .unwrap_or_default()
+    }
+
+    /// Makes later searches look for a proof with a different shape from `proof`, which should be
+    /// an earlier proof of the same sequent. The search fails if every proof it can find has the
+    /// shape of an excluded one. Proofs are compared by `ProofShape`, so calling this after each
+    /// search gives a new way of proving the sequent each time.
+    pub fn exclude(&mut self, proof: &Proof) {
+        self.excluded.push(ProofShape::of(&proof.lines));
+    }
+
+    /// Counters kept over the searches so far, including those of sub proof searches.
+    pub fn stats(&self) -> SearchStats {
+        self.stats.get()
+    }
+
+    /// Makes later searches record everything they do, including in sub proof searches. The
+    /// recording is returned by `trace`.
+    pub fn record_trace(&mut self) {
+        self.trace = Some(Rc::new(RefCell::new(Trace::new(
+            self.settings.as_ref().clone(),
+        ))));
+    }
+
+    /// The trace recorded since `record_trace` was called.
+    pub fn trace(&self) -> Option<Trace> {
+        self.trace.as_ref().map(|trace| trace.borrow().clone())
+    }
+
+    /// Searches again with the settings recorded in `trace`, checking that the search does exactly
+    /// what the trace says it did. The proof should be for the same sequent the trace was recorded
+    /// from and not have been searched yet.
+    pub fn replay(&mut self, trace: &Trace) -> Result<(), TraceDivergence> {
+        self.settings = Rc::new(trace.settings.clone());
+        self.record_trace();
+        // Whether the proof was found is already part of what the trace records
+        let _ = self.search();
+        let replayed = self.trace().unwrap().events;
+        let index = trace
+            .events
             .iter()
-            .filter(|x| x.rule != Rule::Assumption)
-            .cloned()
-            .collect()
+            .zip(replayed.iter())
+            .position(|(expected, found)| expected != found)
+            .unwrap_or(trace.events.len().min(replayed.len()));
+        if index == trace.events.len() && index == replayed.len() {
+            return Ok(());
+        }
+        Err(TraceDivergence {
+            index,
+            expected: trace.events.get(index).cloned(),
+            found: replayed.get(index).cloned(),
+        })
     }
-}
 
-impl Display for Proof {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut nested_proof_level = 0;
-
-        writeln!(f, "Assumptions: [{}]", join_expressions(&self.assumptions))?;
-        writeln!(f, "Conclusion: {}", self.conclusion)?;
-        writeln!(f, "Total Proof Steps: {}", self.lines.len())?;
-        writeln!(f, "Proof Steps:")?;
-
-        for line in &self.lines {
-            // Check if the line starts or ends a nested proof
-            match line.rule {
-                Rule::OrEliminationAssumption => nested_proof_level += 1,
-                Rule::OrElimination => nested_proof_level -= 1,
-                Rule::ConditionalProofAssumption => nested_proof_level += 1,
-                Rule::ConditionalProof => nested_proof_level -= 1,
-                _ => (),
+    /// The number of iterations searched so far, including those of sub proof searches.
+    pub fn iterations(&self) -> usize {
+        self.iterations.get()
+    }
+
+    /// Combines this proof and `other` into one derivation: the premises of both, each once, this
+    /// proof's lines and then `other`'s, with citations renumbered to match. A line of `other`
+    /// that derives the same expression from the same assumptions as an earlier line is left out,
+    /// and the earlier line is cited in its place, as are lines of `other` that are then no longer
+    /// needed. The result proves `other`'s conclusion and keeps this proof's settings and
+    /// notation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use propositional_logic_calculator::proof::{parse_expression, Proof};
+    ///
+    /// let premises = || vec![parse_expression("P&Q").unwrap(), parse_expression("P>R").unwrap()];
+    /// let mut first = Proof::new(premises(), parse_expression("R").unwrap());
+    /// first.search().unwrap();
+    /// let mut second = Proof::new(premises(), parse_expression("R&Q").unwrap());
+    /// second.search().unwrap();
+    ///
+    /// let merged = first.merge(&second);
+    /// assert_eq!(merged.conclusion(), second.conclusion());
+    /// // The second proof cites the first one's `R`, adding only the lines for `R&Q`
+    /// assert_eq!(merged.lines().len(), first.lines().len() + 2);
+    /// ```
+    pub fn merge(&self, other: &Proof) -> Proof {
+        let mut assumptions = self.assumptions.clone();
+        for assumption in &other.assumptions {
+            if !assumptions.contains(assumption) {
+                assumptions.push(assumption.clone());
             }
+        }
+        let mut merged = Proof::new_raw(
+            assumptions.clone(),
+            other.conclusion.clone(),
+            create_assumption_lines(assumptions),
+            self.settings.as_ref().clone(),
+        );
+        merged.contradiction = other.contradiction;
+        merged.notation = self.notation.clone();
+        merge_lines(&mut merged.lines, &self.lines);
+        let start = merged.lines.len();
+        let moved = merge_lines(&mut merged.lines, &other.lines);
+        if let Some(goal) = moved.last() {
+            remove_unused(&mut merged.lines, start, *goal);
+        }
+        note_assumptions(&mut merged.lines);
+        merged
+    }
 
-            // Apply indentation if in a nested proof
-            let indent = if nested_proof_level > 0 {
-                "  ".repeat(nested_proof_level)
-            } else {
-                "".to_string()
-            };
-            writeln!(f, "{}{}", indent, line)?;
+    /// All lines of the proof, premises included
+    pub fn lines(&self) -> &[Line] {
+        &self.lines
+    }
+
+    /// Attaches `comment` to the line numbered `line_number` (counting from 0, as in `Line`),
+    /// replacing any comment it already had. Returns `false` if there is no such line.
+    pub fn annotate(&mut self, line_number: usize, comment: impl Into<String>) -> bool {
+        match self.lines.get_mut(line_number) {
+            Some(line) => {
+                line.comment = Some(comment.into());
+                true
+            }
+            None => false,
         }
+    }
 
-        Ok(())
+    /// Names the line numbered `line_number` (counting from 0) so that citations can refer to it
+    /// by `label`. Returns `false` if there is no such line.
+    pub fn label(&mut self, line_number: usize, label: impl Into<String>) -> bool {
+        match self.lines.get_mut(line_number) {
+            Some(line) => {
+                line.label = Some(label.into());
+                true
+            }
+            None => false,
+        }
     }
-}
 
-fn join_expressions(expressions: &[Expression]) -> String {
-    expressions
-        .iter()
-        .map(ToString::to_string)
-        .collect::<Vec<String>>()
-        .join(", ")
-}
+    /// Sets how the displayed proof cites lines.
+    pub fn set_citations(&mut self, citations: Citations) {
+        self.notation.citations = citations;
+    }
 
-impl Display for Line {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // Base line format with assumption lines, line number, and expression
-        write!(
-            f,
-            "Line {}: {} [{}] using {}",
-            self.line_number + 1,
-            self.expression,
-            join(&self.assumption_lines),
-            self.rule,
-        )?;
+    /// Wraps displayed lines that are longer than `max_width` columns, continuing them on
+    /// further lines with a hanging indent. `None` (the default) never wraps.
+    pub fn set_max_width(&mut self, max_width: Option<usize>) {
+        self.notation.max_width = max_width;
+    }
 
-        // Append 'from lines' only if there are deduction lines
-        if !self.deduction_lines.is_empty() {
-            write!(f, " from lines {}", join(&self.deduction_lines))?;
-        }
+    /// Records `metadata` in the proof's LaTeX, HTML and Markdown output. `None` (the default)
+    /// records nothing.
+    pub fn set_metadata(&mut self, metadata: Option<Metadata>) {
+        self.notation.metadata = metadata;
+    }
 
-        Ok(())
+    /// The notation the proof is displayed with.
+    pub fn notation(&self) -> &NotationConfig {
+        &self.notation
     }
-}
 
-fn join(array: &[usize]) -> String {
-    let mut array = array.to_owned();
-    array.dedup();
-    array.sort();
-    array
-        .iter()
-        .map(|x| x + 1)
-        .map(|x| x.to_string())
-        .collect::<Vec<String>>()
-        .join(", ")
+    /// The settings the proof is searched with.
+    pub fn settings(&self) -> &SearchSettings {
+        &self.settings
+    }
+
+    /// The premises of the sequent being proved.
+    pub fn assumptions(&self) -> &[Expression] {
+        &self.assumptions
+    }
+
+    /// The conclusion of the sequent being proved.
+    pub fn conclusion(&self) -> &Expression {
+        &self.conclusion
+    }
+
+    /// Get all lines that are not assumptions
+    pub fn get_deduction_lines(&self) -> Vec<Line> {
+        self.lines
+            .iter()
+            .filter(|x| x.rule != Rule::Assumption)
+            .cloned()
+            .collect()
+    }
 }
 
 impl SearchNode {
@@ -219,17 +813,108 @@ impl SearchNode {
         conclusion: Expression,
         settings: Rc<SearchSettings>,
     ) -> Rc<RefCell<Self>> {
+        let mut arena = ExprArena::new();
+        let line_ids = lines
+            .iter()
+            .map(|line| arena.intern(&line.expression))
+            .collect();
+        let goal = arena.intern(&conclusion);
         Rc::new(RefCell::new(SearchNode {
             parent: None,
             children: vec![],
             lines,
             conclusion,
+            iteration_limit: settings.iterations,
             settings,
+            contradiction: false,
+            iterations: Rc::new(Cell::new(0)),
+            stats: Rc::new(Cell::new(SearchStats::default())),
+            depth: 0,
+            started: Instant::now(),
+            arena: Rc::new(RefCell::new(arena)),
+            line_ids,
+            goal,
+            trace: None,
+            excluded: Rc::new(Vec::new()),
+            failures: Rc::new(RefCell::new(Vec::new())),
         }))
     }
 
+    /// Creates a node that continues this one's search with the lines of `possible`, which
+    /// `PossibleFinder::find` gave it.
+    pub fn child(&self, possible: &Possible) -> Rc<RefCell<Self>> {
+        let mut lines = self.lines.clone();
+        lines.extend(self.stamp(&possible.lines));
+        let mut line_ids = self.line_ids.clone();
+        line_ids.extend(&possible.ids);
+        Rc::new(RefCell::new(SearchNode {
+            parent: None,
+            children: vec![],
+            lines,
+            conclusion: self.conclusion.clone(),
+            settings: self.settings.clone(),
+            contradiction: self.contradiction,
+            iterations: self.iterations.clone(),
+            stats: self.stats.clone(),
+            iteration_limit: self.iteration_limit,
+            depth: self.depth,
+            started: self.started,
+            arena: self.arena.clone(),
+            line_ids,
+            goal: self.goal,
+            trace: self.trace.clone(),
+            excluded: self.excluded.clone(),
+            failures: self.failures.clone(),
+        }))
+    }
+
+    /// Adds `event` to the trace being recorded, if there is one.
+    pub(crate) fn record(&self, event: TraceEvent) {
+        if let Some(trace) = &self.trace {
+            trace.borrow_mut().record(event);
+        }
+    }
+
+    /// Records on `lines` that this node's search produced them. Lines that a sub proof search
+    /// already recorded keep the provenance it gave them.
+    fn stamp(&self, lines: &[Line]) -> Vec<Line> {
+        let provenance = Provenance {
+            iteration: Some(self.iterations.get()),
+            elapsed: Some(self.started.elapsed()),
+            depth: self.depth,
+        };
+        lines
+            .iter()
+            .cloned()
+            .map(|mut line| {
+                if line.provenance.iteration.is_none() {
+                    line.provenance = provenance.clone();
+                }
+                line
+            })
+            .collect()
+    }
+
     pub fn is_complete(&self) -> bool {
-        self.lines.iter().any(|x| x.expression == self.conclusion)
+        self.line_ids.contains(&self.goal)
+            || (self.contradiction && self.lines.iter().any(|x| is_contradiction(&x.expression)))
+    }
+
+    /// Returns `true` if `lines` has the shape of a proof the search was told to skip.
+    fn is_excluded(&self, lines: &[Line]) -> bool {
+        !self.excluded.is_empty() && self.excluded.contains(&ProofShape::of(lines))
+    }
+
+    /// Returns `true` if deriving `expression` finishes the search.
+    pub fn is_goal(&self, expression: &Expression) -> bool {
+        *expression == self.conclusion || (self.contradiction && is_contradiction(expression))
+    }
+
+    /// Returns `true` if the last line of `possible`, as given by `PossibleFinder::find`,
+    /// finishes the search, comparing its id with that of the conclusion.
+    fn reaches_goal(&self, possible: &Possible) -> bool {
+        possible.ids.last() == Some(&self.goal)
+            || (self.contradiction && is_contradiction(&possible.lines.last().unwrap().expression))
     }
 
     pub fn assumptions(&self) -> Vec<Expression> {
@@ -241,61 +926,367 @@ impl SearchNode {
     }
 }
 
-fn search(head: Rc<RefCell<SearchNode>>, proof: &mut Proof) -> Result<Vec<Line>, ProofError> {
-    let mut queue = VecDeque::new();
-    queue.push_back(head.clone());
-    while let Some(current_rc) = queue.pop_front() {
-        let current = current_rc.borrow();
+/// A search for a proof that is run one step at a time, started by `Proof::start_search`.
+///
+/// Each step expands one node of the search tree, which counts as one iteration, together with
+/// the sub proof searches that expanding it starts. Callers can stop between steps to do other
+/// work or to apply their own budget. Dropping the search before it finishes leaves the proof as
+/// it was.
+pub struct ProofSearch<'a> {
+    proof: &'a mut Proof,
+    head: Rc<RefCell<SearchNode>>,
+    /// Nodes waiting to be expanded, in breadth-first order
+    queue: VecDeque<Rc<RefCell<SearchNode>>>,
+    opened_by: Option<Line>,
+    iterations_before: usize,
+    state: SearchState,
+}
 
-        if current.is_complete() {
-            return Ok(current.lines.clone());
+impl ProofSearch<'_> {
+    /// Runs the search until it has expanded one more node or finished.
+    ///
+    /// Returns `SearchState::Searching` while there is more to search, then the state it finished
+    /// in: `FinishedProof` if the proof was found, or the reason it was not. Once finished, every
+    /// further step returns the same state.
+    pub fn step(&mut self) -> SearchState {
+        if self.state != SearchState::Searching {
+            return self.state.clone();
         }
+        let result = match self.expand_next() {
+            Some(result) => result,
+            None => return SearchState::Searching,
+        };
+        self.finish(result)
+    }
 
-        if current.lines.len() > current.settings.max_line_length {
-            continue;
+    /// Runs at most `steps` steps, stopping early if the search finishes, and returns the state
+    /// it is left in.
+    pub fn run(&mut self, steps: usize) -> SearchState {
+        for _ in 0..steps {
+            if self.step() != SearchState::Searching {
+                break;
+            }
         }
+        self.state.clone()
+    }
+
+    /// `SearchState::Searching` until the search finishes, then the state it finished in.
+    pub fn state(&self) -> SearchState {
+        self.state.clone()
+    }
+
+    /// The number of iterations the proof has searched so far, including those of sub proof
+    /// searches and of earlier searches of the same proof.
+    pub fn iterations(&self) -> usize {
+        self.proof.iterations.get()
+    }
+
+    /// Takes nodes off the queue until one is expanded, returning the outcome of the search if it
+    /// ends first.
+    fn expand_next(&mut self) -> Option<Result<Vec<Line>, SearchState>> {
+        while let Some(current_rc) = self.queue.pop_front() {
+            let current = current_rc.borrow();
+            if let Some(lemmas) = self.proof.lemmas.as_mut() {
+                lemmas.harvest(&current.lines);
+            }
 
-        proof.iterations += 1;
-        if proof.iterations > proof.settings.iterations {
-            return Err(ProofError::SearchError(SearchState::MaximumIteration));
+            if current.is_complete() {
+                if current.is_excluded(&current.lines) {
+                    // Adding lines to a finished proof would not make it a different proof
+                    continue;
+                }
+                return Some(Ok(current.lines.clone()));
+            }
+
+            if current.lines.len() > current.settings.max_line_length {
+                current.record(TraceEvent::Pruned {
+                    depth: current.depth,
+                    lines: current.lines.len(),
+                });
+                continue;
+            }
+
+            if current.iterations.get() >= current.iteration_limit {
+                return Some(Err(SearchState::MaximumIteration));
+            }
+            let unit = current.settings.iteration_unit;
+            if unit == IterationUnit::Expansions {
+                current.iterations.set(current.iterations.get() + 1);
+            }
+            current.record(TraceEvent::Expand {
+                depth: current.depth,
+                iteration: current.iterations.get(),
+                lines: current.lines.len(),
+            });
+
+            let mut finder = PossibleFinder::new(current.clone());
+            finder.find();
+            let possibles = finder.possibles();
+            let lines = possibles.iter().map(|possible| possible.lines.len()).sum();
+            let mut stats = current.stats.get();
+            stats.expansions += 1;
+            stats.candidates += possibles.len();
+            stats.rule_applications += lines;
+            current.stats.set(stats);
+            let work = match unit {
+                IterationUnit::Expansions => 0,
+                IterationUnit::Candidates => possibles.len(),
+                IterationUnit::RuleApplications => lines,
+            };
+            current.iterations.set(current.iterations.get() + work);
+            current.record(TraceEvent::Candidates {
+                depth: current.depth,
+                count: possibles.len(),
+            });
+
+            for possible in possibles {
+                #[allow(clippy::single_match)]
+                match current.reaches_goal(possible) {
+                    true => {
+                        let mut new_lines = current.lines.clone();
+                        new_lines.extend(current.stamp(&possible.lines));
+                        if !current.is_excluded(&new_lines) {
+                            return Some(Ok(new_lines));
+                        }
+                    }
+                    false => (),
+                }
+            }
+            if let Some(near_misses) = self.proof.near_misses.as_mut() {
+                for possible in possibles {
+                    let last = &possible.lines.last().unwrap().expression;
+                    near_misses.offer(&self.proof.conclusion, last, || {
+                        let mut new_lines = current.lines.clone();
+                        new_lines.extend(current.stamp(&possible.lines));
+                        new_lines
+                    });
+                }
+            }
+            for possible in possibles {
+                self.queue.push_back(current.child(possible));
+            }
+            return None;
         }
 
-        let mut finder = PossibleFinder::new(current.clone());
-        finder.find();
-        let possibles = finder.possibles();
-        if possibles.is_empty() {
-            continue;
+        // Work out which error to return
+        let head = self.head.borrow();
+        if head.lines.len() > head.settings.max_line_length {
+            return Some(Err(SearchState::MaximumLines));
         }
+        Some(Err(SearchState::DeadEnd))
+    }
 
-        for possible in possibles {
-            let last = possible.lines.last().unwrap();
-            match last.matches_expression(&current.conclusion) {
-                true => {
-                    let mut new_lines = current.lines.clone();
-                    new_lines.extend(possible.lines.clone());
-                    return Ok(new_lines);
+    /// Records how the search ended and, if it found the proof, gives the proof its lines.
+    fn finish(&mut self, result: Result<Vec<Line>, SearchState>) -> SearchState {
+        let proof = &mut *self.proof;
+        if let (true, Some(opened_by)) = (proof.depth > 0, self.opened_by.take()) {
+            self.head.borrow().record(TraceEvent::SubProof {
+                depth: proof.depth - 1,
+                rule: opened_by.rule.clone(),
+                found: result.is_ok(),
+            });
+            if result.is_err() {
+                proof.record_failure(opened_by, proof.iterations.get() - self.iterations_before);
+            }
+        }
+        self.queue.clear();
+        self.state = match result {
+            Ok(mut lines) => {
+                if proof.depth == 0 {
+                    note_assumptions(&mut lines);
+                }
+                proof.lines = lines;
+                SearchState::FinishedProof
+            }
+            Err(state) => state,
+        };
+        self.state.clone()
+    }
+}
+
+impl Lemmas {
+    /// Keeping every lemma would flood the search above with candidates
+    const MAX_LEMMAS: usize = 5;
+
+    /// Records the last of `lines` if it is a lemma that is new and worth keeping.
+    fn harvest(&mut self, lines: &[Line]) {
+        let Some(last) = lines.last() else {
+            return;
+        };
+        if self.derivations.len() >= Self::MAX_LEMMAS
+            || last.line_number < self.start
+            || last.assumption_lines.iter().any(|a| *a >= self.start)
+            || lines[..self.start]
+                .iter()
+                .any(|line| line.expression == last.expression)
+            || self
+                .derivations
+                .iter()
+                .any(|d| d.last().unwrap().expression == last.expression)
+        {
+            return;
+        }
+        // Collect the lines the lemma was derived from, leaving out those already above `start`
+        let mut needed = vec![last.line_number];
+        let mut i = 0;
+        while i < needed.len() {
+            for n in &lines[needed[i]].deduction_lines {
+                if *n >= self.start && !needed.contains(n) {
+                    needed.push(*n);
                 }
-                false => (),
+            }
+            i += 1;
+        }
+        // A lemma derived in one step is something the search above would find by itself
+        if needed.len() < 2 {
+            return;
+        }
+        needed.sort();
+        let renumber = |n: &usize| match needed.iter().position(|m| m == n) {
+            Some(position) if *n >= self.start => self.start + position,
+            _ => *n,
+        };
+        let derivation = needed
+            .iter()
+            .map(|n| {
+                let mut line = lines[*n].clone();
+                line.assumption_lines = line.assumption_lines.iter().map(renumber).collect();
+                line.line_number = renumber(n);
+                line.deduction_lines = line.deduction_lines.iter().map(renumber).collect();
+                line
+            })
+            .collect();
+        self.derivations.push(derivation);
+    }
+}
+
+/// Comments each sub proof assumption in `lines` that has no comment yet with the rule it was
+/// made for and the line that discharges it, so readers can tell it from a premise.
+fn note_assumptions(lines: &mut [Line]) {
+    for i in 0..lines.len() {
+        if lines[i].comment.is_none() {
+            lines[i].comment = assumption_note(lines, i);
+        }
+    }
+}
+
+/// The comment `note_assumptions` gives line `i` of `lines`, if it is a sub proof assumption that
+/// a later line discharges.
+fn assumption_note(lines: &[Line], i: usize) -> Option<String> {
+    let closing_rule = lines[i].rule.closing_rule()?;
+    let discharged_at = lines[i..]
+        .iter()
+        .find(|line| line.rule == closing_rule && line.deduction_lines.contains(&i))?;
+    Some(format!(
+        "Assumed for {}, discharged at line {}",
+        closing_rule,
+        discharged_at.line_number + 1
+    ))
+}
+
+/// Adds the lines of `proof` after the premises to `merged`, which starts with the premises of
+/// `proof` among its own, renumbering what they cite. A line with the same expression and
+/// assumptions as one already in `merged` outside any sub proof is left out and the earlier line
+/// cited in its place.
+fn merge_lines(merged: &mut Vec<Line>, proof: &[Line]) -> Vec<usize> {
+    // Where each line of `proof` ends up in `merged`
+    let mut moved = Vec::with_capacity(proof.len());
+    for (i, line) in proof.iter().enumerate() {
+        if line.rule == Rule::Assumption {
+            let premise = merged
+                .iter()
+                .position(|premise| {
+                    premise.rule == Rule::Assumption && premise.expression == line.expression
+                })
+                .unwrap();
+            let premise_line = &mut merged[premise];
+            premise_line.label = premise_line.label.take().or_else(|| line.label.clone());
+            premise_line.comment = premise_line.comment.take().or_else(|| line.comment.clone());
+            moved.push(premise);
+            continue;
+        }
+        moved.push(merged.len());
+        let mut line = line.clone();
+        // The note numbers the line that discharges the assumption, which may move
+        if line.comment.is_some() && line.comment == assumption_note(proof, i) {
+            line.comment = None;
+        }
+        line.assumption_lines = line.assumption_lines.iter().map(|n| moved[*n]).collect();
+        line.assumption_lines.sort();
+        line.deduction_lines = line.deduction_lines.iter().map(|n| moved[*n]).collect();
+        // Lines inside a sub proof cannot be cited from outside it
+        let depths = subproof_depths(merged);
+        let existing = merged.iter().zip(depths).position(|(earlier, depth)| {
+            depth == 0
+                && earlier.expression == line.expression
+                && earlier.assumption_lines == line.assumption_lines
+        });
+        match existing {
+            Some(earlier) if line.rule.closing_rule().is_none() => moved[i] = earlier,
+            _ => {
+                line.line_number = merged.len();
+                merged.push(line);
             }
         }
-        for possible in possibles {
-            let mut new_lines = current.lines.clone();
-            new_lines.extend(possible.lines.clone());
-            let new_node = SearchNode::new(
-                new_lines,
-                current.conclusion.clone(),
-                current.settings.clone(),
-            );
-            queue.push_back(new_node);
+    }
+    moved
+}
+
+/// Removes the lines from `start` onwards that neither line `goal` nor any line before `start`
+/// leads to, which a merge leaves behind when it cites earlier lines in place of their own
+/// derivations. The remaining lines are renumbered.
+fn remove_unused(lines: &mut Vec<Line>, start: usize, goal: usize) {
+    let mut used = vec![false; lines.len()];
+    used[..start].fill(true);
+    used[goal] = true;
+    for i in (start..lines.len()).rev() {
+        if used[i] {
+            for n in lines[i]
+                .deduction_lines
+                .iter()
+                .chain(&lines[i].assumption_lines)
+            {
+                used[*n] = true;
+            }
         }
     }
+    let renumbered = used
+        .iter()
+        .scan(0, |next, used| {
+            *next += usize::from(*used);
+            Some(*next - 1)
+        })
+        .collect::<Vec<_>>();
+    let mut i = 0;
+    lines.retain(|_| {
+        i += 1;
+        used[i - 1]
+    });
+    for line in lines.iter_mut() {
+        line.line_number = renumbered[line.line_number];
+        for n in line
+            .deduction_lines
+            .iter_mut()
+            .chain(&mut line.assumption_lines)
+        {
+            *n = renumbered[*n];
+        }
+    }
+}
 
-    // Work out which error to return
-    let current = head.borrow();
-    if current.lines.len() > current.settings.max_line_length {
-        return Err(ProofError::SearchError(SearchState::MaximumLines));
+/// Returns `true` if `expression` has the shape `X & -X` or `-X & X`.
+pub(crate) fn is_contradiction(expression: &Expression) -> bool {
+    match expression {
+        Expression::Bottom => true,
+        Expression::And(left, right) => {
+            let negates = |a: &Expression, b: &Expression| match a {
+                Expression::Not(inner) => inner.as_ref() == b,
+                _ => false,
+            };
+            negates(left, right) || negates(right, left)
+        }
+        _ => false,
     }
-    Err(ProofError::SearchError(SearchState::DeadEnd))
 }
 
 pub fn create_assumption_lines(assumptions: Vec<Expression>) -> Vec<Line> {
@@ -310,3 +1301,89 @@ pub fn parse_expression(expression: &str) -> Result<Expression, ParserError> {
     let mut parser = Parser::new(expression);
     parser.parse()
 }
+
+/// The ways of writing the delimiter between the premises and the conclusion of an argument.
+const DELIMITERS: [&str; 6] = ["/", "⊢", "|-", "∴", "therefore", "Therefore"];
+
+/// Parses an argument, written as premises separated by commas, a delimiter (`/`, `⊢`, `|-`, `∴`
+/// or the word `therefore`) and the conclusion, into its premises and conclusion.
+///
+/// A conclusion of `#` stands for any contradiction and is read as `⊥`, so `A, -A / #` asks to
+/// show the premises are inconsistent. `Proof::refutation` searches for such a proof.
+///
+/// `|-` is always read as the delimiter, so a disjunction with a negated right side must be
+/// written with a space, as in `A| -B`, or with `v`.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{
+///     error::ArgumentError,
+///     proof::{parse_argument, parse_expression},
+/// };
+///
+/// let (premises, conclusion) = parse_argument("A, A>B / B").unwrap();
+/// assert_eq!(premises, vec![parse_expression("A").unwrap(), parse_expression("A>B").unwrap()]);
+/// assert_eq!(conclusion, parse_expression("B").unwrap());
+///
+/// assert!(matches!(parse_argument("⊢ P>P"), Err(ArgumentError::NoPremises)));
+///
+/// let pasted = parse_argument("A, A>B ∴ B").unwrap();
+/// assert_eq!(parse_argument("A, A>B therefore B").unwrap(), pasted);
+/// ```
+///
+/// # Errors
+///
+/// Returns an `ArgumentError` if there is no delimiter or more than one, if there are no
+/// premises or one is empty, or if a premise or the conclusion is not a valid expression.
+pub fn parse_argument(input: &str) -> Result<(Vec<Expression>, Expression), ArgumentError> {
+    let (start, delimiter) = find_delimiter(input, 0).ok_or(ArgumentError::MissingDelimiter)?;
+    let end = start + delimiter.len();
+    if let Some((offset, second)) = find_delimiter(input, end) {
+        return Err(ArgumentError::TrailingInput(
+            second.to_string(),
+            chars_before(input, offset),
+        ));
+    }
+    let premises_str = &input[..start];
+    if premises_str.trim().is_empty() {
+        return Err(ArgumentError::NoPremises);
+    }
+    let mut premises = Vec::new();
+    let mut offset = 0;
+    for premise in premises_str.split(',') {
+        let next = offset + premise.len();
+        if premise.trim().is_empty() {
+            return Err(ArgumentError::EmptyPremise(chars_before(input, next)));
+        }
+        premises.push(parse_part(input, offset, premise)?);
+        offset = next + 1;
+    }
+    let conclusion = match input[end..].trim() {
+        "#" => Expression::Bottom,
+        _ => parse_part(input, end, &input[end..])?,
+    };
+    Ok((premises, conclusion))
+}
+
+/// The first delimiter in `input` at or after byte `from`, with its byte offset.
+fn find_delimiter(input: &str, from: usize) -> Option<(usize, &'static str)> {
+    DELIMITERS
+        .iter()
+        .filter_map(|delimiter| {
+            input[from..]
+                .find(delimiter)
+                .map(|offset| (from + offset, *delimiter))
+        })
+        .min_by_key(|(offset, _)| *offset)
+}
+
+/// Parses `part`, which starts at byte `offset` of `input`, placing any error within `input`.
+fn parse_part(input: &str, offset: usize, part: &str) -> Result<Expression, ParserError> {
+    parse_expression(part).map_err(|err| err.shifted(chars_before(input, offset)))
+}
+
+/// How many characters of `input` come before byte `offset`.
+fn chars_before(input: &str, offset: usize) -> usize {
+    input[..offset].chars().count()
+}
@@ -0,0 +1,496 @@
+use std::fmt::{self, Display};
+
+use crate::{
+    expression::Expression,
+    lines::{Line, Rule},
+    metadata::{escape_attribute, Metadata},
+    proof::Proof,
+};
+
+/// Choices about notation shared by every renderer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NotationConfig {
+    /// How lines are cited
+    pub citations: Citations,
+    /// Column at which lines of plain text output are wrapped. `None` never wraps.
+    pub max_width: Option<usize>,
+    /// Recorded in the LaTeX, HTML and Markdown output, as described on `Metadata`
+    pub metadata: Option<Metadata>,
+}
+
+/// How a rendered proof refers to the lines it cites.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Citations {
+    /// By line number
+    #[default]
+    Numbers,
+    /// By the cited line's label, or its number if it has none
+    Labels,
+}
+
+/// Turns a proof into text in some format. Implement this to add a format of your own; the
+/// helpers on `Proof` (`lines`, `assumptions` and `conclusion`) give everything there is to
+/// render.
+pub trait ProofRenderer {
+    fn render(&self, proof: &Proof, notation: &NotationConfig) -> String;
+}
+
+/// The format used by `Proof`'s `Display`: a header with the sequent, then one line per step with
+/// sub proofs indented.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Terminal;
+
+/// Lemmon style: the assumption set, the line number in brackets, the formula and then the cited
+/// lines with the rule, in aligned columns.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Lemmon;
+
+/// Fitch style: each sub proof is marked by a vertical bar, with a rule under the assumptions that
+/// open it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Fitch;
+
+/// A Markdown table, with sub proofs indented inside the formula column.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Markdown;
+
+/// A LaTeX `tabular` in Lemmon style.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Latex;
+
+/// An HTML `<table>` whose rows have the class `depth-N` for a line `N` sub proofs deep, so the
+/// page can indent them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Html;
+
+impl ProofRenderer for Terminal {
+    fn render(&self, proof: &Proof, notation: &NotationConfig) -> String {
+        let conclusion = if proof.is_refutation() {
+            "the assumptions are inconsistent".to_string()
+        } else {
+            proof.conclusion().to_string()
+        };
+        let mut out = format!(
+            "Assumptions: [{}]\nConclusion: {}\nTotal Proof Steps: {}\nProof Steps:\n",
+            join_expressions(proof.assumptions()),
+            conclusion,
+            proof.lines().len()
+        );
+        let cite = citer(proof, notation);
+        for (line, depth) in proof.lines().iter().zip(subproof_depths(proof.lines())) {
+            // Apply indentation if in a nested proof
+            let indent = "  ".repeat(depth);
+            let mut text = String::new();
+            // Writing to a `String` cannot fail
+            write_line(&mut text, line, &cite).unwrap();
+            match notation.max_width {
+                Some(width) => {
+                    let width = width.saturating_sub(indent.len());
+                    for (i, piece) in wrap(&text, width, HANGING_INDENT).iter().enumerate() {
+                        let hanging = if i == 0 { 0 } else { HANGING_INDENT };
+                        out.push_str(&format!("{}{}{}\n", indent, " ".repeat(hanging), piece));
+                    }
+                }
+                None => out.push_str(&format!("{}{}\n", indent, text)),
+            }
+        }
+        out
+    }
+}
+
+impl ProofRenderer for Lemmon {
+    fn render(&self, proof: &Proof, notation: &NotationConfig) -> String {
+        let cite = citer(proof, notation);
+        let rows = proof
+            .lines()
+            .iter()
+            .map(|line| {
+                let mut justification = join(&line.deduction_lines, &cite, ",");
+                if !justification.is_empty() {
+                    justification.push(' ');
+                }
+                justification.push_str(&line.rule.to_string());
+                if let Some(comment) = &line.comment {
+                    justification.push_str(&format!("  # {}", comment));
+                }
+                vec![
+                    join(&line.assumption_lines, &cite, ","),
+                    format!("({})", line_name(line)),
+                    line.expression.to_string(),
+                    justification,
+                ]
+            })
+            .collect::<Vec<Vec<String>>>();
+        columns(&rows)
+    }
+}
+
+impl ProofRenderer for Fitch {
+    fn render(&self, proof: &Proof, notation: &NotationConfig) -> String {
+        let cite = citer(proof, notation);
+        let lines = proof.lines();
+        let depths = subproof_depths(lines);
+        let rows = lines
+            .iter()
+            .zip(&depths)
+            .map(|(line, depth)| {
+                let mut justification = line.rule.to_string();
+                if !line.deduction_lines.is_empty() {
+                    justification.push(' ');
+                    justification.push_str(&join(&line.deduction_lines, &cite, ", "));
+                }
+                if let Some(comment) = &line.comment {
+                    justification.push_str(&format!("  # {}", comment));
+                }
+                vec![
+                    line_name(line),
+                    format!("{}{}", "| ".repeat(depth + 1), line.expression),
+                    justification,
+                ]
+            })
+            .collect::<Vec<Vec<String>>>();
+        let name_width = rows.iter().map(|row| row[0].chars().count()).max();
+        let mut out = String::new();
+        for (i, (row, line)) in columns(&rows).lines().zip(lines).enumerate() {
+            out.push_str(row);
+            out.push('\n');
+            // Assumptions are separated from what is derived from them
+            let ends_premises = line.rule == Rule::Assumption
+                && lines.get(i + 1).map(|next| &next.rule) != Some(&Rule::Assumption);
+            if ends_premises || is_subproof_assumption(&line.rule) {
+                out.push_str(&format!(
+                    "{:width$}  {}|----\n",
+                    "",
+                    "| ".repeat(depths[i]),
+                    width = name_width.unwrap_or(0)
+                ));
+            }
+        }
+        out
+    }
+}
+
+impl ProofRenderer for Markdown {
+    fn render(&self, proof: &Proof, notation: &NotationConfig) -> String {
+        let cite = citer(proof, notation);
+        let mut out = String::new();
+        for (name, value) in metadata_fields(notation) {
+            out.push_str(&format!(
+                "<!-- {}: {} -->\n",
+                name,
+                value.replace("--", "- -")
+            ));
+        }
+        out.push_str("| Assumptions | Line | Formula | Justification |\n|---|---|---|---|\n");
+        for (line, depth) in proof.lines().iter().zip(subproof_depths(proof.lines())) {
+            let mut justification = line.rule.to_string();
+            if !line.deduction_lines.is_empty() {
+                justification = format!(
+                    "{} {}",
+                    join(&line.deduction_lines, &cite, ", "),
+                    justification
+                );
+            }
+            if let Some(comment) = &line.comment {
+                justification.push_str(&format!(" *{}*", comment));
+            }
+            out.push_str(&format!(
+                "| {} | ({}) | {}`{}` | {} |\n",
+                join(&line.assumption_lines, &cite, ", "),
+                line_name(line),
+                "&emsp;".repeat(depth),
+                line.expression,
+                justification.replace('|', "\\|")
+            ));
+        }
+        out
+    }
+}
+
+impl ProofRenderer for Latex {
+    fn render(&self, proof: &Proof, notation: &NotationConfig) -> String {
+        let cite = |n: usize| escape_latex(&citer(proof, notation)(n));
+        let mut out = String::new();
+        for (name, value) in metadata_fields(notation) {
+            out.push_str(&format!("% {}: {}\n", name, value));
+        }
+        out.push_str("\\begin{tabular}{llll}\n");
+        for (line, depth) in proof.lines().iter().zip(subproof_depths(proof.lines())) {
+            let mut justification = escape_latex(&line.rule.to_string());
+            if !line.deduction_lines.is_empty() {
+                justification = format!(
+                    "{} {}",
+                    join(&line.deduction_lines, &cite, ","),
+                    justification
+                );
+            }
+            if let Some(comment) = &line.comment {
+                justification.push_str(&format!(" \\quad \\textit{{{}}}", escape_latex(comment)));
+            }
+            out.push_str(&format!(
+                "{} & ({}) & {}${}$ & {} \\\\\n",
+                join(&line.assumption_lines, &cite, ","),
+                escape_latex(&line_name(line)),
+                "\\quad ".repeat(depth),
+                latex_expression(&line.expression),
+                justification
+            ));
+        }
+        out.push_str("\\end{tabular}\n");
+        out
+    }
+}
+
+impl ProofRenderer for Html {
+    fn render(&self, proof: &Proof, notation: &NotationConfig) -> String {
+        let cite = |n: usize| escape_html(&citer(proof, notation)(n));
+        let mut out = String::from("<table class=\"proof\"");
+        for (name, value) in metadata_fields(notation) {
+            out.push_str(&format!(" data-{}=\"{}\"", name, escape_attribute(&value)));
+        }
+        out.push_str(">\n<tbody>\n");
+        for (line, depth) in proof.lines().iter().zip(subproof_depths(proof.lines())) {
+            out.push_str(&format!(
+                "<tr class=\"depth-{}\"><td>{}</td><td>({})</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                depth,
+                join(&line.assumption_lines, &cite, ", "),
+                escape_html(&line_name(line)),
+                escape_html(&line.expression.to_string()),
+                escape_html(&line.rule.to_string()),
+                join(&line.deduction_lines, &cite, ", "),
+                escape_html(line.comment.as_deref().unwrap_or_default()),
+            ));
+        }
+        out.push_str("</tbody>\n</table>\n");
+        out
+    }
+}
+
+impl Display for Proof {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Terminal.render(self, self.notation()))
+    }
+}
+
+impl Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_line(f, self, &|n| (n + 1).to_string())
+    }
+}
+
+/// The fields of the metadata set on `notation`, if any.
+fn metadata_fields(notation: &NotationConfig) -> Vec<(&'static str, String)> {
+    notation
+        .metadata
+        .as_ref()
+        .map(Metadata::fields)
+        .unwrap_or_default()
+}
+
+/// How many sub proofs each of `lines` sits inside.
+pub(crate) fn subproof_depths(lines: &[Line]) -> Vec<usize> {
+    // Open sub proofs, recorded as the assumption rule and the line it was derived from
+    let mut open_subproofs: Vec<(Rule, Option<usize>)> = Vec::new();
+    let mut depths = Vec::new();
+    for line in lines {
+        // Check if the line starts or ends a nested proof
+        match line.rule {
+            Rule::OrEliminationAssumption
+            | Rule::ConditionalProofAssumption
+            | Rule::ReductioAdAbsurdiumAssumption => {
+                let subproof = (line.rule.clone(), line.deduction_lines.first().copied());
+                // The cases of a single vE sit side by side rather than inside each other
+                if line.rule != Rule::OrEliminationAssumption
+                    || open_subproofs.last() != Some(&subproof)
+                {
+                    open_subproofs.push(subproof);
+                }
+            }
+            Rule::OrElimination | Rule::ConditionalProof | Rule::ReductioAdAbsurdium => {
+                open_subproofs.pop();
+            }
+            _ => (),
+        }
+        depths.push(open_subproofs.len());
+    }
+    depths
+}
+
+fn is_subproof_assumption(rule: &Rule) -> bool {
+    matches!(
+        rule,
+        Rule::OrEliminationAssumption
+            | Rule::ConditionalProofAssumption
+            | Rule::ReductioAdAbsurdiumAssumption
+    )
+}
+
+/// Names cited lines as `notation` asks.
+fn citer<'a>(proof: &'a Proof, notation: &NotationConfig) -> impl Fn(usize) -> String + 'a {
+    let citations = notation.citations;
+    move |n: usize| match proof.lines().get(n).and_then(|line| line.label.as_ref()) {
+        Some(label) if citations == Citations::Labels => label.clone(),
+        _ => (n + 1).to_string(),
+    }
+}
+
+/// The line's number, followed by its label if it has one.
+fn line_name(line: &Line) -> String {
+    match &line.label {
+        Some(label) => format!("{} {}", line.line_number + 1, label),
+        None => (line.line_number + 1).to_string(),
+    }
+}
+
+fn join_expressions(expressions: &[Expression]) -> String {
+    expressions
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Lays out `rows` in left-aligned columns.
+fn columns(rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = Vec::new();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            let width = cell.chars().count();
+            match widths.get_mut(i) {
+                Some(max) => *max = (*max).max(width),
+                None => widths.push(width),
+            }
+        }
+    }
+    let mut out = String::new();
+    for row in rows {
+        let cells = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<String>>();
+        out.push_str(cells.join("  ").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// How far the continuation of a wrapped line is indented past its first part
+const HANGING_INDENT: usize = 4;
+
+/// Breaks `text` at spaces into pieces that fit in `width` columns, leaving room for every piece
+/// after the first to be indented by `hanging`. Words longer than the width are not split.
+fn wrap(text: &str, width: usize, hanging: usize) -> Vec<String> {
+    let mut pieces = vec![String::new()];
+    for word in text.split(' ') {
+        let limit = if pieces.len() == 1 {
+            width
+        } else {
+            width.saturating_sub(hanging)
+        };
+        let current = pieces.last_mut().unwrap();
+        let length = current.chars().count();
+        if length > 0 && length + 1 + word.chars().count() > limit {
+            pieces.push(word.to_string());
+        } else {
+            if length > 0 {
+                current.push(' ');
+            }
+            current.push_str(word);
+        }
+    }
+    pieces
+}
+
+/// Writes `line`, naming each line it cites with `cite`.
+fn write_line(f: &mut dyn fmt::Write, line: &Line, cite: &dyn Fn(usize) -> String) -> fmt::Result {
+    write!(f, "Line {}", line.line_number + 1)?;
+    if let Some(label) = &line.label {
+        write!(f, " ({})", label)?;
+    }
+    // Base line format with assumption lines, line number, and expression
+    write!(
+        f,
+        ": {} [{}] using {}",
+        line.expression,
+        join(&line.assumption_lines, cite, ", "),
+        line.rule,
+    )?;
+
+    // Append 'from lines' only if there are deduction lines
+    if !line.deduction_lines.is_empty() {
+        write!(f, " from lines {}", join(&line.deduction_lines, cite, ", "))?;
+    }
+
+    if let Some(comment) = &line.comment {
+        write!(f, "  # {}", comment)?;
+    }
+
+    Ok(())
+}
+
+fn join(array: &[usize], cite: &dyn Fn(usize) -> String, separator: &str) -> String {
+    let mut array = array.to_owned();
+    array.sort();
+    array.dedup();
+    array
+        .iter()
+        .map(|x| cite(*x))
+        .collect::<Vec<String>>()
+        .join(separator)
+}
+
+fn latex_expression(expression: &Expression) -> String {
+    match expression {
+        Expression::And(left, right) => format!(
+            "({} \\land {})",
+            latex_expression(left),
+            latex_expression(right)
+        ),
+        Expression::Or(left, right) => format!(
+            "({} \\lor {})",
+            latex_expression(left),
+            latex_expression(right)
+        ),
+        Expression::Implies(left, right) => format!(
+            "({} \\to {})",
+            latex_expression(left),
+            latex_expression(right)
+        ),
+        Expression::Iff(left, right) => format!(
+            "({} \\leftrightarrow {})",
+            latex_expression(left),
+            latex_expression(right)
+        ),
+        Expression::Not(expr) => format!("\\neg {}", latex_expression(expr)),
+        Expression::Var(name) => match name.index() {
+            Some(index) => format!("{}_{{{}}}", name.letter(), index),
+            None => name.to_string(),
+        },
+        Expression::Top => "\\top".to_string(),
+        Expression::Bottom => "\\bot".to_string(),
+    }
+}
+
+fn escape_latex(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '~' => out.push_str("\\textasciitilde{}"),
+            '^' => out.push_str("\\textasciicircum{}"),
+            '\\' => out.push_str("\\textbackslash{}"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
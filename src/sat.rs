@@ -0,0 +1,420 @@
+use crate::{
+    cnf::Clause,
+    expression::{Expression, Symbol},
+    semantics::{collect_atoms, Model},
+};
+
+/// Finds a model that satisfies every clause in `clauses`, or returns `None` if there is none.
+/// The model assigns every letter of the clauses. See `Solver` for how it is found.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{cnf::Literal, sat::solve};
+///
+/// let p = Literal::new('P', true);
+/// let q = Literal::new('Q', true);
+/// let model = solve(&[vec![p, q], vec![p.negate()]]).unwrap();
+/// assert_eq!(model.get('Q'), Some(true));
+/// assert_eq!(solve(&[vec![p], vec![p.negate(), q], vec![q.negate()]]), None);
+/// ```
+pub fn solve(clauses: &[Clause]) -> Option<Model> {
+    Solver::new(clauses).solve()
+}
+
+/// Finds a model that makes every formula in `formulas` true, assigning each of their sentence
+/// letters, or returns `None` if they are inconsistent. The formulas are turned into clauses
+/// with `Expression::tseitin`, so unlike a truth table this does not need to look at every
+/// assignment of the letters.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{proof::parse_expression, sat::find_model};
+///
+/// let formulas = ["P>Q", "Q>R", "P"].map(|x| parse_expression(x).unwrap());
+/// assert_eq!(find_model(&formulas).unwrap().to_string(), "P = T, Q = T, R = T");
+/// assert_eq!(find_model(&[parse_expression("P&-P").unwrap()]), None);
+/// ```
+pub fn find_model(formulas: &[Expression]) -> Option<Model> {
+    let conjunction = formulas
+        .iter()
+        .cloned()
+        .reduce(|left, right| Expression::And(left.wrap(), right.wrap()))
+        .unwrap_or(Expression::Top);
+    let model = solve(&conjunction.tseitin().clauses)?;
+    // Letters the clauses did not need a value for can have any
+    Some(
+        collect_atoms(formulas)
+            .into_iter()
+            .map(|var| (var, model.get(var).unwrap_or(false)))
+            .collect(),
+    )
+}
+
+/// Returns `true` if `premises` entail `conclusion`, that is if the premises together with the
+/// negation of the conclusion have no model.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{proof::parse_expression, sat::is_valid};
+///
+/// let premises = ["PvQ", "-P"].map(|x| parse_expression(x).unwrap());
+/// assert!(is_valid(&premises, &parse_expression("Q").unwrap()));
+/// assert!(!is_valid(&premises, &parse_expression("P").unwrap()));
+/// ```
+pub fn is_valid(premises: &[Expression], conclusion: &Expression) -> bool {
+    let mut formulas = premises.to_vec();
+    formulas.push(Expression::Not(conclusion.clone().wrap()));
+    find_model(&formulas).is_none()
+}
+
+/// Counters kept by a `Solver` over its search.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SolverStats {
+    /// Letters given a value by choice rather than forced by a clause
+    pub decisions: usize,
+    /// Letters given a value because a clause had no other literal left that could be true
+    pub propagations: usize,
+    /// Times every literal of some clause became false
+    pub conflicts: usize,
+    /// Clauses learned from conflicts and added to the clauses
+    pub learned_clauses: usize,
+}
+
+/// A conflict-driven clause learning (CDCL) SAT solver over a set of clauses.
+///
+/// The solver gives letters values one decision at a time, most active letter first, and after
+/// each one sets every literal that some clause forces, watching two literals of each clause so
+/// that only clauses that may have become unit are looked at. When a clause becomes false, the
+/// solver works out from the reasons for the values involved a clause that rules out that
+/// combination of decisions, learns it, and backtracks straight to the decision it first applies
+/// to, rather than only undoing the last decision as DPLL does.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{cnf::Literal, sat::Solver};
+///
+/// let p = Literal::new('P', true);
+/// let q = Literal::new('Q', true);
+/// let mut solver = Solver::new(&[vec![p, q], vec![p, q.negate()], vec![p.negate(), q]]);
+/// let model = solver.solve().unwrap();
+/// assert_eq!((model.get('P'), model.get('Q')), (Some(true), Some(true)));
+/// let stats = solver.stats();
+/// assert!(stats.learned_clauses <= stats.conflicts);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Solver {
+    // The letters of the clauses, sorted; letter `vars[v]` is numbered `v`, and literal `2v` is
+    // the letter and `2v + 1` its negation
+    vars: Vec<Symbol>,
+    // Clauses of two or more literals, each watched by its first two
+    clauses: Vec<Vec<usize>>,
+    // Literals that are clauses on their own
+    units: Vec<usize>,
+    // Whether some clause is empty
+    empty: bool,
+    // The clauses watching each literal, which are looked at when it becomes false
+    watches: Vec<Vec<usize>>,
+    values: Vec<Option<bool>>,
+    // The decision level each letter was given its value at
+    levels: Vec<usize>,
+    // The clause that forced each letter's value, or `None` for decisions
+    reasons: Vec<Option<usize>>,
+    // The literals made true, in order, with where each decision level starts
+    trail: Vec<usize>,
+    decisions: Vec<usize>,
+    // How much of the trail has been propagated
+    propagated: usize,
+    // How often each letter has been part of a recent conflict, and how much a bump adds
+    activity: Vec<f64>,
+    increment: f64,
+    // The value each letter last had, tried first when it is next decided
+    phases: Vec<bool>,
+    stats: SolverStats,
+}
+
+/// How much more each conflict's bump of activity counts than the last one's.
+const ACTIVITY_GROWTH: f64 = 1.05;
+
+fn var_of(literal: usize) -> usize {
+    literal / 2
+}
+
+fn is_positive(literal: usize) -> bool {
+    literal & 1 == 0
+}
+
+fn negate(literal: usize) -> usize {
+    literal ^ 1
+}
+
+impl Solver {
+    pub fn new(clauses: &[Clause]) -> Self {
+        let mut vars = clauses
+            .iter()
+            .flatten()
+            .map(|literal| literal.var)
+            .collect::<Vec<_>>();
+        vars.sort();
+        vars.dedup();
+        let mut solver = Solver {
+            clauses: Vec::new(),
+            units: Vec::new(),
+            empty: false,
+            watches: vec![Vec::new(); 2 * vars.len()],
+            values: vec![None; vars.len()],
+            levels: vec![0; vars.len()],
+            reasons: vec![None; vars.len()],
+            trail: Vec::new(),
+            decisions: Vec::new(),
+            propagated: 0,
+            activity: vec![0.0; vars.len()],
+            increment: 1.0,
+            phases: vec![false; vars.len()],
+            stats: SolverStats::default(),
+            vars,
+        };
+        for clause in clauses {
+            let mut literals = clause
+                .iter()
+                .map(|literal| {
+                    let var = solver.vars.binary_search(&literal.var).unwrap();
+                    2 * var + usize::from(!literal.positive)
+                })
+                .collect::<Vec<_>>();
+            literals.sort();
+            literals.dedup();
+            // A clause with a literal and its negation is always true
+            if literals.windows(2).any(|pair| pair[1] == negate(pair[0])) {
+                continue;
+            }
+            match literals[..] {
+                [] => solver.empty = true,
+                [unit] => solver.units.push(unit),
+                _ => {
+                    solver.add_clause(literals);
+                }
+            }
+        }
+        solver
+    }
+
+    /// Finds a model of the clauses, assigning every letter, or returns `None` if there is none.
+    pub fn solve(&mut self) -> Option<Model> {
+        self.backtrack(0);
+        self.propagated = 0;
+        self.trail.clear();
+        self.values.iter_mut().for_each(|value| *value = None);
+        if self.empty {
+            return None;
+        }
+        for unit in self.units.clone() {
+            match self.value(unit) {
+                Some(false) => return None,
+                Some(true) => {}
+                None => self.enqueue(unit, None),
+            }
+        }
+        loop {
+            if let Some(conflict) = self.propagate() {
+                self.stats.conflicts += 1;
+                if self.decisions.is_empty() {
+                    return None;
+                }
+                let (learned, level) = self.analyze(conflict);
+                self.backtrack(level);
+                let asserting = learned[0];
+                if learned.len() == 1 {
+                    self.units.push(asserting);
+                    self.enqueue(asserting, None);
+                } else {
+                    let index = self.add_clause(learned);
+                    self.enqueue(asserting, Some(index));
+                }
+                self.stats.learned_clauses += 1;
+                self.increment *= ACTIVITY_GROWTH;
+                // Scale everything down before the bumps grow too large to represent
+                if self.increment > 1e100 {
+                    self.activity
+                        .iter_mut()
+                        .for_each(|activity| *activity *= 1e-100);
+                    self.increment *= 1e-100;
+                }
+            } else {
+                let Some(var) = self.pick() else {
+                    return Some(
+                        self.vars
+                            .iter()
+                            .zip(&self.values)
+                            .map(|(var, value)| (*var, value.unwrap()))
+                            .collect(),
+                    );
+                };
+                self.stats.decisions += 1;
+                self.decisions.push(self.trail.len());
+                self.enqueue(2 * var + usize::from(!self.phases[var]), None);
+            }
+        }
+    }
+
+    /// The counters of the searches so far.
+    pub fn stats(&self) -> SolverStats {
+        self.stats
+    }
+
+    /// Adds a clause of two or more literals, watched by its first two, returning its index.
+    fn add_clause(&mut self, literals: Vec<usize>) -> usize {
+        let index = self.clauses.len();
+        self.watches[literals[0]].push(index);
+        self.watches[literals[1]].push(index);
+        self.clauses.push(literals);
+        index
+    }
+
+    fn value(&self, literal: usize) -> Option<bool> {
+        self.values[var_of(literal)].map(|value| value == is_positive(literal))
+    }
+
+    /// Makes `literal` true at the current decision level, forced by the clause `reason`.
+    fn enqueue(&mut self, literal: usize, reason: Option<usize>) {
+        let var = var_of(literal);
+        self.values[var] = Some(is_positive(literal));
+        self.levels[var] = self.decisions.len();
+        self.reasons[var] = reason;
+        self.trail.push(literal);
+    }
+
+    /// Sets every literal forced by a clause, returning the clause that became false if any did.
+    fn propagate(&mut self) -> Option<usize> {
+        while self.propagated < self.trail.len() {
+            let falsified = negate(self.trail[self.propagated]);
+            self.propagated += 1;
+            let watching = std::mem::take(&mut self.watches[falsified]);
+            let mut kept = Vec::with_capacity(watching.len());
+            let mut conflict = None;
+            for (i, &index) in watching.iter().enumerate() {
+                if conflict.is_some() {
+                    kept.extend_from_slice(&watching[i..]);
+                    break;
+                }
+                let clause = &mut self.clauses[index];
+                // Keep the falsified watch second
+                if clause[0] == falsified {
+                    clause.swap(0, 1);
+                }
+                let first = clause[0];
+                if self.values[var_of(first)].map(|value| value == is_positive(first)) == Some(true)
+                {
+                    kept.push(index);
+                    continue;
+                }
+                // Watch some other literal that is not false instead
+                let values = &self.values;
+                let replacement = clause[2..].iter().position(|&literal| {
+                    values[var_of(literal)].map(|value| value == is_positive(literal))
+                        != Some(false)
+                });
+                if let Some(k) = replacement {
+                    clause.swap(1, k + 2);
+                    let watch = clause[1];
+                    self.watches[watch].push(index);
+                    continue;
+                }
+                kept.push(index);
+                if self.value(first) == Some(false) {
+                    conflict = Some(index);
+                } else {
+                    self.stats.propagations += 1;
+                    self.enqueue(first, Some(index));
+                }
+            }
+            self.watches[falsified] = kept;
+            if conflict.is_some() {
+                return conflict;
+            }
+        }
+        None
+    }
+
+    /// Works out from the clause `conflict` a clause to learn, whose first literal is the only
+    /// one from the current decision level, so that it forces that literal after backtracking.
+    /// Returns the clause with the level to backtrack to, the highest level of its other literals.
+    fn analyze(&mut self, conflict: usize) -> (Vec<usize>, usize) {
+        let level = self.decisions.len();
+        let mut seen = vec![false; self.vars.len()];
+        let mut learned = vec![0];
+        // Literals of the current level seen but not yet resolved away
+        let mut pending = 0;
+        let mut index = self.trail.len();
+        let mut clause = conflict;
+        let mut resolved = None;
+        loop {
+            for &literal in &self.clauses[clause] {
+                let var = var_of(literal);
+                if Some(literal) == resolved || seen[var] || self.levels[var] == 0 {
+                    continue;
+                }
+                seen[var] = true;
+                self.activity[var] += self.increment;
+                if self.levels[var] == level {
+                    pending += 1;
+                } else {
+                    learned.push(literal);
+                }
+            }
+            // The latest literal on the trail that was seen
+            loop {
+                index -= 1;
+                if seen[var_of(self.trail[index])] {
+                    break;
+                }
+            }
+            let literal = self.trail[index];
+            pending -= 1;
+            if pending == 0 {
+                learned[0] = negate(literal);
+                break;
+            }
+            resolved = Some(literal);
+            clause = self.reasons[var_of(literal)].unwrap();
+        }
+        // Watch the literal of the highest other level second, so it is the first to be unset
+        let mut backtrack = 0;
+        for i in 1..learned.len() {
+            let var_level = self.levels[var_of(learned[i])];
+            if var_level > backtrack {
+                backtrack = var_level;
+                learned.swap(1, i);
+            }
+        }
+        (learned, backtrack)
+    }
+
+    /// Undoes every value given after decision level `level`.
+    fn backtrack(&mut self, level: usize) {
+        if let Some(&start) = self.decisions.get(level) {
+            for literal in self.trail.drain(start..) {
+                let var = var_of(literal);
+                self.phases[var] = is_positive(literal);
+                self.values[var] = None;
+                self.reasons[var] = None;
+            }
+            self.decisions.truncate(level);
+            self.propagated = self.trail.len();
+        }
+    }
+
+    /// The unassigned letter with the most activity, if any is left.
+    fn pick(&self) -> Option<usize> {
+        (0..self.vars.len())
+            .filter(|&var| self.values[var].is_none())
+            .max_by(|&a, &b| {
+                self.activity[a]
+                    .total_cmp(&self.activity[b])
+                    .then(b.cmp(&a))
+            })
+    }
+}
@@ -0,0 +1,796 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::{self, Display},
+    rc::Rc,
+};
+
+use crate::{
+    cnf::fresh_atoms,
+    error::Inconsistent,
+    expression::{Expression, Symbol},
+    sat,
+};
+
+/// An assignment of truth values to sentence letters.
+///
+/// A `Model` may be partial: letters that have not been assigned a value are treated as unknown,
+/// and evaluating an expression that depends on them yields `None`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Model {
+    values: BTreeMap<Symbol, bool>,
+}
+
+impl Model {
+    /// Creates an empty model with no letters assigned.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the value assigned to `var`, if any.
+    pub fn get(&self, var: impl Into<Symbol>) -> Option<bool> {
+        self.values.get(&var.into()).copied()
+    }
+
+    /// Assigns `value` to `var`, replacing any previous assignment.
+    pub fn set(&mut self, var: impl Into<Symbol>, value: bool) {
+        self.values.insert(var.into(), value);
+    }
+
+    /// Iterates over the assigned letters in alphabetical order.
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol, bool)> + '_ {
+        self.values.iter().map(|(var, value)| (*var, *value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Evaluates `expression` under this model.
+    ///
+    /// Evaluation is short-circuiting, so `None` is only returned when the value of the expression
+    /// actually depends on an unassigned letter (e.g. `P & Q` is false whenever `P` is false).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use propositional_logic_calculator::{proof::parse_expression, semantics::Model};
+    ///
+    /// let mut model = Model::new();
+    /// model.set('P', false);
+    /// assert_eq!(model.evaluate(&parse_expression("P>Q").unwrap()), Some(true));
+    /// assert_eq!(model.evaluate(&parse_expression("PvQ").unwrap()), None);
+    /// ```
+    pub fn evaluate(&self, expression: &Expression) -> Option<bool> {
+        match expression {
+            Expression::And(left, right) => match (self.evaluate(left), self.evaluate(right)) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            },
+            Expression::Or(left, right) => match (self.evaluate(left), self.evaluate(right)) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (Some(false), Some(false)) => Some(false),
+                _ => None,
+            },
+            Expression::Implies(left, right) => match (self.evaluate(left), self.evaluate(right)) {
+                (Some(false), _) | (_, Some(true)) => Some(true),
+                (Some(true), Some(false)) => Some(false),
+                _ => None,
+            },
+            Expression::Iff(left, right) => match (self.evaluate(left), self.evaluate(right)) {
+                (Some(left), Some(right)) => Some(left == right),
+                _ => None,
+            },
+            Expression::Not(expr) => self.evaluate(expr).map(|value| !value),
+            Expression::Var(var) => self.get(*var),
+            Expression::Top => Some(true),
+            Expression::Bottom => Some(false),
+        }
+    }
+
+    /// Returns `true` if `expression` is definitely true under this model.
+    pub fn satisfies(&self, expression: &Expression) -> bool {
+        self.evaluate(expression) == Some(true)
+    }
+}
+
+impl<S: Into<Symbol>> FromIterator<(S, bool)> for Model {
+    fn from_iter<I: IntoIterator<Item = (S, bool)>>(iter: I) -> Self {
+        Model {
+            values: iter
+                .into_iter()
+                .map(|(var, value)| (var.into(), value))
+                .collect(),
+        }
+    }
+}
+
+impl Display for Model {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let values = self
+            .iter()
+            .map(|(var, value)| format!("{} = {}", var, if value { "T" } else { "F" }))
+            .collect::<Vec<String>>();
+        write!(f, "{}", values.join(", "))
+    }
+}
+
+/// Searches for models by truth table while their formulas have at most this many sentence
+/// letters, and with `sat::find_model` beyond it, where the table grows too long to look through.
+pub const MAX_TABLE_ATOMS: usize = 20;
+
+/// Iterates over every total model of `atoms` in truth-table order: the first atom is the most
+/// significant column and rows start from all-true.
+///
+/// # Panics
+///
+/// Panics if there are as many `atoms` as bits in a `usize`, where the rows cannot be numbered.
+pub fn all_models(atoms: &[Symbol]) -> impl Iterator<Item = Model> + '_ {
+    let rows = 1usize
+        .checked_shl(atoms.len() as u32)
+        .expect("too many sentence letters to list every model of");
+    (0..rows).map(move |row| {
+        atoms
+            .iter()
+            .enumerate()
+            .map(|(i, var)| (*var, row & (1 << (atoms.len() - 1 - i)) == 0))
+            .collect()
+    })
+}
+
+/// Checks whether `a` could still be true given `gamma`, i.e. whether `gamma` together with `a` is
+/// satisfiable, returning a witnessing model over their sentence letters if so.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{proof::parse_expression, semantics::consistent_with};
+///
+/// let gamma = vec![parse_expression("P>Q").unwrap()];
+/// let model = consistent_with(&gamma, &parse_expression("P").unwrap()).unwrap();
+/// assert_eq!(model.get('Q'), Some(true));
+/// assert!(consistent_with(&gamma, &parse_expression("P&-Q").unwrap()).is_err());
+/// ```
+pub fn consistent_with(gamma: &[Expression], a: &Expression) -> Result<Model, Inconsistent> {
+    let mut formulas = gamma.to_vec();
+    formulas.push(a.clone());
+    find_model(&formulas).ok_or(Inconsistent)
+}
+
+/// Finds the maximal consistent subsets of `premises`, as sorted lists of premise indices.
+///
+/// A subset is maximal if adding any other premise to it makes it inconsistent. Subsets are
+/// returned largest first, and in lexicographic order of their indices within each size.
+///
+/// Each premise is given a fresh selector letter that implies it. A model of those implications
+/// picks out a consistent subset, which is grown one premise at a time until it is maximal, and
+/// a clause then asks for some premise outside it before the next model is looked for. So the
+/// consistency checks grow with the number of maximal subsets rather than with every subset of
+/// the premises.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{
+///     proof::parse_expression,
+///     semantics::maximal_consistent_subsets,
+/// };
+///
+/// let premises = ["P", "P>Q", "-Q"].map(|x| parse_expression(x).unwrap());
+/// assert_eq!(
+///     maximal_consistent_subsets(&premises),
+///     vec![vec![0, 1], vec![0, 2], vec![1, 2]]
+/// );
+/// ```
+pub fn maximal_consistent_subsets(premises: &[Expression]) -> Vec<Vec<usize>> {
+    let selectors = fresh_atoms(collect_atoms(premises))
+        .take(premises.len())
+        .collect::<Vec<Symbol>>();
+    let mut formulas = selectors
+        .iter()
+        .zip(premises)
+        .map(|(selector, premise)| {
+            Expression::Implies(Expression::Var(*selector).wrap(), premise.clone().wrap())
+        })
+        .collect::<Vec<Expression>>();
+
+    let mut subsets = Vec::new();
+    while let Some(model) = find_model(&formulas) {
+        let mut subset = (0..premises.len())
+            .filter(|i| model.satisfies(&premises[*i]))
+            .collect::<Vec<usize>>();
+        for i in 0..premises.len() {
+            if subset.contains(&i) {
+                continue;
+            }
+            let grown = subset
+                .iter()
+                .chain([&i])
+                .map(|j| premises[*j].clone())
+                .collect::<Vec<Expression>>();
+            if find_model(&grown).is_some() {
+                subset.push(i);
+            }
+        }
+        subset.sort();
+        // Later subsets must take some premise this one leaves out
+        formulas.push(
+            (0..premises.len())
+                .filter(|i| !subset.contains(i))
+                .map(|i| Expression::Var(selectors[i]))
+                .reduce(|left, right| Expression::Or(left.wrap(), right.wrap()))
+                .unwrap_or(Expression::Bottom),
+        );
+        subsets.push(subset);
+    }
+    subsets.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    subsets
+}
+
+/// Finds the minimal correction sets of `premises`: the smallest groups of premises whose removal
+/// restores consistency. These are exactly the complements of the maximal consistent subsets and
+/// are returned in the same order.
+pub fn minimal_correction_sets(premises: &[Expression]) -> Vec<Vec<usize>> {
+    maximal_consistent_subsets(premises)
+        .into_iter()
+        .map(|subset| {
+            (0..premises.len())
+                .filter(|i| !subset.contains(i))
+                .collect()
+        })
+        .collect()
+}
+
+/// Finds a smallest subset of `assumptions` that still entails `conclusion`, as sorted premise
+/// indices, or `None` if the full set does not entail it.
+///
+/// Subsets are generated one at a time in order of increasing size (and lexicographically within
+/// a size), so the result has minimum cardinality rather than merely being irredundant. Because the rules of the
+/// proof system are sound and complete, the slimmed sequent is derivable whenever the original is.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{
+///     proof::parse_expression,
+///     semantics::minimal_sufficient_premises,
+/// };
+///
+/// let assumptions = ["R", "P", "S>R", "P>Q"].map(|x| parse_expression(x).unwrap());
+/// let conclusion = parse_expression("Q").unwrap();
+/// assert_eq!(minimal_sufficient_premises(&assumptions, &conclusion), Some(vec![1, 3]));
+/// ```
+pub fn minimal_sufficient_premises(
+    assumptions: &[Expression],
+    conclusion: &Expression,
+) -> Option<Vec<usize>> {
+    let entailed_by = |subset: &[usize]| {
+        let gamma = subset
+            .iter()
+            .map(|i| assumptions[*i].clone())
+            .collect::<Vec<Expression>>();
+        entails(&gamma, conclusion).is_valid()
+    };
+    let all = (0..assumptions.len()).collect::<Vec<usize>>();
+    if !entailed_by(&all) {
+        return None;
+    }
+    (0..=assumptions.len()).find_map(|size| {
+        let mut subset = (0..size).collect::<Vec<usize>>();
+        loop {
+            if entailed_by(&subset) {
+                return Some(subset);
+            }
+            if !next_combination(&mut subset, assumptions.len()) {
+                return None;
+            }
+        }
+    })
+}
+
+/// Advances `subset`, a sorted list of distinct indices below `len`, to the next subset of the
+/// same size in lexicographic order. Returns `false`, leaving it unchanged, if it was the last.
+fn next_combination(subset: &mut [usize], len: usize) -> bool {
+    let size = subset.len();
+    // The last index that can still move right
+    let Some(i) = (0..size).rev().find(|i| subset[*i] < len - size + i) else {
+        return false;
+    };
+    subset[i] += 1;
+    for j in i + 1..size {
+        subset[j] = subset[j - 1] + 1;
+    }
+    true
+}
+
+/// Finds a model over the letters of `formulas` that makes all of them true: the first in
+/// truth-table order, or any that `sat::find_model` finds once there are more than
+/// `MAX_TABLE_ATOMS` letters.
+fn find_model(formulas: &[Expression]) -> Option<Model> {
+    let atoms = collect_atoms(formulas);
+    if atoms.len() > MAX_TABLE_ATOMS {
+        return sat::find_model(formulas);
+    }
+    let model = all_models(&atoms).find(|model| formulas.iter().all(|x| model.satisfies(x)));
+    model
+}
+
+/// Whether a formula is true under every model of its sentence letters, under none, or under
+/// some but not others, as given by `Expression::classify`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Classification {
+    /// True under every model
+    Tautology,
+    /// False under every model
+    Contradiction,
+    /// True under some models and false under others
+    Contingent,
+}
+
+impl Display for Classification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Classification::Tautology => write!(f, "tautology"),
+            Classification::Contradiction => write!(f, "contradiction"),
+            Classification::Contingent => write!(f, "contingent"),
+        }
+    }
+}
+
+/// How the right-hand side of `entails_set` is read when it contains several conclusions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConclusionMode {
+    /// Every model of the premises must make at least one conclusion true, as in sequent calculus.
+    /// An empty set of conclusions is then only entailed by inconsistent premises.
+    Disjunctive,
+    /// Every model of the premises must make all of the conclusions true.
+    Conjunctive,
+}
+
+/// The result of a semantic entailment check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Entailment {
+    /// The premises entail the conclusions.
+    Valid,
+    /// A model that makes every premise true but does not satisfy the conclusions.
+    Invalid(Model),
+}
+
+impl Entailment {
+    pub fn is_valid(&self) -> bool {
+        *self == Entailment::Valid
+    }
+
+    /// Returns the countermodel if the entailment does not hold.
+    pub fn countermodel(&self) -> Option<&Model> {
+        match self {
+            Entailment::Valid => None,
+            Entailment::Invalid(model) => Some(model),
+        }
+    }
+}
+
+/// Checks whether `gamma` entails `conclusion`, giving a model of `gamma` that makes
+/// `conclusion` false if it does not. The countermodel assigns every sentence letter of the
+/// argument.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{proof::parse_expression, semantics::entails};
+///
+/// let gamma = vec![parse_expression("P>Q").unwrap(), parse_expression("Q").unwrap()];
+/// assert!(entails(&gamma, &parse_expression("PvQ").unwrap()).is_valid());
+///
+/// let entailment = entails(&gamma, &parse_expression("P").unwrap());
+/// assert_eq!(entailment.countermodel().unwrap().to_string(), "P = F, Q = T");
+/// ```
+pub fn entails(gamma: &[Expression], conclusion: &Expression) -> Entailment {
+    entails_set(
+        gamma,
+        std::slice::from_ref(conclusion),
+        ConclusionMode::Conjunctive,
+    )
+}
+
+/// Checks whether `gamma` entails the set `delta`, reading `delta` according to `mode`.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{
+///     proof::parse_expression,
+///     semantics::{entails_set, ConclusionMode},
+/// };
+///
+/// let gamma = vec![parse_expression("PvQ").unwrap()];
+/// let delta = vec![parse_expression("P").unwrap(), parse_expression("Q").unwrap()];
+/// assert!(entails_set(&gamma, &delta, ConclusionMode::Disjunctive).is_valid());
+/// assert!(!entails_set(&gamma, &delta, ConclusionMode::Conjunctive).is_valid());
+/// ```
+pub fn entails_set(gamma: &[Expression], delta: &[Expression], mode: ConclusionMode) -> Entailment {
+    let mut formulas = gamma.to_vec();
+    match mode {
+        ConclusionMode::Disjunctive => formulas.extend(
+            delta
+                .iter()
+                .map(|conclusion| Expression::Not(conclusion.clone().wrap())),
+        ),
+        ConclusionMode::Conjunctive => match delta
+            .iter()
+            .cloned()
+            .reduce(|left, right| Expression::And(left.wrap(), right.wrap()))
+        {
+            Some(conjunction) => formulas.push(Expression::Not(conjunction.wrap())),
+            // Every model makes all of no conclusions true
+            None => return Entailment::Valid,
+        },
+    }
+    match find_model(&formulas) {
+        Some(model) => Entailment::Invalid(model),
+        None => Entailment::Valid,
+    }
+}
+
+/// A complete truth table for a list of formulas over the letters they mention.
+#[derive(Debug, Clone)]
+pub struct TruthTable {
+    atoms: Vec<Symbol>,
+    formulas: Vec<Expression>,
+    rows: Vec<TruthTableRow>,
+}
+
+/// A single row of a `TruthTable`: the model for the row and the value of each formula under it.
+#[derive(Debug, Clone)]
+pub struct TruthTableRow {
+    pub model: Model,
+    pub values: Vec<bool>,
+}
+
+impl TruthTable {
+    /// Builds the truth table for `formulas`, with rows in the order produced by `all_models`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `formulas` have as many sentence letters as bits in a `usize`.
+    pub fn new(formulas: &[Expression]) -> Self {
+        let atoms = collect_atoms(formulas);
+        let rows = all_models(&atoms)
+            .map(|model| {
+                let values = formulas
+                    .iter()
+                    .map(|formula| model.satisfies(formula))
+                    .collect();
+                TruthTableRow { model, values }
+            })
+            .collect();
+        TruthTable {
+            atoms,
+            formulas: formulas.to_vec(),
+            rows,
+        }
+    }
+
+    pub fn atoms(&self) -> &[Symbol] {
+        &self.atoms
+    }
+
+    pub fn formulas(&self) -> &[Expression] {
+        &self.formulas
+    }
+
+    pub fn rows(&self) -> &[TruthTableRow] {
+        &self.rows
+    }
+}
+
+/// The outcome of propagating a partial valuation through a set of assumptions.
+#[derive(Debug, Clone)]
+pub struct Propagation {
+    /// The input model extended with every sentence letter whose value was forced.
+    pub model: Model,
+    /// Every subformula of the assumptions whose value is determined by `model`, with that value.
+    pub determined: Vec<(Expression, bool)>,
+    /// The first formula that was forced to a value it cannot take, if the constraints clash.
+    pub conflict: Option<Expression>,
+}
+
+impl Propagation {
+    /// Returns the value `expression` was determined to have, if it is a determined subformula.
+    pub fn value_of(&self, expression: &Expression) -> Option<bool> {
+        self.determined
+            .iter()
+            .find(|(determined, _)| determined == expression)
+            .map(|(_, value)| *value)
+    }
+
+    /// Returns `true` if propagation found that the assumptions cannot all be true under the
+    /// starting model.
+    pub fn is_conflicting(&self) -> bool {
+        self.conflict.is_some()
+    }
+}
+
+/// Propagates the truth values in `model` through `assumptions`, each of which is required to be
+/// true (Boolean constraint propagation).
+///
+/// Only values that are *forced* are derived: a conjunction required to be true forces both
+/// conjuncts, a true conditional with a true antecedent forces its consequent, and so on, until
+/// nothing changes. Propagation stops at the first conflict.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{proof::parse_expression, semantics::{propagate, Model}};
+///
+/// let assumptions = vec![parse_expression("P>Q").unwrap(), parse_expression("-(Q&R)").unwrap()];
+/// let mut model = Model::new();
+/// model.set('P', true);
+/// let propagation = propagate(&assumptions, &model);
+/// assert_eq!(propagation.model.get('Q'), Some(true));
+/// assert_eq!(propagation.model.get('R'), Some(false));
+/// ```
+pub fn propagate(assumptions: &[Expression], model: &Model) -> Propagation {
+    let mut model = model.clone();
+    let mut conflict = None;
+    loop {
+        let mut changed = false;
+        for assumption in assumptions {
+            if let Err(expression) = force(assumption, true, &mut model, &mut changed) {
+                conflict = Some(expression);
+                break;
+            }
+        }
+        if conflict.is_some() || !changed {
+            break;
+        }
+    }
+
+    let mut determined: Vec<(Expression, bool)> = Vec::new();
+    for expression in assumptions.iter().flat_map(Expression::list_expressions) {
+        if determined.iter().any(|(seen, _)| *seen == expression) {
+            continue;
+        }
+        if let Some(value) = model.evaluate(&expression) {
+            determined.push((expression, value));
+        }
+    }
+
+    Propagation {
+        model,
+        determined,
+        conflict,
+    }
+}
+
+/// Requires `expression` to have `value`, assigning any letters this forces. Returns the offending
+/// expression if it already has the opposite value.
+fn force(
+    expression: &Expression,
+    value: bool,
+    model: &mut Model,
+    changed: &mut bool,
+) -> Result<(), Expression> {
+    match model.evaluate(expression) {
+        Some(actual) if actual == value => return Ok(()),
+        Some(_) => return Err(expression.clone()),
+        None => (),
+    }
+    match (expression, value) {
+        (Expression::Var(var), _) => {
+            model.set(*var, value);
+            *changed = true;
+        }
+        (Expression::Not(inner), _) => force(inner, !value, model, changed)?,
+        (Expression::And(left, right), true) | (Expression::Or(left, right), false) => {
+            force(left, value, model, changed)?;
+            force(right, value, model, changed)?;
+        }
+        (Expression::And(left, right), false) | (Expression::Or(left, right), true) => {
+            if model.evaluate(left) == Some(!value) {
+                force(right, value, model, changed)?;
+            } else if model.evaluate(right) == Some(!value) {
+                force(left, value, model, changed)?;
+            }
+        }
+        (Expression::Implies(left, right), false) => {
+            force(left, true, model, changed)?;
+            force(right, false, model, changed)?;
+        }
+        (Expression::Iff(left, right), _) => {
+            // Each side must equal the other when `value` is true and differ from it otherwise
+            if let Some(known) = model.evaluate(left) {
+                force(right, known == value, model, changed)?;
+            } else if let Some(known) = model.evaluate(right) {
+                force(left, known == value, model, changed)?;
+            }
+        }
+        (Expression::Implies(left, right), true) => {
+            if model.evaluate(left) == Some(true) {
+                force(right, true, model, changed)?;
+            } else if model.evaluate(right) == Some(false) {
+                force(left, false, model, changed)?;
+            }
+        }
+        // Constants always have a value, so they were dealt with above
+        (Expression::Top | Expression::Bottom, _) => (),
+    }
+    Ok(())
+}
+
+/// How many models of the sentence letters of `expression` make it true, out of the 2^n models of
+/// its n letters.
+///
+/// Rather than going through every model, this picks the letter that occurs most often, counts
+/// the models with it true and with it false separately after simplifying the formula with that
+/// value, and multiplies the counts of conjuncts that share no letters. The count of each
+/// simplified formula is cached, so a subformula reached by different assignments is counted
+/// once.
+///
+/// # Panics
+///
+/// Panics if `expression` has more than 127 sentence letters, where the count may not fit.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{proof::parse_expression, semantics::count_models};
+///
+/// assert_eq!(count_models(&parse_expression("PvQ").unwrap()), 3);
+/// assert_eq!(count_models(&parse_expression("P>(Q&R)").unwrap()), 5);
+/// assert_eq!(count_models(&parse_expression("P&-P").unwrap()), 0);
+/// ```
+pub fn count_models(expression: &Expression) -> u128 {
+    assert!(
+        collect_atoms([expression]).len() < 128,
+        "too many sentence letters to count models of"
+    );
+    count_restricted(expression, &mut HashMap::new())
+}
+
+/// The models of the letters of `expression` that make it true, caching counts by formula.
+fn count_restricted(expression: &Expression, cache: &mut HashMap<Expression, u128>) -> u128 {
+    match expression {
+        Expression::Top => return 1,
+        Expression::Bottom => return 0,
+        _ => {}
+    }
+    if let Some(count) = cache.get(expression) {
+        return *count;
+    }
+    let atoms = collect_atoms([expression]);
+    let components = components(expression);
+    let count = if atoms.is_empty() {
+        u128::from(Model::new().satisfies(expression))
+    } else if components.len() > 1 {
+        components
+            .iter()
+            .map(|component| count_restricted(component, cache))
+            .product()
+    } else {
+        let letters = expression
+            .list_expressions()
+            .into_iter()
+            .filter_map(|sub| match sub {
+                Expression::Var(var) => Some(var),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let var = *atoms
+            .iter()
+            .max_by_key(|var| letters.iter().filter(|letter| letter == var).count())
+            .unwrap();
+        [true, false]
+            .into_iter()
+            .map(|value| {
+                let restricted = restrict(expression, var, value);
+                // Letters the value made disappear can have either value
+                let gone = atoms.len() - 1 - collect_atoms([&restricted]).len();
+                count_restricted(&restricted, cache) << gone
+            })
+            .sum()
+    };
+    cache.insert(expression.clone(), count);
+    count
+}
+
+/// Splits the conjuncts of `expression` into conjunctions that share no sentence letters.
+fn components(expression: &Expression) -> Vec<Expression> {
+    let mut conjuncts = Vec::new();
+    let mut pending = vec![expression.clone()];
+    while let Some(next) = pending.pop() {
+        match next {
+            Expression::And(left, right) => {
+                pending.push(right.as_ref().clone());
+                pending.push(left.as_ref().clone());
+            }
+            conjunct => conjuncts.push(conjunct),
+        }
+    }
+    // Each group of conjuncts with the letters it mentions, merged whenever a conjunct links two
+    let mut groups: Vec<(Vec<Expression>, Vec<Symbol>)> = Vec::new();
+    for conjunct in conjuncts {
+        let atoms = collect_atoms([&conjunct]);
+        let mut group = (vec![conjunct], atoms);
+        groups.retain(|(others, letters)| {
+            if letters.iter().any(|letter| group.1.contains(letter)) {
+                group.0.extend(others.iter().cloned());
+                group.1.extend(letters);
+                false
+            } else {
+                true
+            }
+        });
+        groups.push(group);
+    }
+    groups
+        .into_iter()
+        .map(|(conjuncts, _)| {
+            conjuncts
+                .into_iter()
+                .reduce(|left, right| Expression::And(left.wrap(), right.wrap()))
+                .unwrap()
+        })
+        .collect()
+}
+
+/// `expression` with `var` replaced by `value` and the constants that leaves simplified away.
+fn restrict(expression: &Expression, var: Symbol, value: bool) -> Expression {
+    use Expression::{Bottom, Top};
+    let not = |x: Expression| match x {
+        Top => Bottom,
+        Bottom => Top,
+        x => Expression::Not(x.wrap()),
+    };
+    let binary = |left: &Rc<Expression>, right: &Rc<Expression>| {
+        (restrict(left, var, value), restrict(right, var, value))
+    };
+    match expression {
+        Expression::Var(x) if *x == var => {
+            if value {
+                Top
+            } else {
+                Bottom
+            }
+        }
+        Expression::Var(_) | Top | Bottom => expression.clone(),
+        Expression::Not(x) => not(restrict(x, var, value)),
+        Expression::And(left, right) => match binary(left, right) {
+            (Bottom, _) | (_, Bottom) => Bottom,
+            (Top, x) | (x, Top) => x,
+            (left, right) => Expression::And(left.wrap(), right.wrap()),
+        },
+        Expression::Or(left, right) => match binary(left, right) {
+            (Top, _) | (_, Top) => Top,
+            (Bottom, x) | (x, Bottom) => x,
+            (left, right) => Expression::Or(left.wrap(), right.wrap()),
+        },
+        Expression::Implies(left, right) => match binary(left, right) {
+            (Bottom, _) | (_, Top) => Top,
+            (Top, x) => x,
+            (x, Bottom) => not(x),
+            (left, right) => Expression::Implies(left.wrap(), right.wrap()),
+        },
+        Expression::Iff(left, right) => match binary(left, right) {
+            (Top, x) | (x, Top) => x,
+            (Bottom, x) | (x, Bottom) => not(x),
+            (left, right) => Expression::Iff(left.wrap(), right.wrap()),
+        },
+    }
+}
+
+/// Collects the sentence letters occurring in `expressions`, sorted and without duplicates.
+pub(crate) fn collect_atoms<'a>(
+    expressions: impl IntoIterator<Item = &'a Expression>,
+) -> Vec<Symbol> {
+    expressions
+        .into_iter()
+        .flat_map(Expression::letters)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
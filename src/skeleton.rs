@@ -0,0 +1,217 @@
+use std::fmt::{self, Display};
+
+use rand::Rng;
+
+use crate::{lines::Line, proof::Proof};
+
+/// What a line of a skeleton has hidden.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Blank {
+    /// The formula on the line
+    Formula,
+    /// The rule and the lines it cites
+    Rule,
+    /// Both the formula and the rule
+    Both,
+}
+
+impl Blank {
+    pub fn hides_formula(self) -> bool {
+        matches!(self, Blank::Formula | Blank::Both)
+    }
+
+    pub fn hides_rule(self) -> bool {
+        matches!(self, Blank::Rule | Blank::Both)
+    }
+}
+
+/// Controls how much of a proof a skeleton hides.
+#[derive(Debug, Clone)]
+pub struct SkeletonSettings {
+    /// Chance that the formula of each line that can be blanked is hidden.
+    pub formula_rate: f64,
+    /// Chance that the rule of each line that can be blanked is hidden.
+    pub rule_rate: f64,
+    /// Whether the premises can be blanked.
+    pub hide_premises: bool,
+    /// Whether the formula of the last line, the conclusion, can be hidden. Its rule can always
+    /// be.
+    pub hide_conclusion: bool,
+}
+
+impl SkeletonSettings {
+    /// Hides a few rules, for a first exercise on naming the rules.
+    pub const EASY: SkeletonSettings = SkeletonSettings {
+        formula_rate: 0.0,
+        rule_rate: 0.3,
+        hide_premises: false,
+        hide_conclusion: false,
+    };
+
+    /// Hides some formulas and some rules.
+    pub const MEDIUM: SkeletonSettings = SkeletonSettings {
+        formula_rate: 0.3,
+        rule_rate: 0.3,
+        hide_premises: false,
+        hide_conclusion: false,
+    };
+
+    /// Hides most of the proof, leaving the premises and the conclusion as the only fixed points.
+    pub const HARD: SkeletonSettings = SkeletonSettings {
+        formula_rate: 0.7,
+        rule_rate: 0.7,
+        hide_premises: false,
+        hide_conclusion: false,
+    };
+}
+
+impl Default for SkeletonSettings {
+    fn default() -> Self {
+        Self::MEDIUM
+    }
+}
+
+/// A proof with some of its lines blanked out, as a fill-in-the-blank exercise. The display shows
+/// the exercise and `answer_key` shows what was hidden.
+#[derive(Debug, Clone)]
+pub struct Skeleton {
+    /// Every line of the proof, hidden parts included
+    pub lines: Vec<Line>,
+    /// What each line has hidden, if anything
+    pub blanks: Vec<Option<Blank>>,
+}
+
+/// Blanks out parts of the lines of `proof`, which should be complete, as `settings` asks.
+///
+/// Every line that can be blanked is considered on its own. If none ends up with anything
+/// hidden, the formula of one of them is hidden, or its rule if formulas may not be, so that the
+/// exercise always has at least one blank when the proof has a line that can take one.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{
+///     proof::{parse_expression, Proof},
+///     skeleton::{skeleton, SkeletonSettings},
+/// };
+///
+/// let mut proof = Proof::new(
+///     vec![parse_expression("P>Q").unwrap(), parse_expression("P").unwrap()],
+///     parse_expression("Q").unwrap(),
+/// );
+/// proof.search().unwrap();
+/// let exercise = skeleton(&mut rand::thread_rng(), &proof, &SkeletonSettings::EASY);
+/// assert!(exercise.to_string().contains("____"));
+/// assert!(exercise.answer_key().contains("MPP"));
+/// ```
+pub fn skeleton<R: Rng + ?Sized>(
+    rng: &mut R,
+    proof: &Proof,
+    settings: &SkeletonSettings,
+) -> Skeleton {
+    let lines = proof.lines().to_vec();
+    let last = lines.len().saturating_sub(1);
+    let eligible =
+        |line: &Line| settings.hide_premises || line.line_number >= proof.assumptions().len();
+    let mut blanks = lines
+        .iter()
+        .map(|line| {
+            if !eligible(line) {
+                return None;
+            }
+            let formula = line.line_number != last || settings.hide_conclusion;
+            let formula = formula && rng.gen_bool(settings.formula_rate);
+            let rule = rng.gen_bool(settings.rule_rate);
+            match (formula, rule) {
+                (true, true) => Some(Blank::Both),
+                (true, false) => Some(Blank::Formula),
+                (false, true) => Some(Blank::Rule),
+                (false, false) => None,
+            }
+        })
+        .collect::<Vec<_>>();
+    if blanks.iter().all(Option::is_none) {
+        let candidates = lines
+            .iter()
+            .filter(|line| eligible(line))
+            .collect::<Vec<_>>();
+        if !candidates.is_empty() {
+            let line = candidates[rng.gen_range(0..candidates.len())];
+            let formula = settings.formula_rate > 0.0
+                && (line.line_number != last || settings.hide_conclusion);
+            blanks[line.line_number] = Some(if formula { Blank::Formula } else { Blank::Rule });
+        }
+    }
+    Skeleton { lines, blanks }
+}
+
+impl Skeleton {
+    /// The hidden parts of each line with a blank, one line each, as in
+    /// `Line 3: Q using MPP from lines 1, 2`.
+    pub fn answer_key(&self) -> String {
+        let mut key = String::new();
+        for (line, blank) in self.lines.iter().zip(&self.blanks) {
+            let Some(blank) = blank else {
+                continue;
+            };
+            key.push_str(&format!("Line {}:", line.line_number + 1));
+            if blank.hides_formula() {
+                key.push_str(&format!(" {}", line.expression));
+            }
+            if blank.hides_rule() {
+                key.push_str(&format!(" using {}", rule(line)));
+            }
+            key.push('\n');
+        }
+        key
+    }
+}
+
+impl Display for Skeleton {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (line, blank) in self.lines.iter().zip(&self.blanks) {
+            let hides_formula = blank.is_some_and(Blank::hides_formula);
+            let hides_rule = blank.is_some_and(Blank::hides_rule);
+            writeln!(
+                f,
+                "Line {}: {} [{}] using {}",
+                line.line_number + 1,
+                if hides_formula {
+                    BLANK.to_string()
+                } else {
+                    line.expression.to_string()
+                },
+                numbers(&line.assumption_lines),
+                if hides_rule {
+                    BLANK.to_string()
+                } else {
+                    rule(line)
+                },
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// What a hidden part of a line is shown as.
+const BLANK: &str = "____";
+
+/// The rule of `line` with the lines it cites, numbered from 1.
+fn rule(line: &Line) -> String {
+    if line.deduction_lines.is_empty() {
+        return line.rule.to_string();
+    }
+    format!(
+        "{} from lines {}",
+        line.rule,
+        numbers(&line.deduction_lines)
+    )
+}
+
+fn numbers(lines: &[usize]) -> String {
+    lines
+        .iter()
+        .map(|n| (n + 1).to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,331 @@
+use std::{iter::Peekable, rc::Rc, str::Chars};
+
+use crate::{
+    error::SmtLibError,
+    expression::{Expression, Symbol},
+    generate::letter_for,
+    parser::ParserOptions,
+};
+
+/// The Boolean assertions of an SMT-LIB script, with its constants renamed to sentence letters.
+///
+/// The script may declare Boolean constants with `declare-const` or with `declare-fun` taking no
+/// arguments, define them with `define-fun`, and `assert` terms built from `true`, `false`,
+/// `not`, `and`, `or`, `=>`, `xor`, `=` and `ite`. Commands that do not change the assertions,
+/// such as `set-logic`, `set-info` and `check-sat`, are skipped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmtLibScript {
+    pub assertions: Vec<Expression>,
+    /// Each declared constant with the sentence letter it was given, in order of declaration
+    pub atoms: Vec<(String, Symbol)>,
+}
+
+/// Reads the assertions of an SMT-LIB script. See `SmtLibScript` for what it may contain.
+///
+/// The assertions are satisfiable together exactly when the script's `check-sat` answers `sat`,
+/// so asking for a proof of `⊥` from them shows that it answers `unsat`.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{proof::parse_expression, smtlib::parse_smtlib};
+///
+/// let assertions = parse_smtlib(
+///     "(set-logic QF_UF)
+///      (declare-const p Bool)
+///      (declare-fun q () Bool)
+///      (assert (=> p q))
+///      (assert (and p (not q)))
+///      (check-sat)",
+/// )
+/// .unwrap();
+/// assert_eq!(assertions[0], parse_expression("P>Q").unwrap());
+/// assert_eq!(assertions[1], parse_expression("P&-Q").unwrap());
+/// ```
+pub fn parse_smtlib(input: &str) -> Result<Vec<Expression>, SmtLibError> {
+    Ok(SmtLibScript::parse(input)?.assertions)
+}
+
+impl SmtLibScript {
+    /// Reads a script, keeping the names of its constants. See `parse_smtlib`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `SmtLibError` if the input is not made of well formed commands, uses anything
+    /// beyond Boolean propositional logic, uses an undeclared symbol, declares more constants
+    /// than there are sentence letters or nests terms too deeply.
+    pub fn parse(input: &str) -> Result<Self, SmtLibError> {
+        let mut script = SmtLibScript {
+            assertions: Vec::new(),
+            atoms: Vec::new(),
+        };
+        let mut definitions = Vec::new();
+        let mut reader = Reader {
+            chars: input.chars().peekable(),
+            line: 1,
+            depth: 0,
+        };
+        while let Some(command) = reader.read()? {
+            script.command(command, &mut definitions)?;
+        }
+        Ok(script)
+    }
+
+    /// Carries out one command, adding what it declares to `self` and what it defines to
+    /// `definitions`.
+    fn command(
+        &mut self,
+        command: SExpr,
+        definitions: &mut Vec<(String, Expression)>,
+    ) -> Result<(), SmtLibError> {
+        let line = command.line();
+        let SExpr::List(items, _) = command else {
+            return Err(unexpected(&command));
+        };
+        let Some(SExpr::Symbol(name, _)) = items.first() else {
+            return Err(SmtLibError::Unexpected("'('".into(), line));
+        };
+        match (name.as_str(), &items[1..]) {
+            ("declare-const", [SExpr::Symbol(constant, _), sort]) => {
+                boolean(sort)?;
+                self.declare(constant)
+            }
+            ("declare-fun", [SExpr::Symbol(constant, _), SExpr::List(arguments, _), sort])
+                if arguments.is_empty() =>
+            {
+                boolean(sort)?;
+                self.declare(constant)
+            }
+            ("define-fun", [SExpr::Symbol(constant, _), SExpr::List(arguments, _), sort, body])
+                if arguments.is_empty() =>
+            {
+                boolean(sort)?;
+                let body = self.term(body, definitions)?;
+                definitions.push((constant.clone(), body));
+                Ok(())
+            }
+            ("assert", [term]) => {
+                let assertion = self.term(term, definitions)?;
+                self.assertions.push(assertion);
+                Ok(())
+            }
+            (
+                "set-logic" | "set-info" | "set-option" | "check-sat" | "get-model" | "get-value"
+                | "get-info" | "get-option" | "echo" | "exit",
+                _,
+            ) => Ok(()),
+            _ => Err(SmtLibError::Unsupported(name.clone(), line)),
+        }
+    }
+
+    /// Gives the constant `name` the next sentence letter.
+    fn declare(&mut self, name: &str) -> Result<(), SmtLibError> {
+        letter_for(&mut self.atoms, name)
+            .map(|_| ())
+            .ok_or_else(|| SmtLibError::TooManyAtoms(name.to_string()))
+    }
+
+    /// The formula for the Boolean `term`.
+    fn term(
+        &self,
+        term: &SExpr,
+        definitions: &[(String, Expression)],
+    ) -> Result<Expression, SmtLibError> {
+        let (name, arguments, line) = match term {
+            SExpr::Symbol(name, line) => {
+                if let Some((_, letter)) = self.atoms.iter().find(|(atom, _)| atom == name) {
+                    return Ok(Expression::Var(*letter));
+                }
+                if let Some((_, body)) = definitions.iter().rev().find(|(d, _)| d == name) {
+                    return Ok(body.clone());
+                }
+                return match name.as_str() {
+                    "true" => Ok(Expression::Top),
+                    "false" => Ok(Expression::Bottom),
+                    _ => Err(SmtLibError::UndeclaredSymbol(name.clone(), *line)),
+                };
+            }
+            SExpr::List(items, line) => match items.split_first() {
+                Some((SExpr::Symbol(name, _), arguments)) if !arguments.is_empty() => {
+                    (name, arguments, *line)
+                }
+                _ => return Err(unexpected(term)),
+            },
+        };
+        // Annotations such as `(! t :named a)` only give the term a name
+        if name == "!" {
+            return self.term(&arguments[0], definitions);
+        }
+        if !CONNECTIVES.contains(&name.as_str()) {
+            return Err(SmtLibError::Unsupported(name.clone(), line));
+        }
+        let arguments = arguments
+            .iter()
+            .map(|argument| self.term(argument, definitions))
+            .collect::<Result<Vec<_>, _>>()?;
+        let pairwise = |join: fn(Expression, Expression) -> Expression| {
+            arguments
+                .windows(2)
+                .map(|pair| join(pair[0].clone(), pair[1].clone()))
+                .reduce(|a, b| Expression::And(a.wrap(), b.wrap()))
+        };
+        let expression = match (name.as_str(), arguments.len()) {
+            ("not", 1) => Some(Expression::Not(arguments[0].clone().wrap())),
+            ("and", _) => fold_left(&arguments, Expression::And),
+            ("or", _) => fold_left(&arguments, Expression::Or),
+            ("xor", _) => fold_left(&arguments, |a, b| {
+                Expression::Not(Expression::Iff(a, b).wrap())
+            }),
+            ("=>", 2..) => arguments
+                .iter()
+                .rev()
+                .cloned()
+                .reduce(|right, left| Expression::Implies(left.wrap(), right.wrap())),
+            ("=", 2..) => pairwise(|a, b| Expression::Iff(a.wrap(), b.wrap())),
+            ("ite", 3) => {
+                let [condition, then, otherwise] = [0, 1, 2].map(|i| arguments[i].clone().wrap());
+                Some(Expression::And(
+                    Expression::Implies(condition.clone(), then).wrap(),
+                    Expression::Implies(Expression::Not(condition).wrap(), otherwise).wrap(),
+                ))
+            }
+            _ => None,
+        };
+        expression.ok_or_else(|| SmtLibError::Unsupported(name.clone(), line))
+    }
+}
+
+/// How deeply lists may be nested. Reading and converting terms recurse once for each level, so
+/// this keeps adversarial input from overflowing the stack, as `ParserOptions::max_depth` does
+/// for formulas.
+const MAX_DEPTH: usize = ParserOptions::DEFAULT_MAX_DEPTH;
+
+/// The functions that terms may apply.
+const CONNECTIVES: [&str; 7] = ["not", "and", "or", "xor", "=>", "=", "ite"];
+
+/// Joins `arguments` with `connective`, grouping to the left.
+fn fold_left(
+    arguments: &[Expression],
+    connective: fn(Rc<Expression>, Rc<Expression>) -> Expression,
+) -> Option<Expression> {
+    arguments
+        .iter()
+        .cloned()
+        .reduce(|left, right| connective(left.wrap(), right.wrap()))
+}
+
+/// Checks that `sort` is `Bool`.
+fn boolean(sort: &SExpr) -> Result<(), SmtLibError> {
+    match sort {
+        SExpr::Symbol(name, _) if name == "Bool" => Ok(()),
+        SExpr::Symbol(name, line) => Err(SmtLibError::Unsupported(name.clone(), *line)),
+        SExpr::List(..) => Err(SmtLibError::Unsupported("(".into(), sort.line())),
+    }
+}
+
+fn unexpected(expression: &SExpr) -> SmtLibError {
+    match expression {
+        SExpr::Symbol(name, line) => SmtLibError::Unexpected(format!("'{}'", name), *line),
+        SExpr::List(_, line) => SmtLibError::Unexpected("'('".into(), *line),
+    }
+}
+
+/// A symbol or a list, with the line it starts on.
+#[derive(Debug, Clone)]
+enum SExpr {
+    Symbol(String, usize),
+    List(Vec<SExpr>, usize),
+}
+
+impl SExpr {
+    fn line(&self) -> usize {
+        match self {
+            SExpr::Symbol(_, line) | SExpr::List(_, line) => *line,
+        }
+    }
+}
+
+/// Reads s-expressions one at a time, skipping whitespace and comments.
+struct Reader<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    // How many lists the one being read is inside, counted against the parser's depth limit.
+    depth: usize,
+}
+
+impl Reader<'_> {
+    /// The next s-expression, or `None` at the end of the input.
+    fn read(&mut self) -> Result<Option<SExpr>, SmtLibError> {
+        self.skip_blank();
+        let line = self.line;
+        let Some(c) = self.chars.next() else {
+            return Ok(None);
+        };
+        match c {
+            '(' => {
+                if self.depth == MAX_DEPTH {
+                    return Err(SmtLibError::TooDeeplyNested(MAX_DEPTH, line));
+                }
+                self.depth += 1;
+                let mut items = Vec::new();
+                loop {
+                    self.skip_blank();
+                    match self.chars.peek() {
+                        Some(')') => {
+                            self.chars.next();
+                            self.depth -= 1;
+                            return Ok(Some(SExpr::List(items, line)));
+                        }
+                        Some(_) => items.extend(self.read()?),
+                        None => {
+                            return Err(SmtLibError::Unexpected("end of input".into(), self.line))
+                        }
+                    }
+                }
+            }
+            ')' => Err(SmtLibError::Unexpected("')'".into(), line)),
+            // Quoted symbols and string literals run to their closing delimiter
+            '|' | '"' => {
+                let mut text = String::new();
+                loop {
+                    match self.chars.next() {
+                        Some(end) if end == c => break,
+                        Some(next) => {
+                            self.line += usize::from(next == '\n');
+                            text.push(next);
+                        }
+                        None => {
+                            return Err(SmtLibError::Unexpected("end of input".into(), self.line))
+                        }
+                    }
+                }
+                Ok(Some(SExpr::Symbol(text, line)))
+            }
+            _ => {
+                let mut text = c.to_string();
+                while let Some(next) = self
+                    .chars
+                    .next_if(|c| !c.is_whitespace() && !matches!(c, '(' | ')' | ';' | '|' | '"'))
+                {
+                    text.push(next);
+                }
+                Ok(Some(SExpr::Symbol(text, line)))
+            }
+        }
+    }
+
+    fn skip_blank(&mut self) {
+        loop {
+            match self.chars.peek() {
+                Some('\n') => self.line += 1,
+                Some(';') => {
+                    while self.chars.next_if(|c| *c != '\n').is_some() {}
+                    continue;
+                }
+                Some(c) if c.is_whitespace() => (),
+                _ => return,
+            }
+            self.chars.next();
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use std::{env, fs, io, path::Path};
+
+use crate::{
+    proof::Proof,
+    render::{NotationConfig, ProofRenderer, Terminal},
+};
+
+/// The variable that, when set, makes `assert_snapshot` rewrite the snapshots it checks instead
+/// of comparing against them.
+pub const UPDATE_VARIABLE: &str = "UPDATE_SNAPSHOTS";
+
+/// A proof as text that does not depend on how it was set up to be shown, for comparing against
+/// a snapshot.
+///
+/// The proof is rendered in the `Terminal` format with the default `NotationConfig`, so lines are
+/// cited by number, nothing is wrapped and no metadata is written, whatever the proof's own
+/// notation is. Cited lines are listed in order, trailing whitespace is removed and every line
+/// ends with `\n`.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{
+///     proof::{parse_expression, Proof},
+///     render::Citations,
+///     snapshot::normalize,
+/// };
+///
+/// let mut proof = Proof::new(
+///     vec![parse_expression("P>Q").unwrap(), parse_expression("P").unwrap()],
+///     parse_expression("Q").unwrap(),
+/// );
+/// proof.search().unwrap();
+/// let text = normalize(&proof);
+/// proof.set_citations(Citations::Labels);
+/// assert_eq!(normalize(&proof), text);
+/// assert!(text.ends_with("Line 3: Q [1, 2] using MPP from lines 1, 2\n"));
+/// ```
+pub fn normalize(proof: &Proof) -> String {
+    normalize_text(&Terminal.render(proof, &NotationConfig::default()))
+}
+
+/// Trims the end of each line of `text` and ends each with `\n`, so that snapshots compare the
+/// same whatever line endings they were saved with.
+fn normalize_text(text: &str) -> String {
+    text.lines()
+        .map(|line| line.trim_end().to_string() + "\n")
+        .collect()
+}
+
+/// The lines that differ between `expected` and `actual`, or `None` if there are none.
+///
+/// Lines in both are prefixed with two spaces, lines only in `expected` with `- ` and lines only
+/// in `actual` with `+ `, in the order of a longest common subsequence of the two.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::snapshot::diff;
+///
+/// assert_eq!(diff("a\nb\nc\n", "a\nb\nc\n"), None);
+/// assert_eq!(diff("a\nb\nc\n", "a\nd\nc\n").unwrap(), "  a\n- b\n+ d\n  c\n");
+/// ```
+pub fn diff(expected: &str, actual: &str) -> Option<String> {
+    if expected == actual {
+        return None;
+    }
+    let expected = expected.lines().collect::<Vec<_>>();
+    let actual = actual.lines().collect::<Vec<_>>();
+    // common[i][j] is the length of a longest common subsequence of expected[i..] and actual[j..]
+    let mut common = vec![vec![0; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            common[i][j] = if expected[i] == actual[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            out.push_str(&format!("  {}\n", expected[i]));
+            i += 1;
+            j += 1;
+        } else if j == actual.len() || (i < expected.len() && common[i + 1][j] >= common[i][j + 1])
+        {
+            out.push_str(&format!("- {}\n", expected[i]));
+            i += 1;
+        } else {
+            out.push_str(&format!("+ {}\n", actual[j]));
+            j += 1;
+        }
+    }
+    Some(out)
+}
+
+/// Compares `proof`, normalized, with the snapshot at `path`, returning the difference as given by
+/// `diff` if there is one.
+///
+/// A snapshot that does not exist yet is written rather than compared, as is every snapshot when
+/// the variable `UPDATE_SNAPSHOTS` is set, and the directories it goes in are created.
+///
+/// # Errors
+///
+/// Returns the `io::Error` if the snapshot cannot be read or written.
+pub fn check_snapshot(path: impl AsRef<Path>, proof: &Proof) -> io::Result<Option<String>> {
+    let path = path.as_ref();
+    let actual = normalize(proof);
+    if env::var_os(UPDATE_VARIABLE).is_some() || !path.exists() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, actual)?;
+        return Ok(None);
+    }
+    let expected = normalize_text(&fs::read_to_string(path)?);
+    Ok(diff(&expected, &actual))
+}
+
+/// Checks `proof` against the snapshot at `path` as `check_snapshot` does.
+///
+/// # Panics
+///
+/// Panics with the difference if the proof does not match the snapshot, or if the snapshot cannot
+/// be read or written.
+#[track_caller]
+pub fn assert_snapshot(path: impl AsRef<Path>, proof: &Proof) {
+    let path = path.as_ref();
+    match check_snapshot(path, proof) {
+        Ok(None) => {}
+        Ok(Some(diff)) => panic!(
+            "proof does not match the snapshot {} (- snapshot, + proof); set {} to update it:\n{}",
+            path.display(),
+            UPDATE_VARIABLE,
+            diff
+        ),
+        Err(err) => panic!("cannot use the snapshot {}: {}", path.display(), err),
+    }
+}
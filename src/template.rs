@@ -0,0 +1,170 @@
+use std::{
+    fmt::{self, Display},
+    rc::Rc,
+};
+
+use crate::{
+    error::{ParserError, TemplateError},
+    expression::{Expression, Symbol},
+    generate::atom_names,
+    lines::{Line, Rule},
+    parser::Parser,
+    possible::PossibleFinder,
+    proof::{SearchNode, SearchSettings},
+    semantics::{entails_set, ConclusionMode},
+};
+
+/// A formula with holes, written `?1`, `?2` and so on, for "complete the formula" exercises.
+///
+/// A hole can be filled with any formula, and the same hole gets the same formula wherever it
+/// appears. Inside the template each hole is held as a sentence letter the template does not
+/// otherwise use, and filling it substitutes the formula for that letter.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{proof::parse_expression, template::Template};
+///
+/// let premise = Template::parse("P > ?1").unwrap();
+/// let conclusion = Template::parse("?1").unwrap();
+/// let p = Template::parse("P").unwrap();
+/// let filling = [parse_expression("Q&R").unwrap()];
+/// assert_eq!(premise.fill(&filling).unwrap(), parse_expression("P>(Q&R)").unwrap());
+/// assert!(Template::makes_valid(&[premise, p], &conclusion, &filling).unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    source: String,
+    expression: Expression,
+    /// Each hole's number with the letter that stands for it
+    holes: Vec<(usize, char)>,
+}
+
+impl Template {
+    /// Reads a template written like a formula for `Parser`, with `?` followed by a number for
+    /// each hole.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `TemplateError` if the template is not a valid formula, with a `?` that is not
+    /// followed by a number reported as an invalid character, or if it uses so many sentence
+    /// letters that none is left for a hole.
+    pub fn parse(input: &str) -> Result<Self, TemplateError> {
+        let used = input
+            .chars()
+            .filter(char::is_ascii_uppercase)
+            .collect::<Vec<_>>();
+        let mut free = atom_names(usize::MAX)
+            .into_iter()
+            .map(|letter| letter.letter())
+            .filter(|letter| !used.contains(letter));
+        let mut holes: Vec<(usize, char)> = Vec::new();
+        // The input with each hole replaced by its letter, and where each character of it came
+        // from
+        let mut replaced = String::new();
+        let mut origins = Vec::new();
+        let mut chars = input.chars().enumerate().peekable();
+        while let Some((position, c)) = chars.next() {
+            let c = if c == '?' {
+                let mut digits = String::new();
+                while let Some((_, digit)) = chars.next_if(|(_, c)| c.is_ascii_digit()) {
+                    digits.push(digit);
+                }
+                let number = match digits.parse::<usize>() {
+                    Ok(number) if number > 0 => number,
+                    _ => return Err(ParserError::InvalidExpression('?', position).into()),
+                };
+                match holes.iter().find(|(hole, _)| *hole == number) {
+                    Some((_, letter)) => *letter,
+                    None => {
+                        let letter = free.next().ok_or(TemplateError::TooManyHoles(number))?;
+                        holes.push((number, letter));
+                        letter
+                    }
+                }
+            } else {
+                c
+            };
+            replaced.push(c);
+            origins.push(position);
+        }
+        let length = input.chars().count();
+        let expression = Parser::new(&replaced).parse().map_err(|err| {
+            err.moved(|position| origins.get(position).copied().unwrap_or(length))
+        })?;
+        holes.sort();
+        Ok(Template {
+            source: input.to_string(),
+            expression,
+            holes,
+        })
+    }
+
+    /// The numbers of the holes, in order.
+    pub fn holes(&self) -> Vec<usize> {
+        self.holes.iter().map(|(hole, _)| *hole).collect()
+    }
+
+    /// The formula with each hole `?n` filled with `fillings[n - 1]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemplateError::MissingFilling` if there are too few fillings for the holes.
+    pub fn fill(&self, fillings: &[Expression]) -> Result<Expression, TemplateError> {
+        for (hole, _) in &self.holes {
+            if *hole > fillings.len() {
+                return Err(TemplateError::MissingFilling(*hole));
+            }
+        }
+        let substitutions = self
+            .holes
+            .iter()
+            .map(|(hole, letter)| (Symbol::new(*letter), fillings[hole - 1].clone()))
+            .collect();
+        Ok(self.expression.substitute_all(&substitutions))
+    }
+
+    /// Whether filling the holes of `premises` and `conclusion` with `fillings` gives a valid
+    /// argument. Holes with the same number share a filling across all the templates.
+    pub fn makes_valid(
+        premises: &[Template],
+        conclusion: &Template,
+        fillings: &[Expression],
+    ) -> Result<bool, TemplateError> {
+        let premises = premises
+            .iter()
+            .map(|premise| premise.fill(fillings))
+            .collect::<Result<Vec<_>, _>>()?;
+        let conclusion = [conclusion.fill(fillings)?];
+        Ok(entails_set(&premises, &conclusion, ConclusionMode::Conjunctive).is_valid())
+    }
+
+    /// Whether the filled formula can be the next line of a proof that has `lines` so far,
+    /// derived by `rule`. The step is correct when the search would derive the formula from those
+    /// lines in one application of the rule, as it derives every line of its own proofs.
+    pub fn is_correct_step(
+        &self,
+        fillings: &[Expression],
+        lines: &[Line],
+        rule: &Rule,
+    ) -> Result<bool, TemplateError> {
+        let expression = self.fill(fillings)?;
+        let node = SearchNode::new(
+            lines.to_vec(),
+            expression.clone(),
+            Rc::new(SearchSettings::default()),
+        );
+        let mut finder = PossibleFinder::new(node.borrow().clone());
+        finder.find();
+        Ok(finder.possibles().iter().any(|possible| {
+            let last = possible.lines.last().unwrap();
+            last.rule == *rule && last.expression == expression
+        }))
+    }
+}
+
+impl Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
@@ -0,0 +1,413 @@
+use std::io::Read;
+
+use crate::{
+    error::TptpError,
+    expression::{Expression, Symbol},
+    generate::letter_for,
+    parser::ParserOptions,
+    proof::{Proof, SearchSettings},
+};
+
+/// A propositional problem read from the TPTP format used by automated theorem provers, with
+/// its atoms renamed to sentence letters.
+///
+/// Both `fof` and `cnf` formulas are read. Formulas with the role `conjecture` give the
+/// conclusion, and those with any other role that asserts them (`axiom`, `hypothesis`,
+/// `negated_conjecture` and so on) give the premises. A problem without a conjecture, such as a
+/// clause set in CNF, asks for the premises to be shown inconsistent, so its conclusion is `⊥`.
+///
+/// # Examples
+///
+/// ```
+/// use propositional_logic_calculator::{proof::parse_expression, tptp::TptpProblem};
+///
+/// let problem = TptpProblem::parse(
+///     "% Modus ponens
+///      fof(rain, axiom, rain => wet).
+///      fof(raining, hypothesis, rain).
+///      fof(goal, conjecture, wet).",
+/// )
+/// .unwrap();
+/// assert_eq!(
+///     problem.atoms,
+///     vec![("rain".to_string(), 'P'.into()), ("wet".to_string(), 'Q'.into())]
+/// );
+/// assert_eq!(problem.premises[0], parse_expression("P>Q").unwrap());
+///
+/// let mut proof = problem.proof();
+/// assert!(proof.search().is_ok());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TptpProblem {
+    pub premises: Vec<Expression>,
+    pub conjecture: Option<Expression>,
+    /// Each atom of the problem with the sentence letter it was given, in order of first use
+    pub atoms: Vec<(String, Symbol)>,
+}
+
+impl TptpProblem {
+    /// Reads a problem from the text of a TPTP file.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `TptpError` if the text is not valid TPTP, uses anything outside propositional
+    /// logic, has more than one conjecture, has more atoms than there are sentence letters or
+    /// nests a formula too deeply.
+    pub fn parse(input: &str) -> Result<Self, TptpError> {
+        let mut parser = TptpParser {
+            tokens: tokenize(input)?,
+            next: 0,
+            depth: 0,
+            problem: TptpProblem {
+                premises: Vec::new(),
+                conjecture: None,
+                atoms: Vec::new(),
+            },
+        };
+        while parser.peek().kind != Kind::End {
+            parser.parse_annotated()?;
+        }
+        Ok(parser.problem)
+    }
+
+    /// Reads a problem from a TPTP file.
+    pub fn read_from(mut reader: impl Read) -> Result<Self, TptpError> {
+        let mut input = String::new();
+        reader.read_to_string(&mut input)?;
+        Self::parse(&input)
+    }
+
+    /// The conjecture, or `⊥` if the problem has none.
+    pub fn conclusion(&self) -> Expression {
+        self.conjecture.clone().unwrap_or(Expression::Bottom)
+    }
+
+    /// A proof of the conclusion from the premises, ready to search with default settings.
+    pub fn proof(&self) -> Proof {
+        self.proof_with_settings(SearchSettings::default())
+    }
+
+    /// A proof of the conclusion from the premises, ready to search with `settings`.
+    pub fn proof_with_settings(&self, settings: SearchSettings) -> Proof {
+        Proof::with_settings(self.premises.clone(), self.conclusion(), settings)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    /// A name starting with a lower case letter or a digit, or one in single quotes
+    Word,
+    /// A name starting with an upper case letter
+    Variable,
+    /// A name starting with `$`, such as `$true`
+    Defined,
+    Open,
+    Close,
+    Comma,
+    Dot,
+    Not,
+    And,
+    Or,
+    Implies,
+    Reverse,
+    Iff,
+    Xor,
+    Nor,
+    Nand,
+    /// `!` or `?`
+    Quantifier,
+    /// Anything else, such as `=`
+    Other,
+    End,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: Kind,
+    text: String,
+    line: usize,
+}
+
+/// Splits `input` into tokens, leaving out whitespace and comments.
+fn tokenize(input: &str) -> Result<Vec<Token>, TptpError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    let mut line = 1;
+    while let Some(c) = chars.next() {
+        let start = line;
+        let (kind, text) = match c {
+            '\n' => {
+                line += 1;
+                continue;
+            }
+            _ if c.is_whitespace() => continue,
+            '%' => {
+                while chars.next_if(|c| *c != '\n').is_some() {}
+                continue;
+            }
+            '/' if chars.next_if_eq(&'*').is_some() => {
+                let mut previous = ' ';
+                loop {
+                    match chars.next() {
+                        Some('/') if previous == '*' => break,
+                        Some(c) => {
+                            line += usize::from(c == '\n');
+                            previous = c;
+                        }
+                        None => return Err(TptpError::Unexpected("end of input".into(), line)),
+                    }
+                }
+                continue;
+            }
+            '\'' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => name.push(c),
+                        None => return Err(TptpError::Unexpected("end of input".into(), line)),
+                    }
+                }
+                (Kind::Word, name)
+            }
+            _ if c.is_alphanumeric() || c == '_' || c == '$' => {
+                let mut name = c.to_string();
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+                    name.push(c);
+                }
+                let kind = match c {
+                    '$' => Kind::Defined,
+                    _ if c.is_uppercase() => Kind::Variable,
+                    _ => Kind::Word,
+                };
+                (kind, name)
+            }
+            '(' => (Kind::Open, "(".into()),
+            ')' => (Kind::Close, ")".into()),
+            ',' => (Kind::Comma, ",".into()),
+            '.' => (Kind::Dot, ".".into()),
+            '&' => (Kind::And, "&".into()),
+            '|' => (Kind::Or, "|".into()),
+            '~' => match chars.next_if(|c| matches!(c, '|' | '&')) {
+                Some('|') => (Kind::Nor, "~|".into()),
+                Some(_) => (Kind::Nand, "~&".into()),
+                None => (Kind::Not, "~".into()),
+            },
+            '=' if chars.next_if_eq(&'>').is_some() => (Kind::Implies, "=>".into()),
+            '<' if chars.next_if_eq(&'~').is_some() => {
+                let text = "<~>";
+                if chars.next_if_eq(&'>').is_none() {
+                    return Err(TptpError::Unexpected(text[..2].into(), line));
+                }
+                (Kind::Xor, text.into())
+            }
+            '<' if chars.next_if_eq(&'=').is_some() => match chars.next_if_eq(&'>') {
+                Some(_) => (Kind::Iff, "<=>".into()),
+                None => (Kind::Reverse, "<=".into()),
+            },
+            '!' | '?' if chars.peek() != Some(&'=') => (Kind::Quantifier, c.to_string()),
+            _ => (Kind::Other, c.to_string()),
+        };
+        tokens.push(Token {
+            kind,
+            text,
+            line: start,
+        });
+    }
+    tokens.push(Token {
+        kind: Kind::End,
+        text: "end of input".into(),
+        line,
+    });
+    Ok(tokens)
+}
+
+struct TptpParser {
+    tokens: Vec<Token>,
+    next: usize,
+    // How deeply the formula being parsed is nested, counted against the parser's depth limit.
+    depth: usize,
+    problem: TptpProblem,
+}
+
+impl TptpParser {
+    /// Goes one level deeper into a formula at `line`, which parsing recurses for, so that
+    /// adversarial input cannot overflow the stack.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TptpError::TooDeeplyNested` if that is deeper than
+    /// `ParserOptions::DEFAULT_MAX_DEPTH`.
+    fn descend(&mut self, line: usize) -> Result<(), TptpError> {
+        self.depth += 1;
+        if self.depth > ParserOptions::DEFAULT_MAX_DEPTH {
+            return Err(TptpError::TooDeeplyNested(
+                ParserOptions::DEFAULT_MAX_DEPTH,
+                line,
+            ));
+        }
+        Ok(())
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.next]
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.next].clone();
+        if token.kind != Kind::End {
+            self.next += 1;
+        }
+        token
+    }
+
+    /// Consumes the next token, which must be of `kind`.
+    fn expect(&mut self, kind: Kind) -> Result<Token, TptpError> {
+        let token = self.advance();
+        if token.kind == kind {
+            Ok(token)
+        } else {
+            Err(unexpected(token))
+        }
+    }
+
+    /// Parses one `fof(name, role, formula).` or `cnf(name, role, clause).`, with any
+    /// annotations after the formula, adding the formula to the problem.
+    fn parse_annotated(&mut self) -> Result<(), TptpError> {
+        let language = self.expect(Kind::Word)?;
+        if !matches!(language.text.as_str(), "fof" | "cnf") {
+            return Err(TptpError::NotPropositional(language.text, language.line));
+        }
+        self.expect(Kind::Open)?;
+        self.expect(Kind::Word)?;
+        self.expect(Kind::Comma)?;
+        let role = self.expect(Kind::Word)?;
+        self.expect(Kind::Comma)?;
+        let formula = self.parse_formula()?;
+        // Annotations say where the formula came from, which does not change the problem
+        if self.peek().kind == Kind::Comma {
+            let mut depth = 0;
+            loop {
+                let token = self.advance();
+                match token.kind {
+                    Kind::Open => depth += 1,
+                    Kind::Close if depth == 0 => {
+                        self.next -= 1;
+                        break;
+                    }
+                    Kind::Close => depth -= 1,
+                    Kind::End => return Err(unexpected(token)),
+                    _ => (),
+                }
+            }
+        }
+        self.expect(Kind::Close)?;
+        self.expect(Kind::Dot)?;
+        match role.text.as_str() {
+            "conjecture" if self.problem.conjecture.is_some() => {
+                Err(TptpError::MultipleConjectures(role.line))
+            }
+            "conjecture" => {
+                self.problem.conjecture = Some(formula);
+                Ok(())
+            }
+            "axiom" | "hypothesis" | "definition" | "assumption" | "lemma" | "theorem"
+            | "corollary" | "negated_conjecture" | "plain" => {
+                self.problem.premises.push(formula);
+                Ok(())
+            }
+            _ => Err(TptpError::UnknownRole(role.text, role.line)),
+        }
+    }
+
+    /// Parses a formula. TPTP needs parentheses around a binary formula inside another, except
+    /// in chains of `&` or of `|`.
+    fn parse_formula(&mut self) -> Result<Expression, TptpError> {
+        let mut left = self.parse_unitary()?;
+        let operator = self.peek().kind;
+        match operator {
+            Kind::And | Kind::Or => {
+                while self.peek().kind == operator {
+                    self.advance();
+                    let right = self.parse_unitary()?.wrap();
+                    left = match operator {
+                        Kind::And => Expression::And(left.wrap(), right),
+                        _ => Expression::Or(left.wrap(), right),
+                    };
+                }
+            }
+            Kind::Implies | Kind::Reverse | Kind::Iff | Kind::Xor | Kind::Nor | Kind::Nand => {
+                self.advance();
+                let (a, b) = (left.wrap(), self.parse_unitary()?.wrap());
+                left = match operator {
+                    Kind::Implies => Expression::Implies(a, b),
+                    Kind::Reverse => Expression::Implies(b, a),
+                    Kind::Iff => Expression::Iff(a, b),
+                    Kind::Xor => Expression::Not(Expression::Iff(a, b).wrap()),
+                    Kind::Nor => Expression::Not(Expression::Or(a, b).wrap()),
+                    _ => Expression::Not(Expression::And(a, b).wrap()),
+                };
+            }
+            _ => return Ok(left),
+        }
+        match self.peek().kind {
+            Kind::And
+            | Kind::Or
+            | Kind::Implies
+            | Kind::Reverse
+            | Kind::Iff
+            | Kind::Xor
+            | Kind::Nor
+            | Kind::Nand => Err(unexpected(self.advance())),
+            _ => Ok(left),
+        }
+    }
+
+    /// Parses an atom, a constant, a negation or a formula in parentheses.
+    fn parse_unitary(&mut self) -> Result<Expression, TptpError> {
+        let token = self.advance();
+        match token.kind {
+            Kind::Not => {
+                self.descend(token.line)?;
+                let operand = self.parse_unitary()?;
+                self.depth -= 1;
+                Ok(Expression::Not(operand.wrap()))
+            }
+            Kind::Open => {
+                self.descend(token.line)?;
+                let formula = self.parse_formula()?;
+                self.depth -= 1;
+                self.expect(Kind::Close)?;
+                Ok(formula)
+            }
+            Kind::Word if self.peek().kind == Kind::Open => {
+                Err(TptpError::NotPropositional(token.text, token.line))
+            }
+            Kind::Word => self.atom(token.text),
+            Kind::Defined => match token.text.as_str() {
+                "$true" => Ok(Expression::Top),
+                "$false" => Ok(Expression::Bottom),
+                _ => Err(TptpError::NotPropositional(token.text, token.line)),
+            },
+            Kind::Variable | Kind::Quantifier => {
+                Err(TptpError::NotPropositional(token.text, token.line))
+            }
+            _ => Err(unexpected(token)),
+        }
+    }
+
+    /// The sentence letter for the atom `name`, giving it the next free one if it is new.
+    fn atom(&mut self, name: String) -> Result<Expression, TptpError> {
+        match letter_for(&mut self.problem.atoms, &name) {
+            Some(letter) => Ok(Expression::Var(letter)),
+            None => Err(TptpError::TooManyAtoms(name)),
+        }
+    }
+}
+
+fn unexpected(token: Token) -> TptpError {
+    match token.kind {
+        Kind::End => TptpError::Unexpected(token.text, token.line),
+        _ => TptpError::Unexpected(format!("'{}'", token.text), token.line),
+    }
+}
@@ -0,0 +1,248 @@
+use std::{
+    fmt::{self, Display},
+    io::{BufRead, Write},
+};
+
+use enum_iterator::all;
+
+use crate::{
+    error::TraceError,
+    expression::Symbol,
+    lines::Rule,
+    proof::{AtomUniverse, IterationUnit, OrIntroduction, SearchSettings},
+};
+
+/// One step taken by a recorded search. `depth` is how many sub proof searches deep the step was
+/// taken, `0` being the top-level search.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TraceEvent {
+    /// A node with `lines` lines was expanded, taking the shared iteration count to `iteration`
+    Expand {
+        depth: usize,
+        iteration: usize,
+        lines: usize,
+    },
+    /// The node just expanded produced `count` candidates after repeated lines were dropped
+    Candidates { depth: usize, count: usize },
+    /// A node with `lines` lines was dropped without expanding it, being over the line limit
+    Pruned { depth: usize, lines: usize },
+    /// A sub proof search opened with an assumption made by `rule` has finished
+    SubProof {
+        depth: usize,
+        rule: Rule,
+        found: bool,
+    },
+}
+
+/// Everything a search did, in order, along with the settings it ran with.
+///
+/// A trace is written as text, one record per line: the settings first and then one event per
+/// line. Since the search is deterministic, replaying a proof's search with the recorded settings
+/// must produce the same events (see `Proof::replay`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trace {
+    pub settings: SearchSettings,
+    pub events: Vec<TraceEvent>,
+}
+
+impl Trace {
+    pub fn new(settings: SearchSettings) -> Self {
+        Trace {
+            settings,
+            events: Vec::new(),
+        }
+    }
+
+    pub(crate) fn record(&mut self, event: TraceEvent) {
+        self.events.push(event);
+    }
+
+    /// Writes the trace in its text format.
+    pub fn write_to(&self, mut writer: impl Write) -> std::io::Result<()> {
+        write!(writer, "{}", self)
+    }
+
+    /// Reads a trace written by `write_to`.
+    pub fn read_from(reader: impl BufRead) -> Result<Self, TraceError> {
+        let mut lines = reader.lines().enumerate();
+        let settings = match lines.next() {
+            Some((_, line)) => parse_settings(&line?).ok_or(TraceError::Malformed(1))?,
+            None => return Err(TraceError::Malformed(1)),
+        };
+        let mut trace = Trace::new(settings);
+        for (i, line) in lines {
+            let event = parse_event(&line?).ok_or(TraceError::Malformed(i + 1))?;
+            trace.record(event);
+        }
+        Ok(trace)
+    }
+}
+
+impl Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let or_introduction = match self.settings.or_introduction {
+            OrIntroduction::Off => "off",
+            OrIntroduction::ConclusionSubformulas => "conclusion",
+            OrIntroduction::AllVariables => "all",
+        };
+        let atoms = match &self.settings.atoms {
+            AtomUniverse::Sequent => "sequent".to_string(),
+            AtomUniverse::Only(atoms) => format!("only:{}", letter_list(atoms)),
+            AtomUniverse::Extended(atoms) => format!("extended:{}", letter_list(atoms)),
+        };
+        let limit = |limit: Option<usize>| match limit {
+            Some(max) => max.to_string(),
+            None => "none".to_string(),
+        };
+        write!(
+            f,
+            "settings {} {} {} {} {} {} {}",
+            self.settings.max_line_length,
+            self.settings.iterations,
+            self.settings.subproof_share,
+            or_introduction,
+            atoms,
+            limit(self.settings.max_candidates),
+            limit(self.settings.max_formula_size)
+        )?;
+        // Left out when it is the default, so traces written before it existed still read
+        match self.settings.iteration_unit {
+            IterationUnit::Expansions => writeln!(f)?,
+            IterationUnit::Candidates => writeln!(f, " candidates")?,
+            IterationUnit::RuleApplications => writeln!(f, " rules")?,
+        }
+        for event in &self.events {
+            writeln!(f, "{}", event)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for TraceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceEvent::Expand {
+                depth,
+                iteration,
+                lines,
+            } => write!(f, "expand {} {} {}", depth, iteration, lines),
+            TraceEvent::Candidates { depth, count } => write!(f, "candidates {} {}", depth, count),
+            TraceEvent::Pruned { depth, lines } => write!(f, "pruned {} {}", depth, lines),
+            TraceEvent::SubProof { depth, rule, found } => write!(
+                f,
+                "subproof {} {} {}",
+                depth,
+                rule,
+                if *found { "found" } else { "failed" }
+            ),
+        }
+    }
+}
+
+/// The sentence letters `atoms`, separated by commas.
+fn letter_list(atoms: &[Symbol]) -> String {
+    atoms
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Reads the sentence letters written by `letter_list`.
+fn parse_letters(atoms: &str) -> Option<Vec<Symbol>> {
+    if atoms.is_empty() {
+        return Some(Vec::new());
+    }
+    atoms
+        .split(',')
+        .map(|atom| {
+            let mut chars = atom.chars();
+            let letter = chars.next().filter(char::is_ascii_uppercase)?;
+            match chars.as_str() {
+                "" => Some(Symbol::new(letter)),
+                digits if digits.chars().all(|c| c.is_ascii_digit()) => {
+                    Some(Symbol::indexed(letter, digits.parse().ok()?))
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn parse_settings(line: &str) -> Option<SearchSettings> {
+    let fields = line
+        .strip_prefix("settings ")?
+        .split(' ')
+        .collect::<Vec<_>>();
+    match fields[..] {
+        [max_line_length, iterations, subproof_share, or_introduction, atoms, max_candidates, max_formula_size, ref unit @ ..]
+            if unit.len() <= 1 =>
+        {
+            Some(SearchSettings {
+                max_line_length: max_line_length.parse().ok()?,
+                iterations: iterations.parse().ok()?,
+                subproof_share: subproof_share.parse().ok()?,
+                or_introduction: match or_introduction {
+                    "off" => OrIntroduction::Off,
+                    "conclusion" => OrIntroduction::ConclusionSubformulas,
+                    "all" => OrIntroduction::AllVariables,
+                    _ => return None,
+                },
+                atoms: match atoms.split_once(':') {
+                    None if atoms == "sequent" => AtomUniverse::Sequent,
+                    Some(("only", atoms)) => AtomUniverse::Only(parse_letters(atoms)?),
+                    Some(("extended", atoms)) => AtomUniverse::Extended(parse_letters(atoms)?),
+                    _ => return None,
+                },
+                max_candidates: parse_limit(max_candidates)?,
+                max_formula_size: parse_limit(max_formula_size)?,
+                iteration_unit: match unit {
+                    [] => IterationUnit::Expansions,
+                    ["candidates"] => IterationUnit::Candidates,
+                    ["rules"] => IterationUnit::RuleApplications,
+                    _ => return None,
+                },
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Reads a limit written as a number, or as `none` for no limit.
+fn parse_limit(field: &str) -> Option<Option<usize>> {
+    match field {
+        "none" => Some(None),
+        max => Some(Some(max.parse().ok()?)),
+    }
+}
+
+fn parse_event(line: &str) -> Option<TraceEvent> {
+    let fields = line.split(' ').collect::<Vec<_>>();
+    let number = |i: usize| fields.get(i)?.parse::<usize>().ok();
+    let event = match fields[0] {
+        "expand" if fields.len() == 4 => TraceEvent::Expand {
+            depth: number(1)?,
+            iteration: number(2)?,
+            lines: number(3)?,
+        },
+        "candidates" if fields.len() == 3 => TraceEvent::Candidates {
+            depth: number(1)?,
+            count: number(2)?,
+        },
+        "pruned" if fields.len() == 3 => TraceEvent::Pruned {
+            depth: number(1)?,
+            lines: number(2)?,
+        },
+        "subproof" if fields.len() == 4 => TraceEvent::SubProof {
+            depth: number(1)?,
+            rule: all::<Rule>().find(|rule| rule.to_string() == fields[2])?,
+            found: match fields[3] {
+                "found" => true,
+                "failed" => false,
+                _ => return None,
+            },
+        },
+        _ => return None,
+    };
+    Some(event)
+}
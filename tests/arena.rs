@@ -0,0 +1,79 @@
+use propositional_logic_calculator::{
+    arena::{ExprArena, Node},
+    expression::Expression,
+    normalize::Normalization,
+    parser::Parser,
+    proof::parse_expression,
+};
+use std::rc::Rc;
+
+#[test]
+fn test_interning_shares_subexpressions() {
+    let mut arena = ExprArena::new();
+    let conditional = arena.intern(&parse_expression("P>Q").unwrap());
+    let conjunction = arena.intern(&parse_expression("(P>Q)&-Q").unwrap());
+    let Node::And(left, right) = arena.node(conjunction) else {
+        panic!("expected a conjunction");
+    };
+    assert_eq!(left, conditional);
+    assert_eq!(
+        arena.node(right),
+        Node::Not(arena.intern(&parse_expression("Q").unwrap()))
+    );
+    // `P`, `Q`, `P > Q`, `-Q` and the conjunction
+    assert_eq!(arena.len(), 5);
+}
+
+#[test]
+fn test_round_trip() {
+    let mut arena = ExprArena::new();
+    for input in ["P", "-(PvQ)", "(P&Q)>(RvS)", "-(-P)"] {
+        let expression = parse_expression(input).unwrap();
+        let id = arena.intern(&expression);
+        assert_eq!(arena.expression(id), expression);
+    }
+    assert_ne!(
+        arena.intern(&parse_expression("P>Q").unwrap()),
+        arena.intern(&parse_expression("Q>P").unwrap())
+    );
+}
+
+#[test]
+fn test_shared_expressions_share_subterms() {
+    let mut arena = ExprArena::new();
+    let premise = Parser::new("P>Q").parse_interned(&mut arena).unwrap();
+    let conclusion = Parser::new("(P>Q)&-(P>Q)")
+        .parse_interned(&mut arena)
+        .unwrap();
+
+    let premise = arena.shared(premise);
+    let conclusion = arena.shared(conclusion);
+    assert_eq!(*conclusion, parse_expression("(P>Q)&-(P>Q)").unwrap());
+    let Expression::And(left, right) = conclusion.as_ref() else {
+        panic!("expected a conjunction");
+    };
+    let Expression::Not(negated) = right.as_ref() else {
+        panic!("expected a negation");
+    };
+    assert!(Rc::ptr_eq(left, &premise));
+    assert!(Rc::ptr_eq(negated, &premise));
+}
+
+#[test]
+fn test_parse_interned_matches_parse() {
+    let mut arena = ExprArena::new();
+    for input in ["P^Q", "-(P↑Q)v(R↓-S)", "(P>Q)&-(P>Q)", "T<->F"] {
+        let id = Parser::new(input).parse_interned(&mut arena).unwrap();
+        assert_eq!(
+            arena.expression(id),
+            parse_expression(input).unwrap(),
+            "{input}"
+        );
+        assert_eq!(id, arena.intern(&parse_expression(input).unwrap()));
+    }
+    let id = Parser::new("--(Q&P)")
+        .normalizing(Normalization::FULL)
+        .parse_interned(&mut arena)
+        .unwrap();
+    assert_eq!(arena.expression(id), parse_expression("P&Q").unwrap());
+}
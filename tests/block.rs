@@ -0,0 +1,66 @@
+use propositional_logic_calculator::{
+    block::{to_blocks, to_lines, ProofBlock},
+    lines::Rule,
+};
+
+use crate::common::create_proof;
+
+/// The rule of the first line of each sub proof directly inside `blocks`.
+fn opening_rules(blocks: &[ProofBlock]) -> Vec<Rule> {
+    blocks
+        .iter()
+        .filter_map(|block| match block {
+            ProofBlock::SubProof(inner) => Some(inner[0].lines()[0].rule.clone()),
+            ProofBlock::Line(_) => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_round_trip() {
+    for (premises, conclusion) in [
+        (vec!["P>(Q>R)"], "Q>(P>R)"),
+        (vec!["PvQ"], "QvP"),
+        (vec!["P>Q", "-Q"], "-P"),
+        (vec!["P&Q"], "Q&P"),
+    ] {
+        let proof = create_proof(&premises, conclusion);
+        let blocks = to_blocks(proof.lines());
+        assert_eq!(to_lines(&blocks), proof.lines());
+        assert_eq!(to_blocks(&to_lines(&blocks)), blocks);
+    }
+}
+
+#[test]
+fn test_nested_sub_proofs() {
+    let proof = create_proof(&["P>(Q>R)"], "Q>(P>R)");
+    let blocks = to_blocks(proof.lines());
+    assert_eq!(
+        opening_rules(&blocks),
+        vec![Rule::ConditionalProofAssumption]
+    );
+    let ProofBlock::SubProof(inner) = &blocks[1] else {
+        panic!("expected a sub proof after the premise");
+    };
+    assert_eq!(opening_rules(inner), vec![Rule::ConditionalProofAssumption]);
+    let last = blocks.last().unwrap().lines();
+    assert_eq!(last[0].rule, Rule::ConditionalProof);
+}
+
+#[test]
+fn test_or_elimination_cases_are_separate_blocks() {
+    let proof = create_proof(&["PvQ"], "QvP");
+    let blocks = to_blocks(proof.lines());
+    assert_eq!(
+        opening_rules(&blocks),
+        vec![Rule::OrEliminationAssumption, Rule::OrEliminationAssumption]
+    );
+}
+
+#[test]
+fn test_flat_proof_has_no_sub_proofs() {
+    let proof = create_proof(&["P&Q"], "Q&P");
+    let blocks = to_blocks(proof.lines());
+    assert!(opening_rules(&blocks).is_empty());
+    assert_eq!(blocks.len(), proof.lines().len());
+}
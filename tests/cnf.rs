@@ -0,0 +1,83 @@
+use propositional_logic_calculator::{
+    cnf::{satisfies_clause, Literal},
+    expression::Symbol,
+    proof::parse_expression,
+    semantics::{all_models, Model},
+};
+
+#[test]
+fn test_tseitin_is_equisatisfiable() {
+    for formula in [
+        "P",
+        "-P",
+        "P&-P",
+        "(P>Q)&P&-Q",
+        "(P<->Q)v(R>-P)",
+        "-(Pv-P)",
+        "(T>P)&(Fv-Q)",
+        "-(P&Q)<->(-Pv-Q)",
+    ] {
+        let formula = parse_expression(formula).unwrap();
+        let tseitin = formula.tseitin();
+        let mut letters = tseitin
+            .clauses
+            .iter()
+            .flatten()
+            .map(|literal| literal.var)
+            .collect::<Vec<_>>();
+        letters.sort();
+        letters.dedup();
+        // Every model of the clauses is a model of the formula, and every model of the formula
+        // extends to one of the clauses
+        let mut satisfiable = false;
+        for model in all_models(&letters) {
+            if tseitin.is_satisfied_by(&model) {
+                assert_eq!(model.evaluate(&formula), Some(true), "{}", formula);
+            }
+            if model.satisfies(&formula) {
+                satisfiable = true;
+                assert!(tseitin.is_satisfied_by(&tseitin.extend(&model)));
+            }
+        }
+        assert_eq!(
+            satisfiable,
+            all_models(&letters).any(|model| tseitin.is_satisfied_by(&model))
+        );
+    }
+}
+
+#[test]
+fn test_tseitin_is_linear() {
+    // Distributing this into CNF would give 2^12 clauses
+    let formula = (0..12)
+        .map(|i| format!("(A{}&B{})", i, i))
+        .collect::<Vec<_>>()
+        .join("v");
+    let tseitin = parse_expression(&formula).unwrap().tseitin();
+    assert_eq!(tseitin.definitions.len(), 23);
+    assert!(tseitin.clauses.len() <= 3 * 23 + 1);
+    assert_eq!(tseitin.clauses.last(), Some(&vec![tseitin.root]));
+}
+
+#[test]
+fn test_fresh_atoms_avoid_the_formula() {
+    let x1 = Symbol::indexed('X', 1);
+    let formula = parse_expression("X1&X2").unwrap();
+    let tseitin = formula.tseitin();
+    let (fresh, definition) = &tseitin.definitions[0];
+    assert!(*fresh != x1 && *fresh != Symbol::indexed('X', 2));
+    assert_eq!(*definition, formula);
+    assert_eq!(tseitin.root, Literal::new(*fresh, true));
+}
+
+#[test]
+fn test_literals() {
+    let literal = Literal::new('P', false);
+    assert_eq!(literal.to_string(), "~P");
+    assert_eq!(literal.negate(), Literal::new('P', true));
+    let model = Model::from_iter([('P', true)]);
+    assert_eq!(literal.evaluate(&model), Some(false));
+    assert_eq!(Literal::new('Q', true).evaluate(&model), None);
+    assert!(satisfies_clause(&model, &[literal, literal.negate()]));
+    assert!(!satisfies_clause(&model, &[]));
+}
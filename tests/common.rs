@@ -0,0 +1,53 @@
+//! Helpers shared by the test modules.
+
+use propositional_logic_calculator::{
+    expression::Expression,
+    proof::{parse_expression, Proof, SearchSettings},
+};
+use rand::{rngs::StdRng, Rng};
+
+/// Each of `expressions` parsed.
+pub fn parse_all(expressions: &[&str]) -> Vec<Expression> {
+    expressions
+        .iter()
+        .map(|x| parse_expression(x).unwrap())
+        .collect()
+}
+
+/// A proof of `conclusion` from `premises` that searches with `settings`, before searching.
+pub fn proof_with_settings(premises: &[&str], conclusion: &str, settings: SearchSettings) -> Proof {
+    Proof::with_settings(
+        parse_all(premises),
+        parse_expression(conclusion).unwrap(),
+        settings,
+    )
+}
+
+/// A proof of `conclusion` from `premises`, searched with the default settings.
+pub fn create_proof(premises: &[&str], conclusion: &str) -> Proof {
+    let mut proof = proof_with_settings(premises, conclusion, SearchSettings::default());
+    proof.search().unwrap();
+    proof
+}
+
+/// A random formula of at most `depth` nested connectives over `P`, `Q` and `R` and the constants.
+pub fn random_expression(rng: &mut StdRng, depth: usize) -> Expression {
+    let choice = if depth == 0 {
+        rng.gen_range(0..5)
+    } else {
+        rng.gen_range(0..10)
+    };
+    let mut operand = || random_expression(rng, depth.saturating_sub(1)).wrap();
+    match choice {
+        0 => Expression::Var('P'.into()),
+        1 => Expression::Var('Q'.into()),
+        2 => Expression::Var('R'.into()),
+        3 => Expression::Top,
+        4 => Expression::Bottom,
+        5 => Expression::Not(operand()),
+        6 => Expression::And(operand(), operand()),
+        7 => Expression::Or(operand(), operand()),
+        8 => Expression::Implies(operand(), operand()),
+        _ => Expression::Iff(operand(), operand()),
+    }
+}
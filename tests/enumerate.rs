@@ -0,0 +1,73 @@
+use propositional_logic_calculator::{
+    enumerate::{conclusions, conclusions_with_settings},
+    expression::Expression,
+    proof::{parse_expression, SearchSettings},
+};
+
+fn parse_premises(premises: Vec<&str>) -> Vec<Expression> {
+    premises
+        .into_iter()
+        .map(|p| parse_expression(p).unwrap())
+        .collect()
+}
+
+fn small_settings() -> SearchSettings {
+    SearchSettings {
+        max_line_length: 8,
+        iterations: 2_000,
+        ..Default::default()
+    }
+}
+
+fn conclusion_strings(premises: Vec<&str>, max_size: usize) -> Vec<String> {
+    conclusions_with_settings(&parse_premises(premises), max_size, small_settings())
+        .iter()
+        .map(|proof| proof.conclusion().to_string())
+        .collect()
+}
+
+#[test]
+fn test_conclusions_of_conjunction() {
+    let found = conclusion_strings(vec!["P&Q"], 3);
+    assert_eq!(&found[..4], ["P", "Q", "~~P", "~~Q"]);
+    for expected in ["(Q & P)", "(P v Q)", "(Q -> P)"] {
+        assert!(found.contains(&expected.to_string()), "{expected} missing");
+    }
+    assert!(!found.contains(&"~P".to_string()));
+}
+
+#[test]
+fn test_conclusions_are_sorted_by_size() {
+    let premises = parse_premises(vec!["P>Q", "P"]);
+    let proofs = conclusions_with_settings(&premises, 3, small_settings());
+    let sizes = proofs
+        .iter()
+        .map(|proof| proof.conclusion().size())
+        .collect::<Vec<_>>();
+    let mut sorted = sizes.clone();
+    sorted.sort();
+    assert_eq!(sizes, sorted);
+}
+
+#[test]
+fn test_conclusions_are_proved() {
+    let premises = parse_premises(vec!["P>Q", "P"]);
+    let proofs = conclusions(&premises, 2);
+    assert_eq!(proofs.len(), 2);
+    for proof in &proofs {
+        assert_eq!(proof.assumptions(), &premises[..]);
+        let last = proof.lines().last().unwrap();
+        assert_eq!(&last.expression, proof.conclusion());
+    }
+}
+
+#[test]
+fn test_conclusions_respect_settings() {
+    let premises = parse_premises(vec!["P&Q"]);
+    let settings = SearchSettings {
+        iterations: 0,
+        ..Default::default()
+    };
+    // Every conclusion of size one needs at least one step
+    assert!(conclusions_with_settings(&premises, 1, settings).is_empty());
+}
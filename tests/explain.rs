@@ -0,0 +1,42 @@
+use propositional_logic_calculator::{explain::explain_invalidity, proof::parse_expression};
+
+use crate::common::parse_all;
+
+#[test]
+fn test_affirming_the_consequent_is_explained() {
+    let assumptions = parse_all(&["P>Q", "Q"]);
+    let conclusion = parse_expression("P").unwrap();
+    let explanation = explain_invalidity(&assumptions, &conclusion).unwrap();
+
+    assert_eq!(explanation.row(), 2);
+    assert_eq!(explanation.countermodel().get('P'), Some(false));
+    assert_eq!(explanation.countermodel().get('Q'), Some(true));
+    assert_eq!(
+        explanation.summary(),
+        "The argument is invalid: when P is false and Q is true, every assumption is true but the conclusion P is false."
+    );
+}
+
+#[test]
+fn test_valid_argument_has_no_explanation() {
+    let assumptions = parse_all(&["P>Q", "P"]);
+    let conclusion = parse_expression("Q").unwrap();
+    assert!(explain_invalidity(&assumptions, &conclusion).is_none());
+}
+
+#[test]
+fn test_explanation_formats() {
+    let assumptions = parse_all(&["P>Q"]);
+    let conclusion = parse_expression("Q>P").unwrap();
+    let explanation = explain_invalidity(&assumptions, &conclusion).unwrap();
+
+    let text = explanation.to_text();
+    assert!(text.lines().any(|line| line.starts_with("> F | T")));
+
+    let markdown = explanation.to_markdown();
+    assert!(markdown.contains("| **F** | **T** | **T** | **F** |"));
+
+    let html = explanation.to_html();
+    assert!(html.contains("<tr class=\"countermodel\"><td>F</td><td>T</td>"));
+    assert!(html.contains("<th>(P -&gt; Q)</th>"));
+}
@@ -0,0 +1,228 @@
+use propositional_logic_calculator::{
+    error::EvalError, expression::Expression, proof::parse_expression, prop, semantics::all_models,
+};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+fn assert_prop(built: Expression, input: &str) {
+    assert_eq!(built, parse_expression(input).unwrap(), "{input}");
+}
+
+#[test]
+fn test_prop_connectives() {
+    assert_prop(prop!(P), "P");
+    assert_prop(prop!(T & F), "T&F");
+    assert_prop(prop!(-P), "-P");
+    assert_prop(prop!(~!P), "--P");
+    assert_prop(prop!(P v Q | R), "PvQvR");
+    assert_prop(prop!(P > Q -> R), "P>(Q>R)");
+    assert_prop(prop!(P <-> Q = R), "P<->(Q<->R)");
+}
+
+#[test]
+fn test_prop_precedence() {
+    assert_prop(prop!(A & B v C), "(A&B)vC");
+    assert_prop(prop!(A v B & C), "Av(B&C)");
+    assert_prop(prop!(A & B & C), "(A&B)&C");
+    assert_prop(prop!(A & (B & C)), "A&(B&C)");
+    assert_prop(prop!(-A > B v C), "(-A)>(BvC)");
+    assert_prop(prop!(-(A > B) <-> A & -B), "-(A>B)<->(A&-B)");
+    assert_prop(
+        prop!(((P & Q) v -(R > S)) > (P > Q)),
+        "((P&Q)v-(R>S))>(P>Q)",
+    );
+}
+
+#[test]
+fn test_prop_lowercase_and_indexed() {
+    assert_prop(prop!(p & q1 > P12), "p&q1>P12");
+}
+
+#[test]
+fn test_distance() {
+    let distance = |a: &str, b: &str| {
+        parse_expression(a)
+            .unwrap()
+            .distance(&parse_expression(b).unwrap())
+    };
+    assert_eq!(distance("P>(Q&R)", "P>(Q&R)"), 0);
+    assert_eq!(distance("--P", "P"), 2);
+    assert_eq!(distance("P<->Q", "P>Q"), 1);
+    assert_eq!(distance("P&Q", "-(P&-Q)"), 2);
+    assert_eq!(distance("P", "Q&R"), 3);
+    assert_eq!(distance("P>Q", "Q>P"), distance("Q>P", "P>Q"));
+}
+
+#[test]
+fn test_eval() {
+    let assignment = HashMap::from([
+        ("P".to_string(), true),
+        ("Q".to_string(), false),
+        ("R2".to_string(), true),
+    ]);
+    let eval = |x: &str| parse_expression(x).unwrap().eval(&assignment);
+    assert_eq!(eval("P&-Q"), Ok(true));
+    assert_eq!(eval("Q>R2"), Ok(true));
+    assert_eq!(eval("(P<->Q)vF"), Ok(false));
+    assert_eq!(eval("T&R2"), Ok(true));
+    // Unassigned letters are errors even where the rest decides the value
+    assert_eq!(
+        eval("Q&S"),
+        Err(EvalError::UnassignedVariable("S".to_string()))
+    );
+
+    // Agrees with models wherever every letter is assigned
+    let formula = parse_expression("(P>Q)<->(-Q>-P)&R").unwrap();
+    for model in all_models(&['P'.into(), 'Q'.into(), 'R'.into()]) {
+        let assignment = model
+            .iter()
+            .map(|(var, value)| (var.to_string(), value))
+            .collect();
+        assert_eq!(formula.eval(&assignment).ok(), model.evaluate(&formula));
+    }
+}
+
+#[test]
+fn test_substitute() {
+    let expr = |x: &str| parse_expression(x).unwrap();
+    // Every occurrence, at any depth
+    assert_eq!(
+        expr("-(P&Q)<->(-Pv-Q)").substitute('P', &expr("R>S")),
+        expr("-((R>S)&Q)<->(-(R>S)v-Q)")
+    );
+    // Letters that do not occur, and constants, are left alone
+    assert_eq!(expr("T&Q").substitute('P', &expr("R")), expr("T&Q"));
+
+    // Instantiating an axiom schema
+    let schema = expr("(A>(B>C))>((A>B)>(A>C))");
+    let instance = schema.substitute_all(&HashMap::from([
+        ('A'.into(), expr("P")),
+        ('B'.into(), expr("P>P")),
+        ('C'.into(), expr("P")),
+    ]));
+    assert_eq!(instance, expr("(P>((P>P)>P))>((P>(P>P))>(P>P))"));
+
+    // Simultaneous, so replacements are not substituted into again
+    let renamed = expr("P>Q").substitute_all(&HashMap::from([
+        ('P'.into(), expr("Q")),
+        ('Q'.into(), expr("-P")),
+    ]));
+    assert_eq!(renamed, expr("Q>-P"));
+}
+
+#[test]
+fn test_is_equivalent_to() {
+    let expr = |x: &str| parse_expression(x).unwrap();
+    for (left, right) in [
+        ("P<->Q", "(P>Q)&(Q>P)"),
+        ("Pv(Q&R)", "(PvQ)&(PvR)"),
+        ("P", "Pv(Q&-Q)"),
+        ("T", "Rv-R"),
+        ("(P&Q)v(P&-Q)", "P"),
+    ] {
+        assert!(
+            expr(left).is_equivalent_to(&expr(right)),
+            "{} {}",
+            left,
+            right
+        );
+        assert!(
+            expr(right).is_equivalent_to(&expr(left)),
+            "{} {}",
+            right,
+            left
+        );
+    }
+    for (left, right) in [("P", "Q"), ("P>Q", "P&Q"), ("F", "P&-Q")] {
+        assert!(
+            !expr(left).is_equivalent_to(&expr(right)),
+            "{} {}",
+            left,
+            right
+        );
+    }
+
+    // What the rewriting functions return means the same as what they were given
+    let formula = expr("-((P>Q)&(RvT))<->(-P&(Q&Q))");
+    assert!(formula.simplify().is_equivalent_to(&formula));
+    assert!(formula.minimize().is_equivalent_to(&formula));
+}
+
+#[test]
+fn test_is_isomorphic_to() {
+    let expr = |x: &str| parse_expression(x).unwrap();
+    let formula = expr("(P>Q)&(-Q<->(RvP))");
+    let renaming = formula
+        .is_isomorphic_to(&expr("(B>A)&(-A<->(P1vB))"))
+        .unwrap();
+    assert_eq!(renaming.len(), 3);
+    let substitutions = renaming
+        .iter()
+        .map(|(from, to)| (*from, Expression::Var(*to)))
+        .collect();
+    assert_eq!(
+        formula.substitute_all(&substitutions),
+        expr("(B>A)&(-A<->(P1vB))")
+    );
+    assert!(formula.is_isomorphic_to(&formula).is_some());
+
+    // Letters must stay distinct, and only letters may change
+    assert_eq!(expr("P&Q").is_isomorphic_to(&expr("P&P")), None);
+    assert_eq!(expr("P&P").is_isomorphic_to(&expr("P&Q")), None);
+    assert_eq!(expr("P&Q").is_isomorphic_to(&expr("PvQ")), None);
+    assert_eq!(expr("P&T").is_isomorphic_to(&expr("P&Q")), None);
+    assert_eq!(expr("(P&Q)&R").is_isomorphic_to(&expr("P&(Q&R)")), None);
+}
+
+#[test]
+fn test_atoms() {
+    let atoms = |x: &str| {
+        parse_expression(x)
+            .unwrap()
+            .atoms()
+            .into_iter()
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(atoms("(P>Q)&(-Q<->(RvP))"), ["P", "Q", "R"]);
+    assert_eq!(atoms("p&(P1vP12)"), ["P1", "P12", "p"]);
+    assert_eq!(atoms("T>F"), Vec::<String>::new());
+    assert!(Expression::Top.atoms().is_empty());
+}
+
+#[test]
+fn test_eq_hash_ord() {
+    let expr = |x: &str| parse_expression(x).unwrap();
+    let seen = ["P&Q", "(P&Q)", "Q&P", "P&Q"]
+        .into_iter()
+        .map(expr)
+        .collect::<HashSet<_>>();
+    assert_eq!(seen.len(), 2);
+    assert!(seen.contains(&expr("Q&P")));
+
+    // Connectives in the order they are declared, then operands from the left, with a letter
+    // before its subscripts
+    let sorted = [
+        "~P", "PvQ", "P&R", "P", "P&Q", "Q", "T", "P1", "F", "P>Q", "P<->Q",
+    ]
+    .into_iter()
+    .map(expr)
+    .collect::<BTreeSet<_>>()
+    .into_iter()
+    .map(|expression| expression.to_string())
+    .collect::<Vec<_>>();
+    assert_eq!(
+        sorted,
+        [
+            "(P & Q)",
+            "(P & R)",
+            "(P v Q)",
+            "(P -> Q)",
+            "(P <-> Q)",
+            "~P",
+            "P",
+            "P1",
+            "Q",
+            "⊤",
+            "⊥"
+        ]
+    );
+}
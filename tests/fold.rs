@@ -0,0 +1,106 @@
+use propositional_logic_calculator::{
+    expression::Expression,
+    fold::{fold_operands, visit_operands, Fold, Visitor},
+    proof::parse_expression,
+};
+
+/// Negation normal form, written as a downstream pass would be.
+struct Nnf;
+
+impl Fold for Nnf {
+    fn fold(&mut self, expression: &Expression) -> Expression {
+        let not = |expression: &Expression| Expression::Not(expression.clone().wrap());
+        match expression {
+            Expression::Implies(left, right) => {
+                Expression::Or(self.fold(&not(left)).wrap(), self.fold(right).wrap())
+            }
+            Expression::Iff(left, right) => self.fold(&Expression::And(
+                Expression::Implies(left.clone(), right.clone()).wrap(),
+                Expression::Implies(right.clone(), left.clone()).wrap(),
+            )),
+            Expression::Not(inner) => match inner.as_ref() {
+                Expression::Not(doubled) => self.fold(doubled),
+                Expression::And(left, right) => {
+                    Expression::Or(self.fold(&not(left)).wrap(), self.fold(&not(right)).wrap())
+                }
+                Expression::Or(left, right) => {
+                    Expression::And(self.fold(&not(left)).wrap(), self.fold(&not(right)).wrap())
+                }
+                Expression::Implies(left, right) => {
+                    Expression::And(self.fold(left).wrap(), self.fold(&not(right)).wrap())
+                }
+                Expression::Iff(..) => {
+                    let unfolded = self.fold(inner);
+                    self.fold(&not(&unfolded))
+                }
+                Expression::Top => Expression::Bottom,
+                Expression::Bottom => Expression::Top,
+                Expression::Var(_) => expression.clone(),
+            },
+            _ => fold_operands(self, expression),
+        }
+    }
+}
+
+/// Checks that only letters are negated and no conditionals remain.
+struct IsNnf(bool);
+
+impl Visitor for IsNnf {
+    fn visit(&mut self, expression: &Expression) {
+        match expression {
+            Expression::Not(inner) if !matches!(inner.as_ref(), Expression::Var(_)) => {
+                self.0 = false
+            }
+            Expression::Implies(..) | Expression::Iff(..) => self.0 = false,
+            _ => visit_operands(self, expression),
+        }
+    }
+}
+
+#[test]
+fn test_fold_nnf() {
+    for input in ["-(P&(Q>-R))", "-(P<->Q)v--R", "(P>Q)<->(-Qv-F)", "P"] {
+        let expression = parse_expression(input).unwrap();
+        let nnf = Nnf.fold(&expression);
+        let mut check = IsNnf(true);
+        check.visit(&nnf);
+        assert!(check.0, "{input} became {nnf}");
+        assert!(nnf.is_equivalent_to(&expression), "{input} became {nnf}");
+    }
+    assert_eq!(
+        Nnf.fold(&parse_expression("-(P&(Q>-R))").unwrap()),
+        parse_expression("-Pv(Q&R)").unwrap()
+    );
+}
+
+#[test]
+fn test_default_passes_change_nothing() {
+    struct Identity;
+    impl Fold for Identity {}
+    struct Nodes(Vec<String>);
+    impl Visitor for Nodes {
+        fn visit(&mut self, expression: &Expression) {
+            self.0.push(expression.to_string());
+            visit_operands(self, expression);
+        }
+    }
+
+    let expression = parse_expression("(P>-Q)<->(TvR1)").unwrap();
+    assert_eq!(Identity.fold(&expression), expression);
+    let mut nodes = Nodes(Vec::new());
+    nodes.visit(&expression);
+    assert_eq!(nodes.0.len(), expression.size());
+    assert_eq!(
+        nodes.0,
+        [
+            "((P -> ~Q) <-> (⊤ v R1))",
+            "(P -> ~Q)",
+            "P",
+            "~Q",
+            "Q",
+            "(⊤ v R1)",
+            "⊤",
+            "R1"
+        ]
+    );
+}
@@ -0,0 +1,96 @@
+use enum_iterator::all;
+use propositional_logic_calculator::{
+    forms::{argument_forms, ArgumentForm},
+    proof::parse_expression,
+    semantics::{entails_set, ConclusionMode},
+};
+
+fn forms(premises: &[&str], conclusion: &str) -> Vec<ArgumentForm> {
+    let premises = premises
+        .iter()
+        .map(|p| parse_expression(p).unwrap())
+        .collect::<Vec<_>>();
+    argument_forms(&premises, &parse_expression(conclusion).unwrap())
+}
+
+#[test]
+fn test_named_forms() {
+    assert_eq!(forms(&["P>Q", "P"], "Q"), vec![ArgumentForm::ModusPonens]);
+    assert_eq!(
+        forms(&["-Q", "P>Q"], "-P"),
+        vec![ArgumentForm::ModusTollens]
+    );
+    assert_eq!(
+        forms(&["(P&R)>Q", "Q>-S"], "(P&R)>-S"),
+        vec![ArgumentForm::HypotheticalSyllogism]
+    );
+    assert_eq!(
+        forms(&["PvQ", "P>R", "Q>S"], "RvS"),
+        vec![ArgumentForm::ConstructiveDilemma]
+    );
+    assert_eq!(
+        forms(&["P>R", "Q>S", "-Rv-S"], "-Pv-Q"),
+        vec![ArgumentForm::DestructiveDilemma]
+    );
+    assert_eq!(
+        forms(&["P>R", "Q>R", "PvQ"], "R"),
+        vec![ArgumentForm::SimpleDilemma]
+    );
+    assert_eq!(
+        forms(&["PvQ", "-P"], "Q"),
+        vec![ArgumentForm::DisjunctiveSyllogism]
+    );
+}
+
+#[test]
+fn test_fallacies() {
+    assert_eq!(
+        forms(&["P>Q", "Q"], "P"),
+        vec![ArgumentForm::AffirmingTheConsequent]
+    );
+    assert_eq!(
+        forms(&["P>Q", "-P"], "-Q"),
+        vec![ArgumentForm::DenyingTheAntecedent]
+    );
+    assert_eq!(
+        forms(&["PvQ", "P"], "-Q"),
+        vec![ArgumentForm::AffirmingADisjunct]
+    );
+}
+
+#[test]
+fn test_no_form() {
+    assert!(forms(&["P>Q", "R"], "Q").is_empty());
+    assert!(forms(&["P>Q"], "Q").is_empty());
+    assert!(forms(&["P>Q", "P", "R"], "Q").is_empty());
+}
+
+#[test]
+fn test_validity_agrees_with_semantics() {
+    // Instantiating each form with distinct letters gives an argument that is valid exactly when
+    // the form is
+    let instances = [
+        (vec!["P>Q", "P"], "Q"),
+        (vec!["P>Q", "-Q"], "-P"),
+        (vec!["P>Q", "Q>R"], "P>R"),
+        (vec!["PvQ", "-P"], "Q"),
+        (vec!["P>R", "Q>S", "PvQ"], "RvS"),
+        (vec!["P>R", "Q>S", "-Rv-S"], "-Pv-Q"),
+        (vec!["P>R", "Q>R", "PvQ"], "R"),
+        (vec!["P>Q", "Q"], "P"),
+        (vec!["P>Q", "-P"], "-Q"),
+        (vec!["PvQ", "P"], "-Q"),
+    ];
+    assert_eq!(instances.len(), all::<ArgumentForm>().count());
+    for (premises, conclusion) in instances {
+        let found = forms(&premises, conclusion);
+        assert_eq!(found.len(), 1);
+        let premises = premises
+            .iter()
+            .map(|p| parse_expression(p).unwrap())
+            .collect::<Vec<_>>();
+        let conclusion = [parse_expression(conclusion).unwrap()];
+        let valid = entails_set(&premises, &conclusion, ConclusionMode::Conjunctive).is_valid();
+        assert_eq!(found[0].is_valid(), valid, "{}", found[0]);
+    }
+}
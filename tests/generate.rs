@@ -0,0 +1,215 @@
+use propositional_logic_calculator::{
+    expression::{Expression, Symbol},
+    generate::{
+        formula, invalid_sequent, mutate, valid_sequent, ConnectiveWeights, GeneratorSettings,
+        MutationKind, MutationSettings,
+    },
+    proof::parse_expression,
+    sat::is_valid,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn test_invalid_sequent_countermodel() {
+    let mut rng = StdRng::seed_from_u64(1967);
+    let settings = GeneratorSettings {
+        atoms: 4,
+        premises: 3,
+        max_depth: 3,
+        ..Default::default()
+    };
+    for _ in 0..200 {
+        let sequent = invalid_sequent(&mut rng, &settings);
+        assert_eq!(sequent.assumptions.len(), 3);
+        for assumption in &sequent.assumptions {
+            assert_eq!(sequent.countermodel.evaluate(assumption), Some(true));
+        }
+        assert_eq!(
+            sequent.countermodel.evaluate(&sequent.conclusion),
+            Some(false)
+        );
+    }
+}
+
+#[test]
+fn test_invalid_sequent_uses_requested_atoms() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let settings = GeneratorSettings {
+        atoms: 2,
+        ..Default::default()
+    };
+    let sequent = invalid_sequent(&mut rng, &settings);
+    let atoms = sequent
+        .countermodel
+        .iter()
+        .map(|(var, _)| var)
+        .collect::<Vec<_>>();
+    assert_eq!(atoms, vec![Symbol::new('P'), Symbol::new('Q')]);
+}
+
+#[test]
+fn test_mutate_single_edits() {
+    let mut rng = StdRng::seed_from_u64(2022);
+    let expression = parse_expression("P>(Q&R)").unwrap();
+    let settings = MutationSettings {
+        count: usize::MAX,
+        ..Default::default()
+    };
+    let mutations = mutate(&mut rng, &expression, &settings);
+    // Negating each of the 5 subformulas, 3 flips of each connective and one swap of each
+    assert_eq!(mutations.len(), 5 + 6 + 2);
+    for mutation in &mutations {
+        assert_ne!(mutation.expression, expression);
+        if mutation.kind == MutationKind::SwapOperands {
+            continue;
+        }
+        assert_eq!(
+            mutation.expression.distance(&expression),
+            1,
+            "{}",
+            mutation.expression
+        );
+    }
+    let expected = parse_expression("P>(R&Q)").unwrap();
+    assert!(mutations
+        .iter()
+        .any(|mutation| mutation.expression == expected
+            && mutation.kind == MutationKind::SwapOperands));
+
+    let only_flips = MutationSettings {
+        count: 2,
+        negate_subformulas: false,
+        swap_operands: false,
+        ..Default::default()
+    };
+    let mutations = mutate(&mut rng, &expression, &only_flips);
+    assert_eq!(mutations.len(), 2);
+    assert!(mutations
+        .iter()
+        .all(|mutation| mutation.kind == MutationKind::FlipConnective));
+}
+
+#[test]
+fn test_mutate_reports_validity() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let settings = MutationSettings {
+        count: usize::MAX,
+        premises: vec![parse_expression("P&Q").unwrap()],
+        ..Default::default()
+    };
+    let mutations = mutate(&mut rng, &parse_expression("PvQ").unwrap(), &settings);
+    let report = |input: &str| {
+        let expression = parse_expression(input).unwrap();
+        let mutation = mutations
+            .iter()
+            .find(|mutation| mutation.expression == expression)
+            .unwrap();
+        (mutation.valid, mutation.preserves_validity)
+    };
+    assert_eq!(report("P&Q"), (true, true));
+    assert_eq!(report("QvP"), (true, true));
+    assert_eq!(report("-(PvQ)"), (false, false));
+    assert_eq!(report("-PvQ"), (true, true));
+    assert_eq!(report("P<->Q"), (true, true));
+}
+
+fn depth(expression: &Expression) -> usize {
+    match expression {
+        Expression::And(left, right)
+        | Expression::Or(left, right)
+        | Expression::Implies(left, right)
+        | Expression::Iff(left, right) => 1 + depth(left).max(depth(right)),
+        Expression::Not(expr) => 1 + depth(expr),
+        Expression::Var(_) | Expression::Top | Expression::Bottom => 0,
+    }
+}
+
+#[test]
+fn test_formula_follows_settings() {
+    let mut rng = StdRng::seed_from_u64(2053);
+    let settings = GeneratorSettings {
+        atoms: 2,
+        max_depth: 4,
+        connectives: ConnectiveWeights {
+            not: 1,
+            and: 0,
+            or: 0,
+            implies: 0,
+            iff: 3,
+        },
+        ..Default::default()
+    };
+    let mut seen_iff = false;
+    for _ in 0..200 {
+        let expression = formula(&mut rng, &settings);
+        assert!(depth(&expression) <= 4, "{expression}");
+        assert!(expression
+            .atoms()
+            .iter()
+            .all(|atom| atom == "P" || atom == "Q"));
+        let written = expression.to_string();
+        assert!(!written.contains(['&', 'v']) && !written.contains(" -> "));
+        seen_iff |= written.contains("<->");
+    }
+    assert!(seen_iff);
+
+    // Without any connective every formula is a letter
+    let settings = GeneratorSettings {
+        connectives: ConnectiveWeights {
+            not: 0,
+            and: 0,
+            or: 0,
+            implies: 0,
+            iff: 0,
+        },
+        ..settings
+    };
+    assert!(matches!(formula(&mut rng, &settings), Expression::Var(_)));
+
+    // Weights may add up to more than a `u32` holds
+    let settings = GeneratorSettings {
+        connectives: ConnectiveWeights {
+            not: 0,
+            and: u32::MAX,
+            or: u32::MAX,
+            implies: 0,
+            iff: u32::MAX,
+        },
+        ..settings
+    };
+    for _ in 0..50 {
+        let written = formula(&mut rng, &settings).to_string();
+        assert!(
+            !written.contains('~') && !written.contains(" -> "),
+            "{written}"
+        );
+    }
+}
+
+#[test]
+fn test_valid_sequent() {
+    let mut rng = StdRng::seed_from_u64(2053);
+    let settings = GeneratorSettings {
+        atoms: 4,
+        premises: 3,
+        max_depth: 3,
+        ..Default::default()
+    };
+    for _ in 0..100 {
+        let sequent = valid_sequent(&mut rng, &settings).unwrap();
+        assert_eq!(sequent.assumptions.len(), 3);
+        assert!(is_valid(&sequent.assumptions, &sequent.conclusion));
+        // The premises are consistent
+        assert!(!is_valid(
+            &sequent.assumptions,
+            &parse_expression("F").unwrap()
+        ));
+    }
+
+    let settings = GeneratorSettings {
+        premises: 0,
+        ..settings
+    };
+    let sequent = valid_sequent(&mut rng, &settings).unwrap();
+    assert!(is_valid(&[], &sequent.conclusion));
+}
@@ -0,0 +1,63 @@
+use propositional_logic_calculator::{
+    error::ParserError, incremental::Document, parser::Parser, proof::parse_expression,
+};
+
+#[test]
+fn test_edit_atom() {
+    let mut document = Document::parse("(P & Q) > R v S").unwrap();
+    assert_eq!(document.edit(5..6, "T").unwrap(), 5..6);
+    assert_eq!(
+        *document.expression(),
+        parse_expression("(P&T)>RvS").unwrap()
+    );
+    // Growing the letter into a bracketed formula moves everything after it
+    assert_eq!(document.edit(10..11, "(R&Q)").unwrap(), 10..15);
+    assert_eq!(document.text(), "(P & T) > (R&Q) v S");
+    assert_eq!(document.tree().children[1].children[1].span, 18..19);
+}
+
+#[test]
+fn test_edit_falls_back_to_full_parse() {
+    let mut document = Document::parse("P & Q").unwrap();
+    // `Q > R` in place of `Q` would group differently from a single letter
+    assert_eq!(document.edit(4..5, "Q > R").unwrap(), 0..9);
+    assert_eq!(*document.expression(), parse_expression("(P&Q)>R").unwrap());
+}
+
+#[test]
+fn test_failed_edit_leaves_document() {
+    let mut document = Document::parse("-(P v Q)").unwrap();
+    assert!(matches!(
+        document.edit(2..3, "&"),
+        Err(ParserError::ExpectedLeftOperand(2))
+    ));
+    assert_eq!(document.text(), "-(P v Q)");
+    assert_eq!(*document.expression(), parse_expression("-(PvQ)").unwrap());
+}
+
+#[test]
+fn test_edits_match_a_fresh_parse() {
+    let original = "-(P & (Q v -R)) <-> ((S) > T & P)";
+    let length = original.chars().count();
+    for start in 0..=length {
+        for end in start..=(start + 2).min(length) {
+            for replacement in ["", "P", "(Q)", "-R", "Q & R", ")", "("] {
+                let mut document = Document::parse(original).unwrap();
+                let result = document.edit(start..end, replacement);
+                let text = original
+                    .chars()
+                    .take(start)
+                    .chain(replacement.chars())
+                    .chain(original.chars().skip(end))
+                    .collect::<String>();
+                match Parser::new(&text).parse_spanned() {
+                    Ok(tree) => {
+                        assert!(result.is_ok(), "{text}");
+                        assert_eq!(*document.tree(), tree, "{text}");
+                    }
+                    Err(_) => assert!(result.is_err(), "{text}"),
+                }
+            }
+        }
+    }
+}
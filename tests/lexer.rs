@@ -0,0 +1,136 @@
+use propositional_logic_calculator::{
+    error::ParserError,
+    expression::Symbol,
+    lexer::{AtomMode, Lexer, TokenKind},
+};
+
+#[test]
+fn test_token_kinds() {
+    let kinds = Lexer::new("(A & T) v -F > B <-> C")
+        .map(|token| token.unwrap().kind)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Open,
+            TokenKind::Atom('A'.into()),
+            TokenKind::And,
+            TokenKind::Top,
+            TokenKind::Close,
+            TokenKind::Or,
+            TokenKind::Not,
+            TokenKind::Bottom,
+            TokenKind::Implies,
+            TokenKind::Atom('B'.into()),
+            TokenKind::Iff,
+            TokenKind::Atom('C'.into()),
+        ]
+    );
+}
+
+#[test]
+fn test_circuit_token_kinds() {
+    let kinds = Lexer::new("A ^ B ⊕ C ↑ D ⊼ E ↓ G ⊽ H")
+        .map(|token| token.unwrap().kind)
+        .filter(|kind| !matches!(kind, TokenKind::Atom(_)))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Xor,
+            TokenKind::Xor,
+            TokenKind::Nand,
+            TokenKind::Nand,
+            TokenKind::Nor,
+            TokenKind::Nor,
+        ]
+    );
+}
+
+#[test]
+fn test_token_text_and_positions() {
+    let tokens = Lexer::new("¬P → Q<->R")
+        .map(Result::unwrap)
+        .map(|token| (token.text, token.position, token.offset))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        tokens,
+        vec![
+            ("¬", 0, 0),
+            ("P", 1, 2),
+            ("→", 3, 4),
+            ("Q", 5, 8),
+            ("<->", 6, 9),
+            ("R", 9, 12),
+        ]
+    );
+}
+
+#[test]
+fn test_errors_do_not_stop_lexing() {
+    let tokens = Lexer::new("A $ <B").collect::<Vec<_>>();
+    assert_eq!(tokens.len(), 4);
+    assert!(matches!(
+        tokens[1],
+        Err(ParserError::InvalidExpression('$', 2))
+    ));
+    assert!(matches!(
+        tokens[2],
+        Err(ParserError::InvalidOperator('<', 4))
+    ));
+    assert_eq!(
+        tokens[3].as_ref().unwrap().kind,
+        TokenKind::Atom('B'.into())
+    );
+}
+
+#[test]
+fn test_names() {
+    let tokens = Lexer::new("rain_2 v P & T | PvQ")
+        .with_mode(AtomMode::Names)
+        .map(Result::unwrap)
+        .map(|token| (token.kind, token.text, token.position))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        tokens,
+        vec![
+            (TokenKind::Name, "rain_2", 0),
+            (TokenKind::Name, "v", 7),
+            (TokenKind::Atom('P'.into()), "P", 9),
+            (TokenKind::And, "&", 11),
+            (TokenKind::Top, "T", 13),
+            (TokenKind::Or, "|", 15),
+            (TokenKind::Name, "PvQ", 17),
+        ]
+    );
+}
+
+#[test]
+fn test_disjunction_in_name() {
+    let tokens = Lexer::new("PvQ v v1").collect::<Vec<_>>();
+    assert!(matches!(tokens[1], Ok(token) if token.kind == TokenKind::Or));
+    assert!(matches!(tokens[3], Ok(token) if token.kind == TokenKind::Or));
+    assert!(matches!(tokens[4], Err(ParserError::DisjunctionInName(6))));
+
+    // Between letters, lowercase or with subscripts, `v` is disjunction
+    let kinds = |input| {
+        Lexer::new(input)
+            .map(|token| token.unwrap().kind)
+            .collect::<Vec<_>>()
+    };
+    let atom = |letter, index| TokenKind::Atom(Symbol::indexed(letter, index));
+    assert_eq!(
+        kinds("pvqvr"),
+        vec![
+            TokenKind::Atom('p'.into()),
+            TokenKind::Or,
+            TokenKind::Atom('q'.into()),
+            TokenKind::Or,
+            TokenKind::Atom('r'.into()),
+        ]
+    );
+    assert_eq!(
+        kinds("p1vq1"),
+        vec![atom('p', 1), TokenKind::Or, atom('q', 1)]
+    );
+}
@@ -0,0 +1,82 @@
+use propositional_logic_calculator::{
+    expression::{Expression, Symbol},
+    minimize::MAX_MINIMIZED_ATOMS,
+    proof::parse_expression,
+    semantics::all_models,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::common::random_expression;
+
+/// How many conjunctions and literals a sum of products has.
+fn cost(expression: &Expression) -> (usize, usize) {
+    match expression {
+        Expression::Or(left, right) => {
+            let (left, right) = (cost(left), cost(right));
+            (left.0 + right.0, left.1 + right.1)
+        }
+        product => {
+            let literals = product
+                .list_expressions()
+                .iter()
+                .filter(|sub| matches!(sub, Expression::Var(_)))
+                .count();
+            (1, literals)
+        }
+    }
+}
+
+#[test]
+fn test_minimize_is_equivalent() {
+    let mut rng = StdRng::seed_from_u64(5);
+    let atoms = ['P', 'Q', 'R', 'S'].map(Symbol::new);
+    for _ in 0..200 {
+        let formula = random_expression(&mut rng, 4);
+        let minimal = formula.minimize();
+        for model in all_models(&atoms) {
+            assert_eq!(
+                model.satisfies(&minimal),
+                model.satisfies(&formula),
+                "{} minimized to {}",
+                formula,
+                minimal
+            );
+        }
+    }
+}
+
+#[test]
+fn test_minimize_finds_smallest_cover() {
+    // The textbook function with minterms 0, 1, 2, 5, 6, 7 of three letters has two covers of
+    // three products each, and no smaller one
+    let formula =
+        parse_expression("(-P&-Q&-R)v(-P&-Q&R)v(-P&Q&-R)v(P&-Q&R)v(P&Q&-R)v(P&Q&R)").unwrap();
+    assert_eq!(cost(&formula.minimize()), (3, 6));
+
+    for (formula, minimal) in [
+        ("(P>Q)&(Q>P)", "(P&Q)v(-P&-Q)"),
+        ("-(P&Q&R)", "-Pv-Qv-R"),
+        ("(P&Q)v(P&R)v(Q&R)v(P&Q&R)", "(P&Q)v(P&R)v(Q&R)"),
+        ("Pv(-P&Q)", "PvQ"),
+        ("P&-P", "F"),
+    ] {
+        let minimized = parse_expression(formula).unwrap().minimize();
+        assert_eq!(
+            cost(&minimized),
+            cost(&parse_expression(minimal).unwrap()),
+            "{} minimized to {}",
+            formula,
+            minimized
+        );
+    }
+}
+
+#[test]
+#[should_panic(expected = "too many sentence letters")]
+fn test_minimize_rejects_large_formulas() {
+    let formula = (1..=MAX_MINIMIZED_ATOMS + 1)
+        .map(|i| format!("P{}", i))
+        .collect::<Vec<_>>()
+        .join("v");
+    parse_expression(&formula).unwrap().minimize();
+}
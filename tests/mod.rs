@@ -1,2 +1,26 @@
+mod arena;
+mod block;
+mod cnf;
+mod common;
+mod enumerate;
+mod explain;
+mod expression;
+mod fold;
+mod forms;
+mod generate;
+mod incremental;
+mod lexer;
+mod minimize;
+mod normalize;
 mod parser;
+mod problems;
 mod proof;
+mod render;
+mod sat;
+mod semantics;
+mod skeleton;
+mod smtlib;
+mod snapshot;
+mod template;
+mod tptp;
+mod trace;
@@ -0,0 +1,115 @@
+use propositional_logic_calculator::{
+    expression::Expression, normalize::Normalization, parser::Parser, proof::parse_expression,
+};
+
+fn normalize(input: &str, normalization: Normalization) -> Expression {
+    Parser::new(input)
+        .normalizing(normalization)
+        .parse()
+        .unwrap()
+}
+
+#[test]
+fn test_no_normalization() {
+    for input in ["--P", "Q&(R&P)", "QvP"] {
+        assert_eq!(
+            normalize(input, Normalization::NONE),
+            parse_expression(input).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_double_negation() {
+    let normalization = Normalization {
+        double_negation: true,
+        ..Default::default()
+    };
+    assert_eq!(normalize("--P", normalization).to_string(), "P");
+    assert_eq!(normalize("---P", normalization).to_string(), "~P");
+    assert_eq!(
+        normalize("--(P>--Q)", normalization).to_string(),
+        "(P -> Q)"
+    );
+}
+
+#[test]
+fn test_flatten() {
+    let normalization = Normalization {
+        flatten: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        normalize("Q&(R&P)", normalization).to_string(),
+        "((Q & R) & P)"
+    );
+    assert_eq!(
+        normalize("(AvB)v(CvD)", normalization).to_string(),
+        "(((A v B) v C) v D)"
+    );
+    // Chains of different connectives stay apart
+    assert_eq!(
+        normalize("A&(BvC)", normalization).to_string(),
+        "(A & (B v C))"
+    );
+}
+
+#[test]
+fn test_sort_operands() {
+    let normalization = Normalization {
+        sort_operands: true,
+        ..Default::default()
+    };
+    assert_eq!(normalize("QvP", normalization).to_string(), "(P v Q)");
+    assert_eq!(normalize("Q<->P", normalization).to_string(), "(P <-> Q)");
+    // The sides of a conditional are not interchangeable
+    assert_eq!(normalize("Q>P", normalization).to_string(), "(Q -> P)");
+}
+
+#[test]
+fn test_full_normalization() {
+    let a = normalize("--(C&(B&--A))v-D", Normalization::FULL);
+    let b = normalize("-Dv(A&B)&C", Normalization::FULL);
+    assert_eq!(a, b);
+    assert_eq!(a.to_string(), "(((A & B) & C) v ~D)");
+}
+
+#[test]
+fn test_source_is_kept() {
+    let parser = Parser::new("--P").normalizing(Normalization::FULL);
+    assert_eq!(parser.source(), "--P");
+}
+
+#[test]
+fn test_simplify() {
+    for (input, simplified) in [
+        ("---P", "-P"),
+        ("P&P", "P"),
+        ("(PvQ)v(PvQ)", "PvQ"),
+        ("P&(PvQ)", "P"),
+        ("(Q&P)vP", "P"),
+        ("P&T", "P"),
+        ("FvP", "P"),
+        ("P&F", "F"),
+        ("Tv-P", "T"),
+        ("-T", "F"),
+        ("F>P", "T"),
+        ("P>T", "T"),
+        ("T>P", "P"),
+        ("P>F", "-P"),
+        ("T<->P", "P"),
+        ("-P<->F", "P"),
+        // Each rewrite exposes the next
+        ("--((P&T)v(P&Q))", "P"),
+        ("((PvF)&(P&T))>(Q&-F)", "P>Q"),
+        // Nothing to do
+        ("(P&Q)vR", "(P&Q)vR"),
+    ] {
+        assert_eq!(
+            parse_expression(input).unwrap().simplify(),
+            parse_expression(simplified).unwrap(),
+            "{}",
+            input
+        );
+    }
+}
@@ -1,9 +1,19 @@
-use propositional_logic_calculator::{expression::Expression, parser::Parser};
+use propositional_logic_calculator::{
+    error::{ArgumentError, ParserError},
+    expression::{Expression, Symbol},
+    lexer::AtomMode,
+    parser::{parse_latex, parse_polish, Parser, ParserOptions, PolishStyle},
+    proof::{parse_argument, parse_expression},
+    semantics::Model,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::common::random_expression;
 
 #[test]
 fn test_parse_simple_expression() {
     let mut parser = Parser::new("A");
-    assert_eq!(parser.parse().unwrap(), Expression::Var('A'));
+    assert_eq!(parser.parse().unwrap(), Expression::Var('A'.into()));
 }
 
 #[test]
@@ -11,7 +21,10 @@ fn test_parse_and_expression() {
     let mut parser = Parser::new("A&B");
     assert_eq!(
         parser.parse().unwrap(),
-        Expression::And(Expression::Var('A').wrap(), Expression::Var('B').wrap(),)
+        Expression::And(
+            Expression::Var('A'.into()).wrap(),
+            Expression::Var('B'.into()).wrap(),
+        )
     );
 }
 
@@ -20,12 +33,18 @@ fn test_parse_or_expression() {
     let mut parser = Parser::new("A|B");
     assert_eq!(
         parser.parse().unwrap(),
-        Expression::Or(Expression::Var('A').wrap(), Expression::Var('B').wrap(),)
+        Expression::Or(
+            Expression::Var('A'.into()).wrap(),
+            Expression::Var('B'.into()).wrap(),
+        )
     );
     let mut parser = Parser::new("AvB");
     assert_eq!(
         parser.parse().unwrap(),
-        Expression::Or(Expression::Var('A').wrap(), Expression::Var('B').wrap(),)
+        Expression::Or(
+            Expression::Var('A'.into()).wrap(),
+            Expression::Var('B'.into()).wrap(),
+        )
     );
 }
 
@@ -34,7 +53,7 @@ fn test_parse_not_expression() {
     let mut parser = Parser::new("-A");
     assert_eq!(
         parser.parse().unwrap(),
-        Expression::Not(Expression::Var('A').wrap())
+        Expression::Not(Expression::Var('A'.into()).wrap())
     );
 }
 
@@ -44,7 +63,11 @@ fn test_parse_nested_expression() {
     assert_eq!(
         parser.parse().unwrap(),
         Expression::Not(
-            Expression::And(Expression::Var('A').wrap(), Expression::Var('B').wrap()).wrap()
+            Expression::And(
+                Expression::Var('A'.into()).wrap(),
+                Expression::Var('B'.into()).wrap()
+            )
+            .wrap()
         )
     );
 }
@@ -66,7 +89,10 @@ fn test_ingore_invalid_characters() {
     let mut parser = Parser::new("A & B");
     assert_eq!(
         parser.parse().unwrap(),
-        Expression::And(Expression::Var('A').wrap(), Expression::Var('B').wrap(),)
+        Expression::And(
+            Expression::Var('A'.into()).wrap(),
+            Expression::Var('B'.into()).wrap(),
+        )
     );
 }
 
@@ -75,7 +101,10 @@ fn test_ignore_invalid_character_in_brackets() {
     let mut parser = Parser::new("(A & B)");
     assert_eq!(
         parser.parse().unwrap(),
-        Expression::And(Expression::Var('A').wrap(), Expression::Var('B').wrap(),)
+        Expression::And(
+            Expression::Var('A'.into()).wrap(),
+            Expression::Var('B'.into()).wrap(),
+        )
     );
 }
 
@@ -84,7 +113,10 @@ fn test_deeply_nested_expression() {
     let mut parser = Parser::new("(((((A))))&B)");
     assert_eq!(
         parser.parse().unwrap(),
-        Expression::And(Expression::Var('A').wrap(), Expression::Var('B').wrap(),)
+        Expression::And(
+            Expression::Var('A'.into()).wrap(),
+            Expression::Var('B'.into()).wrap(),
+        )
     );
 }
 
@@ -117,3 +149,714 @@ fn test_invalid_characters() {
     let mut parser = Parser::new("A&B#C");
     assert!(parser.parse().is_err());
 }
+
+#[test]
+fn test_biconditional() {
+    let expected = Expression::Iff(
+        Expression::Var('A'.into()).wrap(),
+        Expression::Var('B'.into()).wrap(),
+    );
+    assert_eq!(parse_expression("A<->B").unwrap(), expected);
+    assert_eq!(parse_expression("A = B").unwrap(), expected);
+    assert_eq!(expected.to_string(), "(A <-> B)");
+    assert!(parse_expression("A<>B").is_err());
+}
+
+#[test]
+fn test_constants() {
+    assert_eq!(parse_expression("T").unwrap(), Expression::Top);
+    assert_eq!(parse_expression("⊥").unwrap(), Expression::Bottom);
+    let expected = Expression::Implies(
+        Expression::Not(Expression::Top.wrap()).wrap(),
+        Expression::Bottom.wrap(),
+    );
+    assert_eq!(parse_expression("-T>F").unwrap(), expected);
+    assert_eq!(parse_expression("-⊤>⊥").unwrap(), expected);
+    assert_eq!(expected.to_string(), "(~⊤ -> ⊥)");
+}
+
+#[test]
+fn test_unicode_connectives() {
+    assert_eq!(
+        parse_expression("¬(P∧Q)→(R∨S)").unwrap(),
+        parse_expression("-(P&Q)>(RvS)").unwrap()
+    );
+    assert_eq!(
+        parse_expression("P ↔ ¬Q").unwrap(),
+        parse_expression("P <-> -Q").unwrap()
+    );
+}
+
+#[test]
+fn test_negation_aliases() {
+    assert_eq!(
+        parse_expression("~P&!Q").unwrap(),
+        parse_expression("-P&-Q").unwrap()
+    );
+    assert_eq!(
+        parse_expression("~(P>!(Qv~R))").unwrap(),
+        parse_expression("-(P>-(Qv-R))").unwrap()
+    );
+    assert_eq!(
+        parse_expression("~~P").unwrap(),
+        parse_expression("--P").unwrap()
+    );
+    assert!(matches!(
+        parse_expression("P&~"),
+        Err(ParserError::ExpectedExpressionAfterNegation(2))
+    ));
+}
+
+#[test]
+fn test_display_round_trips() {
+    let expression = parse_expression("-(P&-Q)>R").unwrap();
+    assert_eq!(
+        parse_expression(&expression.to_string()).unwrap(),
+        expression
+    );
+}
+
+#[test]
+fn test_canonical_round_trips() {
+    let mut rng = StdRng::seed_from_u64(2017);
+    for _ in 0..2_000 {
+        let expression = random_expression(&mut rng, 5);
+        let canonical = expression.canonical();
+        assert_eq!(
+            parse_expression(&canonical).unwrap(),
+            expression,
+            "{canonical} should read back as {expression}"
+        );
+        assert_eq!(
+            parse_expression(&expression.to_string())
+                .unwrap()
+                .canonical(),
+            canonical
+        );
+    }
+}
+
+#[test]
+fn test_canonical_parentheses() {
+    for (input, canonical) in [
+        ("(A&B)&C", "A & B & C"),
+        ("A&(B&C)", "A & (B & C)"),
+        ("A>(B>C)", "A -> B -> C"),
+        ("(A>B)>C", "(A -> B) -> C"),
+        ("(AvB)&C", "(A v B) & C"),
+        ("Av(B&C)", "A v B & C"),
+        ("(A<->B)<->C", "(A <-> B) <-> C"),
+        ("--(A&B)", "--(A & B)"),
+        ("⊤>⊥", "T -> F"),
+    ] {
+        assert_eq!(parse_expression(input).unwrap().canonical(), canonical);
+    }
+}
+
+#[test]
+fn test_canonical_letters_named_like_constants() {
+    // Only a sentence letter built directly can be named `T` or `F`, and it does not read back
+    let expression = Expression::And(
+        Expression::Var('T'.into()).wrap(),
+        Expression::Var('F'.into()).wrap(),
+    );
+    assert_eq!(expression.canonical(), "T & F");
+    assert_eq!(
+        parse_expression(&expression.canonical()).unwrap(),
+        Expression::And(Expression::Top.wrap(), Expression::Bottom.wrap())
+    );
+    let subscripted = Expression::Var(Symbol::indexed('T', 1));
+    assert_eq!(subscripted.canonical(), "T1");
+    assert!(parse_expression(&subscripted.canonical()).is_err());
+}
+
+fn assert_same(input: &str, bracketed: &str) {
+    assert_eq!(
+        parse_expression(input).unwrap(),
+        parse_expression(bracketed).unwrap(),
+        "{input} should parse as {bracketed}"
+    );
+}
+
+#[test]
+fn test_precedence() {
+    assert_same("A&BvC", "(A&B)vC");
+    assert_same("AvB&C", "Av(B&C)");
+    assert_same("AvB>C", "(AvB)>C");
+    assert_same("A>B<->C", "(A>B)<->C");
+    assert_same("A<->B>C", "A<->(B>C)");
+    assert_same("-A&B", "(-A)&B");
+    assert_same("--A>B", "(--A)>B");
+    assert_same("-(A&B)vC", "(-(A&B))vC");
+}
+
+#[test]
+fn test_associativity() {
+    assert_same("A&B&C", "(A&B)&C");
+    assert_same("AvBvC", "(AvB)vC");
+    assert_same("A>B>C", "A>(B>C)");
+    assert_same("A=B=C", "A=(B=C)");
+}
+
+#[test]
+fn test_nested_parentheses_keep_grouping() {
+    let expected = Expression::And(
+        Expression::Var('A'.into()).wrap(),
+        Expression::Implies(
+            Expression::Var('B'.into()).wrap(),
+            Expression::Var('C'.into()).wrap(),
+        )
+        .wrap(),
+    );
+    assert_eq!(parse_expression("(A&(B>C))").unwrap(), expected);
+    assert_same("((A>B)&(C>D))vE", "((A>B)&(C>D))vE");
+    assert_ne!(
+        parse_expression("(A>B)>C").unwrap(),
+        parse_expression("A>(B>C)").unwrap()
+    );
+}
+
+#[test]
+fn test_parse_errors() {
+    assert!(matches!(
+        parse_expression("A&"),
+        Err(ParserError::EmptyExpression(2))
+    ));
+    assert!(matches!(
+        parse_expression("&A"),
+        Err(ParserError::ExpectedLeftOperand(0))
+    ));
+    assert!(matches!(
+        parse_expression("A>-"),
+        Err(ParserError::ExpectedExpressionAfterNegation(2))
+    ));
+    assert!(matches!(
+        parse_expression("A&(B>C"),
+        Err(ParserError::UnmatchedParentheses(rest, 1, 2)) if rest == "B>C"
+    ));
+    assert!(matches!(
+        parse_expression("A&B)"),
+        Err(ParserError::InvalidExpression(')', 3))
+    ));
+    assert!(matches!(
+        parse_expression("AB"),
+        Err(ParserError::InvalidExpression('B', 1))
+    ));
+}
+
+#[test]
+fn test_error_positions() {
+    for (input, position) in [
+        ("P & $", 4),
+        ("(P & Q) > ", 10),
+        ("P <> Q", 2),
+        ("¬P ∧ (Q → R", 5),
+        ("P & -", 4),
+    ] {
+        let error = parse_expression(input).unwrap_err();
+        assert_eq!(error.position(), position, "{input}: {error}");
+    }
+    let error = parse_expression("(P&Q) # R").unwrap_err();
+    assert_eq!(error.caret("(P&Q) # R"), "(P&Q) # R\n      ^");
+}
+
+#[test]
+fn test_arrow_tokens() {
+    assert_same("A->B", "A>B");
+    assert_same("A -> -B", "A>-B");
+    assert_same("-A->B", "(-A)>B");
+    assert_same("A->B<->-C", "(A>B)=(-C)");
+    assert!(matches!(
+        parse_expression("A-B"),
+        Err(ParserError::InvalidOperator('-', 1))
+    ));
+    // Conditionals and biconditionals are displayed with the arrows the parser reads back
+    let expression = parse_expression("(A->B)<->C").unwrap();
+    assert_eq!(
+        parse_expression(&expression.to_string()).unwrap(),
+        expression
+    );
+}
+
+fn parse_strict(input: &str) -> Result<Expression, ParserError> {
+    Parser::new(input)
+        .with_options(ParserOptions::STRICT)
+        .parse()
+}
+
+#[test]
+fn test_strict_mode() {
+    for input in [
+        "A&B&C",
+        "AvBvC",
+        "A<->B<->C",
+        "(A&B)vC",
+        "-(A>B)>(C>D)",
+        "A>-B",
+    ] {
+        assert_eq!(
+            parse_strict(input).unwrap(),
+            parse_expression(input).unwrap(),
+            "{input} should parse strictly"
+        );
+    }
+    assert!(matches!(
+        parse_strict("A&BvC"),
+        Err(ParserError::AmbiguousGrouping('v', 3))
+    ));
+    assert!(matches!(
+        parse_strict("AvB&C"),
+        Err(ParserError::AmbiguousGrouping('v', 1))
+    ));
+    assert!(matches!(
+        parse_strict("A -> B -> C"),
+        Err(ParserError::AmbiguousGrouping('-', 2))
+    ));
+    assert!(matches!(
+        parse_strict("(A>B&C)"),
+        Err(ParserError::AmbiguousGrouping('>', 2))
+    ));
+}
+
+#[test]
+fn test_circuit_connectives() {
+    assert_same("A^B", "-(A<->B)");
+    assert_same("A⊕B", "-(A<->B)");
+    assert_same("A↑B", "-(A&B)");
+    assert_same("A↓B", "-(AvB)");
+    assert_same("A↑B^C", "-(-(A&B)<->C)");
+    assert_same("A↓B↓C", "-(-(AvB)vC)");
+    assert!(parse_strict("A^B^C").is_ok());
+    assert!(matches!(
+        parse_strict("A↑B↑C"),
+        Err(ParserError::AmbiguousGrouping('↑', 3))
+    ));
+
+    let expression = parse_expression("(P↑Q)^R").unwrap();
+    assert_eq!(
+        parse_expression(&expression.to_string()).unwrap(),
+        expression
+    );
+    for (p, q) in [(false, false), (false, true), (true, false), (true, true)] {
+        let mut model = Model::new();
+        model.set('P', p);
+        model.set('Q', q);
+        for (input, value) in [("P^Q", p != q), ("P↑Q", !(p && q)), ("P↓Q", !(p || q))] {
+            let expression = parse_expression(input).unwrap();
+            assert_eq!(model.evaluate(&expression), Some(value), "{input}");
+        }
+    }
+    assert_eq!(
+        parse_latex("P \\oplus Q \\uparrow R").unwrap(),
+        parse_expression("P^Q↑R").unwrap()
+    );
+}
+
+fn parse_names(input: &str) -> Result<(Expression, Vec<(String, Symbol)>), ParserError> {
+    let options = ParserOptions {
+        atoms: AtomMode::Names,
+        ..ParserOptions::LENIENT
+    };
+    let mut parser = Parser::new(input).with_options(options);
+    let expression = parser.parse()?;
+    Ok((expression, parser.names().to_vec()))
+}
+
+#[test]
+fn test_atom_names() {
+    let (expression, names) = parse_names("(rain -> wet) & rain & P & -Q1 | T").unwrap();
+    assert_eq!(expression, parse_expression("(Q>R)&Q&P&-SvT").unwrap());
+    assert_eq!(
+        names,
+        vec![
+            ("rain".to_string(), 'Q'.into()),
+            ("wet".to_string(), 'R'.into()),
+            ("Q1".to_string(), 'S'.into()),
+        ]
+    );
+    assert_eq!(
+        parse_names("v & -v").unwrap().0,
+        parse_expression("P&-P").unwrap()
+    );
+}
+
+#[test]
+fn test_v_diagnostics() {
+    assert!(matches!(
+        parse_names("rain v wet"),
+        Err(ParserError::AtomNamedV(5))
+    ));
+    assert!(matches!(
+        parse_expression("P & v_1"),
+        Err(ParserError::DisjunctionInName(4))
+    ));
+    assert!(matches!(
+        parse_expression("Pv1"),
+        Err(ParserError::DisjunctionInName(1))
+    ));
+    assert_eq!(
+        parse_expression("pvqvr").unwrap(),
+        parse_expression("p v q v r").unwrap()
+    );
+    assert_eq!(
+        parse_expression("p1vq1").unwrap(),
+        parse_expression("p1 v q1").unwrap()
+    );
+    assert_eq!(
+        parse_expression("PvQ").unwrap(),
+        parse_names("P|Q").unwrap().0
+    );
+}
+
+#[test]
+fn test_lowercase_and_indexed_variables() {
+    let p1 = Symbol::indexed('P', 1);
+    let p12 = Symbol::indexed('P', 12);
+    let expression = parse_expression("p & P1 > P12 v P").unwrap();
+    assert_eq!(
+        expression,
+        Expression::Implies(
+            Expression::And(
+                Expression::Var('p'.into()).wrap(),
+                Expression::Var(p1).wrap()
+            )
+            .wrap(),
+            Expression::Or(
+                Expression::Var(p12).wrap(),
+                Expression::Var('P'.into()).wrap()
+            )
+            .wrap(),
+        )
+    );
+    assert_eq!(expression.to_string(), "((p & P1) -> (P12 v P))");
+    assert_eq!(expression.canonical(), "p & P1 -> P12 v P");
+    assert_eq!(
+        parse_expression(&expression.to_string()).unwrap(),
+        expression
+    );
+    assert_eq!(
+        parse_latex("p \\land P_1 \\to P_{12} \\lor P").unwrap(),
+        expression
+    );
+    assert_eq!(
+        parse_expression("pvq").unwrap(),
+        parse_expression("p v q").unwrap()
+    );
+    assert_eq!(
+        parse_expression("P99999").unwrap(),
+        Expression::Var(Symbol::indexed('P', 99999))
+    );
+    assert!(matches!(
+        parse_expression("P99999999999"),
+        Err(ParserError::InvalidExpression('P', 0))
+    ));
+    assert!(matches!(
+        parse_latex("P_ & Q"),
+        Err(ParserError::InvalidExpression('_', 1))
+    ));
+}
+
+fn recover(input: &str) -> Vec<ParserError> {
+    Parser::new(input).parse_recovering().unwrap_err()
+}
+
+#[test]
+fn test_parse_recovering() {
+    assert_eq!(
+        Parser::new("P & (Q v R)").parse_recovering().unwrap(),
+        parse_expression("P&(QvR)").unwrap()
+    );
+    assert!(matches!(
+        recover("& P Q # (R >").as_slice(),
+        [
+            ParserError::ExpectedLeftOperand(0),
+            ParserError::InvalidExpression('Q', 4),
+            ParserError::InvalidExpression('#', 6),
+            ParserError::InvalidExpression('(', 8),
+            ParserError::MissingRightOperand('>', 11),
+            ParserError::UnmatchedParentheses(_, 1, 8),
+        ]
+    ));
+    assert!(matches!(
+        recover("()) & -").as_slice(),
+        [
+            ParserError::EmptyExpression(1),
+            ParserError::InvalidExpression(')', 2),
+            ParserError::ExpectedExpressionAfterNegation(6),
+        ]
+    ));
+    assert!(matches!(
+        recover("").as_slice(),
+        [ParserError::EmptyExpression(0)]
+    ));
+    // Problems the scan does not look for are still reported
+    assert!(matches!(
+        Parser::new("A&BvC")
+            .with_options(ParserOptions::STRICT)
+            .parse_recovering()
+            .unwrap_err()
+            .as_slice(),
+        [ParserError::AmbiguousGrouping('v', 3)]
+    ));
+}
+
+#[test]
+fn test_parse_argument() {
+    let expected = (
+        vec![
+            parse_expression("A").unwrap(),
+            parse_expression("B>C").unwrap(),
+        ],
+        parse_expression("C").unwrap(),
+    );
+    for input in [
+        "A,B>C/C",
+        "A, B>C / C",
+        "A,B>C ⊢ C",
+        "A,B>C |- C",
+        "A,B>C ∴ C",
+        "A, B>C therefore C",
+        "A, B>C Therefore C",
+    ] {
+        assert_eq!(parse_argument(input).unwrap(), expected, "{input}");
+    }
+}
+
+#[test]
+fn test_parse_argument_errors() {
+    assert!(matches!(
+        parse_argument("A,B>C"),
+        Err(ArgumentError::MissingDelimiter)
+    ));
+    for input in ["|- Pv-P", " / P", "therefore P"] {
+        assert!(
+            matches!(parse_argument(input), Err(ArgumentError::NoPremises)),
+            "{input}"
+        );
+    }
+    assert!(matches!(
+        parse_argument("A,,B/C"),
+        Err(ArgumentError::EmptyPremise(2))
+    ));
+    assert!(matches!(
+        parse_argument("A, /C"),
+        Err(ArgumentError::EmptyPremise(3))
+    ));
+    assert!(matches!(
+        parse_argument("A/B/C"),
+        Err(ArgumentError::TrailingInput(d, 3)) if d == "/"
+    ));
+    assert!(matches!(
+        parse_argument("A ∴ B therefore C"),
+        Err(ArgumentError::TrailingInput(d, 6)) if d == "therefore"
+    ));
+    let error = parse_argument("A,B&$/C").unwrap_err();
+    assert!(matches!(
+        error,
+        ArgumentError::ParserError(ParserError::InvalidExpression('$', 4))
+    ));
+    assert_eq!(error.caret("A,B&$/C"), "A,B&$/C\n    ^");
+    assert!(matches!(
+        parse_argument("A ⊢ B&"),
+        Err(ArgumentError::ParserError(ParserError::EmptyExpression(6)))
+    ));
+}
+
+#[test]
+fn test_parse_latex() {
+    for (latex, ascii) in [
+        ("P \\land Q", "P&Q"),
+        ("P \\wedge Q \\vee R", "(P&Q)vR"),
+        ("\\lnot (P \\lor Q)", "-(PvQ)"),
+        ("\\neg\\neg P", "--P"),
+        ("P \\rightarrow Q \\to R", "P>(Q>R)"),
+        (
+            "P \\implies Q \\iff \\neg Q \\implies \\neg P",
+            "(P>Q)<->(-Q>-P)",
+        ),
+        (
+            "$\\left(P \\leftrightarrow \\top\\right) \\land \\bot$",
+            "(P<->T)&F",
+        ),
+        ("\\neg{P \\land Q}", "-(P&Q)"),
+        ("P & -Q", "P&-Q"),
+    ] {
+        assert_eq!(
+            parse_latex(latex).unwrap(),
+            parse_expression(ascii).unwrap(),
+            "{latex}"
+        );
+    }
+}
+
+#[test]
+fn test_parse_latex_errors() {
+    assert!(matches!(
+        parse_latex("P \\otimes Q"),
+        Err(ParserError::InvalidExpression('\\', 2))
+    ));
+    assert!(matches!(
+        parse_latex("$P \\land$"),
+        Err(ParserError::EmptyExpression(9))
+    ));
+    assert!(matches!(
+        parse_latex("\\lnot \\lnot P \\land %"),
+        Err(ParserError::InvalidExpression('%', 20))
+    ));
+}
+
+#[test]
+fn test_unsupported_operators() {
+    for (input, found, suggestion, position) in [
+        ("P + Q", "+", "v", 2),
+        ("P*Q", "*", "&", 1),
+        ("P => Q", "=>", ">", 2),
+        ("P <=> Q", "<=>", "<->", 2),
+    ] {
+        let err = parse_expression(input).unwrap_err();
+        assert!(
+            matches!(&err, ParserError::UnsupportedOperator(f, s, p) if f == found && *s == suggestion && *p == position),
+            "{input}: {err:?}"
+        );
+        assert_eq!(err.suggestion(), Some(suggestion));
+    }
+    assert_eq!(
+        parse_expression("P + Q").unwrap_err().to_string(),
+        "'+' at position 2 is not an operator; did you mean 'v'?"
+    );
+    // `^` is exclusive disjunction rather than a misspelt conjunction
+    assert!(parse_expression("P ^ Q").is_ok());
+    // The rest of the input is still checked as if the suggestion had been written
+    assert!(matches!(
+        recover("P => Q + ").as_slice(),
+        [
+            ParserError::UnsupportedOperator(_, ">", 2),
+            ParserError::UnsupportedOperator(_, "v", 7),
+            ParserError::EmptyExpression(9),
+        ]
+    ));
+}
+
+#[test]
+fn test_polish_round_trips() {
+    let mut rng = StdRng::seed_from_u64(2023);
+    for _ in 0..1_000 {
+        let expression = random_expression(&mut rng, 5);
+        for style in [PolishStyle::Symbols, PolishStyle::Letters] {
+            let polish = expression.to_polish(style).unwrap();
+            assert_eq!(
+                parse_polish(&polish, style).unwrap(),
+                expression,
+                "{polish} should read back as {expression}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_polish_notation() {
+    let expected = parse_expression("(p&q)>r").unwrap();
+    assert_eq!(
+        parse_polish("CKpqr", PolishStyle::Letters).unwrap(),
+        expected
+    );
+    assert_eq!(
+        parse_polish(">&pq r", PolishStyle::Symbols).unwrap(),
+        expected
+    );
+    assert_eq!(
+        parse_polish("EP1 NP2", PolishStyle::Letters).unwrap(),
+        parse_expression("P1<->-P2").unwrap()
+    );
+    // Only the letters style reads capitals as connectives
+    assert_eq!(
+        parse_polish("& A K", PolishStyle::Symbols).unwrap(),
+        parse_expression("A&K").unwrap()
+    );
+    assert_eq!(
+        parse_expression("P1 v -q")
+            .unwrap()
+            .to_polish(PolishStyle::Letters)
+            .unwrap(),
+        "AP1Nq"
+    );
+    assert_eq!(
+        parse_expression("K")
+            .unwrap()
+            .to_polish(PolishStyle::Letters),
+        None
+    );
+}
+
+#[test]
+fn test_polish_errors() {
+    assert!(matches!(
+        parse_polish("", PolishStyle::Symbols),
+        Err(ParserError::EmptyExpression(0))
+    ));
+    assert!(matches!(
+        parse_polish("& P", PolishStyle::Symbols),
+        Err(ParserError::MissingRightOperand('&', 0))
+    ));
+    assert!(matches!(
+        parse_polish("CN", PolishStyle::Letters),
+        Err(ParserError::ExpectedExpressionAfterNegation(1))
+    ));
+    assert!(matches!(
+        parse_polish("K", PolishStyle::Letters),
+        Err(ParserError::ExpectedLeftOperand(0))
+    ));
+    assert!(matches!(
+        parse_polish("P Q", PolishStyle::Symbols),
+        Err(ParserError::InvalidExpression('Q', 2))
+    ));
+    assert!(matches!(
+        parse_polish("& (P) Q", PolishStyle::Symbols),
+        Err(ParserError::InvalidExpression('(', 2))
+    ));
+    // Deep nesting is an error rather than a stack overflow
+    assert!(parse_polish(&format!("{}p", "N".repeat(200)), PolishStyle::Letters).is_ok());
+    assert!(matches!(
+        parse_polish(&format!("{}p", "N".repeat(100_000)), PolishStyle::Letters),
+        Err(ParserError::TooDeeplyNested(256, 257))
+    ));
+    assert!(matches!(
+        parse_polish(
+            &format!("{}P Q", "& ".repeat(100_000)),
+            PolishStyle::Symbols
+        ),
+        Err(ParserError::TooDeeplyNested(256, 514))
+    ));
+}
+
+#[test]
+fn test_max_depth() {
+    let deep = 100_000;
+    for input in [
+        format!("{}P{}", "(".repeat(deep), ")".repeat(deep)),
+        format!("{}P", "-".repeat(deep)),
+        format!("{}P", "P>".repeat(deep)),
+    ] {
+        assert!(matches!(
+            parse_expression(&input),
+            Err(ParserError::TooDeeplyNested(
+                ParserOptions::DEFAULT_MAX_DEPTH,
+                _
+            ))
+        ));
+    }
+
+    let options = ParserOptions {
+        max_depth: 3,
+        ..ParserOptions::LENIENT
+    };
+    assert!(Parser::new("((P))&-Q")
+        .with_options(options)
+        .parse()
+        .is_ok());
+    let err = Parser::new("P&((-Q))")
+        .with_options(options)
+        .parse()
+        .unwrap_err();
+    assert!(matches!(err, ParserError::TooDeeplyNested(3, 4)));
+}
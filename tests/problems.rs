@@ -0,0 +1,72 @@
+use propositional_logic_calculator::{
+    error::{ArgumentError, ProblemError},
+    expression::Expression,
+    problems::parse_problems,
+    proof::{parse_expression, IterationUnit, OrIntroduction, Proof},
+};
+use std::{env, fs, process};
+
+#[test]
+fn test_parse_problems() {
+    let problems = parse_problems(
+        "# Week 3\n\
+         \n\
+         P>Q, Q>R / P>R ; max_line_length=10 iterations=3000 or_introduction=off\n\
+         A, -A / #\n\
+         Q |- Pv-P ; max_formula_size=5 iteration_unit=rules\n",
+    )
+    .collect::<Result<Vec<_>, _>>()
+    .unwrap();
+    assert_eq!(
+        problems
+            .iter()
+            .map(|problem| problem.line)
+            .collect::<Vec<_>>(),
+        vec![3, 4, 5]
+    );
+    assert_eq!(problems[0].premises.len(), 2);
+    assert_eq!(problems[0].settings.max_line_length, 10);
+    assert_eq!(problems[0].settings.iterations, 3000);
+    assert_eq!(problems[0].settings.or_introduction, OrIntroduction::Off);
+    assert_eq!(problems[1].conclusion, Expression::Bottom);
+    assert!(problems[1].proof().is_refutation());
+    assert_eq!(problems[2].premises.len(), 1);
+    assert_eq!(problems[2].settings.max_formula_size, Some(5));
+    assert_eq!(
+        problems[2].settings.iteration_unit,
+        IterationUnit::RuleApplications
+    );
+    assert_eq!(problems[2].conclusion, parse_expression("Pv-P").unwrap());
+    assert!(problems[0].proof().search().is_ok());
+}
+
+#[test]
+fn test_problem_errors() {
+    let results = parse_problems("P / Q ; depth=3\nP, / Q\nP / Q ; iterations=many\nP / P")
+        .collect::<Vec<_>>();
+    assert_eq!(results.len(), 4);
+    assert!(matches!(
+        &results[0],
+        Err(ProblemError::UnknownSetting(name, 1)) if name == "depth"
+    ));
+    assert!(matches!(
+        results[1],
+        Err(ProblemError::Argument(ArgumentError::EmptyPremise(_), 2))
+    ));
+    assert!(matches!(
+        &results[2],
+        Err(ProblemError::InvalidSetting(setting, 3)) if setting == "iterations=many"
+    ));
+    assert!(results[3].is_ok());
+}
+
+#[test]
+fn test_load_problems() {
+    let path = env::temp_dir().join(format!("problems-{}.txt", process::id()));
+    fs::write(&path, "# Modus ponens\nP>Q, P / Q\n").unwrap();
+    let problems = Proof::load_problems(&path).unwrap().collect::<Vec<_>>();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].as_ref().unwrap().line, 2);
+    assert!(Proof::load_problems(&path).is_err());
+}
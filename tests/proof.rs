@@ -1,21 +1,44 @@
-use propositional_logic_calculator::proof::{parse_expression, Proof, SearchSettings};
+use propositional_logic_calculator::{
+    expression::{Expression, Symbol},
+    lines::{Line, Rule},
+    possible::PossibleFinder,
+    proof::{
+        create_assumption_lines, parse_argument, parse_expression, AtomUniverse, IterationUnit,
+        OrIntroduction, Proof, ProofShape, SearchNode, SearchSettings, SearchState,
+    },
+    render::Citations,
+};
+use std::rc::Rc;
 
-fn create_and_test_proof(assumptions: Vec<&str>, conclusion: &str) {
-    let assumptions = assumptions
-        .into_iter()
-        .map(parse_expression)
-        .collect::<Result<_, _>>()
-        .unwrap();
-    let conclusion = parse_expression(conclusion).unwrap();
+use crate::common::proof_with_settings;
 
-    let mut proof = Proof::with_settings(
-        assumptions,
-        conclusion,
-        SearchSettings {
-            max_line_length: 12,
-            iterations: 25_000,
-        },
+/// The first line of each candidate found from the premise `P` towards `PvQ` under `settings`.
+fn candidates(settings: SearchSettings) -> Vec<String> {
+    let node = SearchNode::new(
+        create_assumption_lines(vec![parse_expression("P").unwrap()]),
+        parse_expression("PvQ").unwrap(),
+        Rc::new(settings),
     );
+    let mut finder = PossibleFinder::new(node.borrow().clone());
+    finder.find();
+    finder
+        .possibles()
+        .iter()
+        .map(|possible| possible.lines[0].expression.to_string())
+        .collect()
+}
+
+/// The settings for the proofs here, which keep the searches short.
+fn settings() -> SearchSettings {
+    SearchSettings {
+        max_line_length: 12,
+        iterations: 25_000,
+        ..Default::default()
+    }
+}
+
+fn create_and_test_proof(assumptions: Vec<&str>, conclusion: &str) {
+    let mut proof = proof_with_settings(&assumptions, conclusion, settings());
     let result = proof.search();
     match result {
         Ok(_) => println!("Found proof: \n{}", proof),
@@ -33,6 +56,11 @@ fn test_double_mpp() {
     create_and_test_proof(vec!["P", "P>Q", "Q>R"], "R");
 }
 
+#[test]
+fn test_lowercase_and_indexed_variables() {
+    create_and_test_proof(vec!["p1>q", "-q"], "-p1");
+}
+
 #[test]
 fn test_mtt() {
     create_and_test_proof(vec!["P>Q", "-Q"], "-P");
@@ -94,3 +122,659 @@ fn test_dn_and_mtt() {
 fn test_conditional_proof() {
     create_and_test_proof(vec!["P>R", "R>Q"], "P>Q");
 }
+
+#[test]
+fn test_n_ary_disjunction_elimination() {
+    create_and_test_proof(vec!["PvQvR", "P>W", "Q>W", "R>W"], "W");
+    create_and_test_proof(vec!["(PvQ)vR", "P>W", "Q>W", "R>W"], "W");
+}
+
+#[test]
+fn test_nested_conditional_proof() {
+    create_and_test_proof(vec!["P>(Q>R)"], "Q>(P>R)");
+}
+
+#[test]
+fn test_disjunction_elimination_inside_case() {
+    create_and_test_proof(vec!["PvQ", "P>(RvS)", "R>W", "S>W", "Q>W"], "W");
+}
+
+#[test]
+fn test_conditional_proof_with_disjunction_elimination() {
+    create_and_test_proof(vec!["PvQ", "P>S", "Q>S"], "R>S");
+}
+
+#[test]
+fn test_reductio_ad_absurdum() {
+    create_and_test_proof(vec!["P>Q", "P>-Q"], "-P");
+}
+
+#[test]
+fn test_reductio_ad_absurdum_inside_conditional_proof() {
+    create_and_test_proof(vec!["-Q>-P"], "P>Q");
+}
+
+#[test]
+fn test_sub_proofs_count_against_iteration_budget() {
+    let settings = SearchSettings {
+        max_line_length: 12,
+        iterations: 2_000,
+        ..Default::default()
+    };
+    let mut proof = Proof::with_settings(
+        vec![parse_expression("PvQ").unwrap()],
+        parse_expression("P&Q").unwrap(),
+        settings.clone(),
+    );
+    assert!(proof.search().is_err());
+    assert!(proof.iterations() <= settings.iterations);
+
+    let mut proof = Proof::with_settings(
+        vec![parse_expression("P>(Q>R)").unwrap()],
+        parse_expression("Q>(P>R)").unwrap(),
+        settings.clone(),
+    );
+    assert!(proof.search().is_ok());
+    assert!(proof.iterations() > 0 && proof.iterations() <= settings.iterations);
+}
+
+#[test]
+fn test_lemmas_from_sub_proofs_keep_citations_in_order() {
+    let mut proof = Proof::with_settings(
+        vec![
+            parse_expression("P>(Q&R)").unwrap(),
+            parse_expression("P").unwrap(),
+        ],
+        parse_expression("S>R").unwrap(),
+        SearchSettings {
+            max_line_length: 12,
+            iterations: 25_000,
+            ..Default::default()
+        },
+    );
+    proof.search().unwrap();
+    for (i, line) in proof.get_deduction_lines().iter().enumerate() {
+        assert_eq!(line.line_number, i + 2);
+        assert!(line.deduction_lines.iter().all(|n| *n < line.line_number));
+    }
+}
+
+#[test]
+fn test_line_provenance() {
+    let mut proof = Proof::new(
+        vec![parse_expression("P>(Q>R)").unwrap()],
+        parse_expression("Q>(P>R)").unwrap(),
+    );
+    proof.search().unwrap();
+    let premise = &proof.lines()[0];
+    assert_eq!(premise.provenance.iteration, None);
+    let lines = proof.get_deduction_lines();
+    for line in &lines {
+        let iteration = line.provenance.iteration.unwrap();
+        assert!(iteration > 0 && iteration <= proof.iterations());
+        assert!(line.provenance.elapsed.is_some());
+    }
+    // The conditional proof of `P>R` is found by a sub proof search of the outer one
+    assert_eq!(lines.last().unwrap().provenance.depth, 0);
+    assert!(lines.iter().any(|line| line.provenance.depth > 0));
+}
+
+#[test]
+fn test_annotated_lines_are_displayed() {
+    let mut proof = Proof::new(
+        vec![
+            parse_expression("P").unwrap(),
+            parse_expression("P>Q").unwrap(),
+        ],
+        parse_expression("Q").unwrap(),
+    );
+    proof.search().unwrap();
+    assert!(proof.annotate(2, "apply the conditional to P"));
+    assert!(!proof.annotate(3, "no such line"));
+    let output = proof.to_string();
+    assert!(
+        output.contains("Line 3: Q [1, 2] using MPP from lines 1, 2  # apply the conditional to P")
+    );
+}
+
+#[test]
+fn test_labeled_citations() {
+    let mut proof = Proof::new(
+        vec![
+            parse_expression("P").unwrap(),
+            parse_expression("P>Q").unwrap(),
+        ],
+        parse_expression("Q").unwrap(),
+    );
+    proof.search().unwrap();
+    assert!(proof.label(0, "Given"));
+    assert!(proof.label(1, "Def"));
+    let output = proof.to_string();
+    assert!(output.contains("Line 1 (Given): P [1] using A"));
+    assert!(output.contains("Line 3: Q [1, 2] using MPP from lines 1, 2"));
+
+    proof.set_citations(Citations::Labels);
+    let output = proof.to_string();
+    assert!(output.contains("Line 1 (Given): P [Given] using A"));
+    assert!(output.contains("Line 3: Q [Given, Def] using MPP from lines Given, Def"));
+}
+
+#[test]
+fn test_wrapped_output_fits_width() {
+    let mut proof = proof_with_settings(&["Pv(Q>R)", "Q", "P>W", "R>W"], "W", settings());
+    proof.search().unwrap();
+    proof.set_max_width(Some(40));
+    let output = proof.to_string();
+    let steps = output.split_once("Proof Steps:\n").unwrap().1;
+    assert!(steps.lines().count() > proof.lines().len());
+    for line in steps.lines() {
+        assert!(line.len() <= 40, "{line:?} is too wide");
+    }
+    // Continuations hang below the start of the line they continue
+    assert!(steps.lines().any(|line| line.starts_with("      ")));
+}
+
+#[test]
+fn test_possibles_are_ordered_by_rule_then_citations() {
+    let proof = proof_with_settings(&["P>Q", "P", "-Q>R", "S&T"], "QvR", settings());
+    let node = SearchNode::new(
+        proof.lines().to_vec(),
+        parse_expression("QvR").unwrap(),
+        Default::default(),
+    );
+    let mut finder = PossibleFinder::new(node.borrow().clone());
+    finder.find();
+    let keys = finder
+        .possibles()
+        .iter()
+        .map(|possible| {
+            let last = possible.lines.last().unwrap();
+            (last.rule.clone(), last.deduction_lines.clone())
+        })
+        .collect::<Vec<_>>();
+    let mut sorted = keys.clone();
+    sorted.sort();
+    assert_eq!(keys, sorted);
+}
+
+#[test]
+fn test_search_is_deterministic() {
+    let output = || {
+        let mut proof = proof_with_settings(&["PvQ", "P>R", "Q>R"], "R", settings());
+        proof.search().unwrap();
+        proof.to_string()
+    };
+    let first = output();
+    assert!((0..3).all(|_| output() == first));
+}
+
+#[test]
+fn test_or_introduction_settings() {
+    let prove = |or_introduction| {
+        let mut proof = Proof::with_settings(
+            vec![
+                parse_expression("P").unwrap(),
+                parse_expression("R").unwrap(),
+            ],
+            parse_expression("PvQ").unwrap(),
+            SearchSettings {
+                max_line_length: 5,
+                iterations: 500,
+                or_introduction,
+                ..Default::default()
+            },
+        );
+        proof.search().is_ok()
+    };
+    assert!(!prove(OrIntroduction::Off));
+    assert!(prove(OrIntroduction::ConclusionSubformulas));
+    assert!(prove(OrIntroduction::AllVariables));
+
+    // `P v P` joins two lines rather than adding a disjunct
+    assert_eq!(
+        candidates(SearchSettings {
+            or_introduction: OrIntroduction::Off,
+            ..Default::default()
+        }),
+        vec!["~~P", "(P & P)", "(P v P)"]
+    );
+    assert!(candidates(SearchSettings {
+        or_introduction: OrIntroduction::ConclusionSubformulas,
+        ..Default::default()
+    })
+    .contains(&"(P v Q)".to_string()));
+}
+
+#[test]
+fn test_atom_universe() {
+    let all = candidates(SearchSettings {
+        or_introduction: OrIntroduction::AllVariables,
+        atoms: AtomUniverse::Sequent,
+        ..Default::default()
+    });
+    assert!(all.contains(&"(P v Q)".to_string()));
+    assert!(!all.iter().any(|candidate| candidate.contains('R')));
+
+    let extended = candidates(SearchSettings {
+        or_introduction: OrIntroduction::AllVariables,
+        atoms: AtomUniverse::Extended(vec!['R'.into()]),
+        ..Default::default()
+    });
+    assert!(extended.contains(&"(P v R)".to_string()));
+    assert!(extended.contains(&"(P v Q)".to_string()));
+
+    // Restricting the universe keeps letters out even when the goal uses them
+    let only = candidates(SearchSettings {
+        or_introduction: OrIntroduction::ConclusionSubformulas,
+        atoms: AtomUniverse::Only(vec!['P'.into()]),
+        ..Default::default()
+    });
+    assert!(!only.iter().any(|candidate| candidate.contains('Q')));
+    assert_eq!(
+        AtomUniverse::Extended(vec!['R'.into(), 'P'.into()]).atoms(&['Q'.into(), 'P'.into()]),
+        vec![Symbol::new('P'), Symbol::new('Q'), Symbol::new('R')]
+    );
+}
+
+#[test]
+fn test_candidate_cap() {
+    let mut proof = Proof::with_settings(
+        vec![
+            parse_expression("P>Q").unwrap(),
+            parse_expression("Q>R").unwrap(),
+            parse_expression("P").unwrap(),
+        ],
+        parse_expression("R").unwrap(),
+        SearchSettings {
+            max_line_length: 12,
+            iterations: 1_000,
+            max_candidates: Some(3),
+            ..Default::default()
+        },
+    );
+    proof.search().unwrap();
+    let stats = proof.stats();
+    assert!(stats.capped_nodes > 0);
+    assert!(stats.dropped_candidates >= stats.capped_nodes);
+
+    let mut proof = proof_with_settings(&["P>Q", "Q>R", "P"], "R", settings());
+    proof.search().unwrap();
+    let stats = proof.stats();
+    assert_eq!((stats.capped_nodes, stats.dropped_candidates), (0, 0));
+}
+
+#[test]
+fn test_iteration_units() {
+    let mut counts = Vec::new();
+    for iteration_unit in [
+        IterationUnit::Expansions,
+        IterationUnit::Candidates,
+        IterationUnit::RuleApplications,
+    ] {
+        let mut proof = Proof::with_settings(
+            vec![parse_expression("P>(Q>R)").unwrap()],
+            parse_expression("Q>(P>R)").unwrap(),
+            SearchSettings {
+                max_line_length: 12,
+                iterations: 100_000,
+                iteration_unit,
+                ..Default::default()
+            },
+        );
+        proof.search().unwrap();
+        let stats = proof.stats();
+        assert!(stats.expansions > 0);
+        assert!(stats.rule_applications >= stats.candidates);
+        counts.push((proof.iterations(), stats));
+    }
+    // The search is the same whatever is counted, including the work of its sub proofs
+    assert!(counts.iter().all(|(_, stats)| *stats == counts[0].1));
+    let stats = counts[0].1;
+    assert_eq!(counts[0].0, stats.expansions);
+    assert_eq!(counts[1].0, stats.candidates);
+    assert_eq!(counts[2].0, stats.rule_applications);
+
+    let mut proof = Proof::with_settings(
+        vec![parse_expression("PvQ").unwrap()],
+        parse_expression("P&Q").unwrap(),
+        SearchSettings {
+            max_line_length: 12,
+            iterations: 500,
+            iteration_unit: IterationUnit::RuleApplications,
+            ..Default::default()
+        },
+    );
+    assert!(proof.search().is_err());
+    assert!(proof.stats().expansions < proof.iterations());
+}
+
+#[test]
+fn test_max_formula_size() {
+    let settings = |max_formula_size| SearchSettings {
+        max_line_length: 8,
+        iterations: 2_000,
+        max_formula_size,
+        ..Default::default()
+    };
+    let mut proof = Proof::with_settings(
+        vec![
+            parse_expression("P").unwrap(),
+            parse_expression("Q").unwrap(),
+        ],
+        parse_expression("(P&Q)&(Q&P)").unwrap(),
+        settings(Some(3)),
+    );
+    proof.search().unwrap();
+    let last = proof.lines().len() - 1;
+    assert!(proof.lines()[..last]
+        .iter()
+        .all(|line| line.expression.size() <= 3));
+
+    // The conclusion can only be built from `P&P`, which is over the limit
+    let mut proof = Proof::with_settings(
+        vec![parse_expression("P").unwrap()],
+        parse_expression("(P&P)&(P&P)").unwrap(),
+        settings(Some(2)),
+    );
+    assert!(proof.search().is_err());
+}
+
+#[test]
+fn test_biconditional_elimination() {
+    create_and_test_proof(vec!["P<->Q", "P"], "Q");
+    create_and_test_proof(vec!["P=Q", "-Q"], "-P");
+}
+
+#[test]
+fn test_biconditional_introduction() {
+    create_and_test_proof(vec!["P>Q", "Q>P"], "P<->Q");
+    create_and_test_proof(vec!["P<->Q"], "Q<->P");
+}
+
+#[test]
+fn test_constants() {
+    create_and_test_proof(vec!["P", "-P"], "F");
+    create_and_test_proof(vec!["F"], "P&Q");
+    create_and_test_proof(vec!["P>F", "P"], "Q");
+    create_and_test_proof(vec![], "T");
+}
+
+#[test]
+fn test_exclude_previous_proof() {
+    for (assumptions, conclusion) in [(vec!["P>Q", "Q>R", "P"], "R"), (vec!["-P>Q", "-Q"], "P")] {
+        let mut first = proof_with_settings(&assumptions, conclusion, settings());
+        first.search().unwrap();
+        let mut second = proof_with_settings(&assumptions, conclusion, settings());
+        second.exclude(&first);
+        second.search().unwrap();
+        assert_ne!(
+            ProofShape::of(first.lines()),
+            ProofShape::of(second.lines())
+        );
+        assert_eq!(
+            second.lines().last().unwrap().expression,
+            parse_expression(conclusion).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_exclude_only_proof() {
+    let mut first = proof_with_settings(&["P"], "P", settings());
+    first.search().unwrap();
+    let mut second = proof_with_settings(&["P"], "P", settings());
+    second.exclude(&first);
+    assert!(second.search().is_err());
+}
+
+#[test]
+fn test_proof_shape_ignores_unused_lines() {
+    let mut proof = proof_with_settings(&["P>Q", "P"], "Q", settings());
+    proof.search().unwrap();
+    let mut padded = proof.lines().to_vec();
+    let mut last = padded.pop().unwrap();
+    padded.push(Line::new(
+        vec![1],
+        2,
+        parse_expression("--P").unwrap(),
+        Rule::DoubleNegation,
+        vec![1],
+    ));
+    last.line_number = 3;
+    padded.push(last);
+    assert_eq!(ProofShape::of(proof.lines()), ProofShape::of(&padded));
+}
+
+#[test]
+fn test_sub_proof_failures() {
+    let assumptions = ["P>Q", "Q>R"]
+        .into_iter()
+        .map(|x| parse_expression(x).unwrap())
+        .collect();
+    let mut proof = Proof::with_settings(
+        assumptions,
+        parse_expression("P>R").unwrap(),
+        SearchSettings {
+            max_line_length: 3,
+            ..Default::default()
+        },
+    );
+    assert!(proof.search().is_err());
+    let failures = proof.sub_proof_failures();
+    let cp = failures
+        .iter()
+        .find(|failure| failure.rule == Rule::ConditionalProofAssumption)
+        .unwrap();
+    assert_eq!(cp.assumption, parse_expression("P").unwrap());
+    assert_eq!(cp.goal, Some(parse_expression("R").unwrap()));
+    assert_eq!(cp.depth, 1);
+    assert!(cp
+        .to_string()
+        .starts_with("Assuming P (A(CP)) did not lead to R in "));
+}
+
+#[test]
+fn test_stepped_search() {
+    let assumptions = || {
+        ["P&Q"]
+            .into_iter()
+            .map(|x| parse_expression(x).unwrap())
+            .collect::<Vec<_>>()
+    };
+    let conclusion = parse_expression("Q&P").unwrap();
+    let mut blocking = Proof::new(assumptions(), conclusion.clone());
+    blocking.search().unwrap();
+
+    let mut stepped = Proof::new(assumptions(), conclusion);
+    let mut search = stepped.start_search();
+    assert_eq!(search.run(1), SearchState::Searching);
+    assert!(search.iterations() >= 1);
+    while search.step() == SearchState::Searching {}
+    assert_eq!(search.step(), SearchState::FinishedProof);
+    assert_eq!(stepped.lines().len(), blocking.lines().len());
+    assert_eq!(stepped.iterations(), blocking.iterations());
+}
+
+#[test]
+fn test_stepped_search_reports_failure() {
+    let mut proof = Proof::with_settings(
+        vec![parse_expression("P").unwrap()],
+        parse_expression("Q").unwrap(),
+        SearchSettings {
+            iterations: 50,
+            ..Default::default()
+        },
+    );
+    let mut search = proof.start_search();
+    let state = search.run(usize::MAX);
+    assert_ne!(state, SearchState::Searching);
+    assert_ne!(state, SearchState::FinishedProof);
+    assert_eq!(search.state(), state);
+    assert_eq!(proof.lines().len(), 1);
+}
+
+#[test]
+fn test_near_misses() {
+    let mut proof = Proof::with_settings(
+        vec![parse_expression("P&-Q").unwrap()],
+        parse_expression("Q").unwrap(),
+        SearchSettings {
+            max_line_length: 3,
+            iterations: 1_000,
+            ..Default::default()
+        },
+    );
+    proof.record_near_misses(2);
+    assert!(proof.search().is_err());
+    let near_misses = proof.near_misses();
+    // P and -Q are both one edit from Q, and nothing closer can be derived
+    assert_eq!(near_misses.len(), 2);
+    assert!(near_misses.iter().all(|near_miss| near_miss.distance == 1));
+    let negated = near_misses
+        .iter()
+        .find(|near_miss| near_miss.line().expression == parse_expression("-Q").unwrap())
+        .unwrap();
+    assert_eq!(
+        negated.to_string(),
+        format!(
+            "Line {} derived ~Q (&E), 1 edit(s) from the conclusion",
+            negated.lines.len()
+        )
+    );
+
+    let mut unrecorded = Proof::new(
+        vec![parse_expression("P").unwrap()],
+        parse_expression("Q").unwrap(),
+    );
+    let _ = unrecorded.start_search().step();
+    assert!(unrecorded.near_misses().is_empty());
+}
+
+#[test]
+fn test_refutation() {
+    let (premises, conclusion) = parse_argument("A, B, -A / #").unwrap();
+    assert_eq!(conclusion, Expression::Bottom);
+    let mut proof = Proof::refutation_with_settings(
+        premises,
+        SearchSettings {
+            max_line_length: 6,
+            iterations: 1_000,
+            ..Default::default()
+        },
+    );
+    assert!(proof.is_refutation());
+    proof.search().unwrap();
+    let last = proof.lines().last().unwrap();
+    assert!(matches!(
+        &last.expression,
+        Expression::Bottom | Expression::And(..)
+    ));
+    assert!(last.assumption_lines.iter().all(|&line| line != 1));
+    assert!(proof
+        .to_string()
+        .contains("Conclusion: the assumptions are inconsistent\n"));
+
+    let mut consistent = Proof::refutation_with_settings(
+        vec![
+            parse_expression("A").unwrap(),
+            parse_expression("A>B").unwrap(),
+        ],
+        SearchSettings {
+            max_line_length: 5,
+            iterations: 1_000,
+            ..Default::default()
+        },
+    );
+    assert!(consistent.search().is_err());
+    assert!(!Proof::new(vec![], parse_expression("P>P").unwrap()).is_refutation());
+}
+
+#[test]
+fn test_sub_proof_assumptions_are_noted() {
+    let mut proof = proof_with_settings(&["PvQ"], "QvP", settings());
+    proof.search().unwrap();
+    let lines = proof.lines();
+    let assumptions = lines
+        .iter()
+        .filter(|line| line.rule == Rule::OrEliminationAssumption)
+        .collect::<Vec<_>>();
+    assert_eq!(assumptions.len(), 2);
+    let discharged_at = lines
+        .iter()
+        .position(|line| line.rule == Rule::OrElimination)
+        .unwrap();
+    for assumption in assumptions {
+        assert_eq!(
+            assumption.comment,
+            Some(format!(
+                "Assumed for vE, discharged at line {}",
+                discharged_at + 1
+            ))
+        );
+    }
+    assert!(lines
+        .iter()
+        .filter(|line| line.rule == Rule::Assumption)
+        .all(|line| line.comment.is_none()));
+}
+
+#[test]
+fn test_merge() {
+    let mut first = proof_with_settings(&["P&Q", "P>R"], "R", settings());
+    first.search().unwrap();
+    first.label(0, "Given");
+    let mut second = proof_with_settings(&["P>R", "P&Q", "S"], "R&S", settings());
+    second.search().unwrap();
+
+    let merged = first.merge(&second);
+    assert_eq!(merged.conclusion(), second.conclusion());
+    assert_eq!(
+        merged.assumptions(),
+        ["P&Q", "P>R", "S"]
+            .into_iter()
+            .map(|x| parse_expression(x).unwrap())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(merged.lines()[0].label.as_deref(), Some("Given"));
+    // The second proof cites the first's `R`, and its own derivation of `R` is left out
+    assert_eq!(merged.lines().len(), first.lines().len() + 2);
+    let last = merged.lines().last().unwrap();
+    assert_eq!(last.expression, *second.conclusion());
+    assert_eq!(last.deduction_lines, vec![first.lines().len(), 2]);
+    assert_eq!(last.assumption_lines, vec![0, 1, 2]);
+    let p = parse_expression("P").unwrap();
+    assert_eq!(
+        merged
+            .lines()
+            .iter()
+            .filter(|line| line.expression == p)
+            .count(),
+        1
+    );
+
+    let mut conditional = proof_with_settings(&["P&Q", "P>R"], "Q>R", settings());
+    conditional.search().unwrap();
+    let merged = merged.merge(&conditional);
+    for (i, line) in merged.lines().iter().enumerate() {
+        assert_eq!(line.line_number, i);
+        assert!(line.deduction_lines.iter().all(|n| *n < i));
+        assert!(line.assumption_lines.iter().all(|n| *n <= i));
+    }
+    // The notes on sub proof assumptions point at where they are discharged in the merged proof
+    let mut noted = 0;
+    for line in merged.lines() {
+        if let Some(comment) = &line.comment {
+            let discharged_at = comment
+                .rsplit(' ')
+                .next()
+                .unwrap()
+                .parse::<usize>()
+                .unwrap();
+            let closing = &merged.lines()[discharged_at - 1];
+            assert_eq!(Some(closing.rule.clone()), line.rule.closing_rule());
+            assert!(closing.deduction_lines.contains(&line.line_number));
+            noted += 1;
+        }
+    }
+    assert!(noted > 0);
+}
@@ -0,0 +1,135 @@
+use propositional_logic_calculator::{
+    metadata::{settings_hash, Metadata},
+    proof::{Proof, SearchSettings},
+    render::{Fitch, Html, Latex, Lemmon, Markdown, NotationConfig, ProofRenderer, Terminal},
+};
+
+use crate::common::create_proof;
+
+#[test]
+fn test_terminal_is_the_default_display() {
+    let proof = create_proof(&["P>(Q>R)"], "Q>(P>R)");
+    assert_eq!(
+        Terminal.render(&proof, &NotationConfig::default()),
+        proof.to_string()
+    );
+}
+
+#[test]
+fn test_lemmon() {
+    let output = Lemmon.render(
+        &create_proof(&["P>(Q>R)"], "Q>(P>R)"),
+        &NotationConfig::default(),
+    );
+    let lines = output.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "1      (1)  (P -> (Q -> R))  A");
+    assert_eq!(lines[3], "1,3    (4)  (Q -> R)         1,3 MPP");
+}
+
+#[test]
+fn test_fitch() {
+    let output = Fitch.render(
+        &create_proof(&["P>(Q>R)"], "Q>(P>R)"),
+        &NotationConfig::default(),
+    );
+    let lines = output.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "1  | (P -> (Q -> R))  A");
+    assert_eq!(lines[1], "   |----");
+    assert_eq!(
+        lines[2],
+        "2  | | Q              A(CP)  # Assumed for CP, discharged at line 7"
+    );
+    assert_eq!(lines[3], "   | |----");
+    assert_eq!(lines[6], "4  | | | (Q -> R)     MPP 1, 3");
+}
+
+#[test]
+fn test_markup_formats() {
+    let mut proof = create_proof(&["P>(Q>R)"], "Q>(P>R)");
+    proof.annotate(2, "assume the antecedent & go on");
+
+    let markdown = Markdown.render(&proof, &NotationConfig::default());
+    assert!(markdown.contains("| 1, 3 | (4) | &emsp;&emsp;`(Q -> R)` | 1, 3 MPP |"));
+
+    let latex = Latex.render(&proof, &NotationConfig::default());
+    assert!(latex.starts_with("\\begin{tabular}"));
+    assert!(latex.contains("1,3 & (4) & \\quad \\quad $(Q \\to R)$ & 1,3 MPP \\\\"));
+    assert!(latex.contains("\\textit{assume the antecedent \\& go on}"));
+
+    let html = Html.render(&proof, &NotationConfig::default());
+    assert!(html.contains("<tr class=\"depth-2\"><td>1, 3</td><td>(4)</td><td>(Q -&gt; R)</td>"));
+    assert!(html.contains("<td>assume the antecedent &amp; go on</td>"));
+}
+
+struct Count;
+
+impl ProofRenderer for Count {
+    fn render(&self, proof: &Proof, _: &NotationConfig) -> String {
+        format!("{} lines", proof.lines().len())
+    }
+}
+
+#[test]
+fn test_custom_renderer() {
+    assert_eq!(
+        Count.render(
+            &create_proof(&["P>(Q>R)"], "Q>(P>R)"),
+            &NotationConfig::default()
+        ),
+        "7 lines"
+    );
+}
+
+#[test]
+fn test_metadata_round_trips() {
+    let mut proof = create_proof(&["P>(Q>R)"], "Q>(P>R)");
+    let metadata = Metadata {
+        author: Some("Ann \"Q\" <Lee> & co".to_string()),
+        course: Some("PHIL 105".to_string()),
+        date: Some("2024-03-01".to_string()),
+        ..Metadata::for_settings(proof.settings())
+    };
+    proof.set_metadata(Some(metadata.clone()));
+    let notation = proof.notation().clone();
+
+    let latex = Latex.render(&proof, &notation);
+    assert!(latex.starts_with("% author: Ann \"Q\" <Lee> & co\n% course: PHIL 105\n"));
+    assert_eq!(Metadata::read(&latex), Some(metadata.clone()));
+
+    let html = Html.render(&proof, &notation);
+    assert!(html.starts_with(
+        "<table class=\"proof\" data-author=\"Ann &quot;Q&quot; &lt;Lee&gt; &amp; co\""
+    ));
+    assert_eq!(Metadata::read(&html), Some(metadata.clone()));
+
+    let markdown = Markdown.render(&proof, &notation);
+    assert!(markdown.contains("<!-- date: 2024-03-01 -->\n<!-- engine-version: "));
+    assert!(markdown.contains(" -->\n| Assumptions |"));
+    assert_eq!(Metadata::read(&markdown), Some(metadata));
+
+    proof.set_metadata(None);
+    assert_eq!(
+        Metadata::read(&Latex.render(&proof, proof.notation())),
+        None
+    );
+    assert_eq!(Metadata::read(&Html.render(&proof, proof.notation())), None);
+}
+
+#[test]
+fn test_settings_hash() {
+    let settings = SearchSettings::default();
+    assert_eq!(settings_hash(&settings), settings_hash(&settings.clone()));
+    assert_ne!(
+        settings_hash(&settings),
+        settings_hash(&SearchSettings {
+            iterations: settings.iterations + 1,
+            ..settings.clone()
+        })
+    );
+    let metadata = Metadata::for_settings(&settings);
+    assert_eq!(
+        metadata.engine_version.as_deref(),
+        Some(env!("CARGO_PKG_VERSION"))
+    );
+    assert_eq!(metadata.settings_hash, Some(settings_hash(&settings)));
+}
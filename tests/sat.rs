@@ -0,0 +1,147 @@
+use propositional_logic_calculator::{
+    cnf::{satisfies_clause, Clause, Literal},
+    expression::{Expression, Symbol},
+    proof::parse_expression,
+    sat::{find_model, is_valid, solve, Solver},
+    semantics::{all_models, entails},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::common::random_expression;
+
+#[test]
+fn test_solve() {
+    let p = Literal::new('P', true);
+    let q = Literal::new('Q', true);
+    let r = Literal::new('R', true);
+    assert!(solve(&[]).unwrap().is_empty());
+    assert_eq!(solve(&[vec![]]), None);
+    // Pigeonhole: P and Q and R cannot all differ pairwise
+    let differ = |a: Literal, b: Literal| vec![vec![a, b], vec![a.negate(), b.negate()]];
+    let clauses = [differ(p, q), differ(q, r), differ(p, r)].concat();
+    assert_eq!(solve(&clauses), None);
+    let model = solve(&[differ(p, q), differ(q, r)].concat()).unwrap();
+    assert_eq!(model.get('P'), model.get('R'));
+    assert_ne!(model.get('P'), model.get('Q'));
+}
+
+#[test]
+fn test_agrees_with_truth_tables() {
+    let mut rng = StdRng::seed_from_u64(7);
+    for _ in 0..300 {
+        let formulas = (0..rng.gen_range(1..4))
+            .map(|_| random_expression(&mut rng, 4))
+            .collect::<Vec<_>>();
+        match find_model(&formulas) {
+            Some(model) => {
+                assert!(formulas.iter().all(|formula| model.satisfies(formula)));
+            }
+            None => assert!(entails(&formulas, &Expression::Bottom).is_valid()),
+        }
+        let (conclusion, premises) = formulas.split_last().unwrap();
+        assert_eq!(
+            is_valid(premises, conclusion),
+            entails(premises, conclusion).is_valid()
+        );
+    }
+}
+
+#[test]
+fn test_many_atoms() {
+    // A chain of 200 conditionals, far beyond a truth table
+    let chain = (1..200)
+        .map(|i| format!("(P{}>P{})", i, i + 1))
+        .collect::<Vec<_>>()
+        .join("&");
+    let premises = vec![
+        parse_expression(&chain).unwrap(),
+        parse_expression("P1").unwrap(),
+    ];
+    assert!(is_valid(&premises, &parse_expression("P200").unwrap()));
+    let model = find_model(&[
+        parse_expression(&chain).unwrap(),
+        parse_expression("-P200").unwrap(),
+    ])
+    .unwrap();
+    assert_eq!(model.len(), 200);
+    assert!(model.iter().all(|(_, value)| !value));
+}
+
+/// A random clause set over `count` letters with `length` literals in each clause.
+fn random_clauses(rng: &mut StdRng, count: u32, clauses: usize, length: usize) -> Vec<Clause> {
+    (0..clauses)
+        .map(|_| {
+            (0..length)
+                .map(|_| {
+                    let var = Symbol::indexed('X', rng.gen_range(1..=count));
+                    Literal::new(var, rng.gen_bool(0.5))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn test_solver_agrees_with_brute_force() {
+    let mut rng = StdRng::seed_from_u64(11);
+    for _ in 0..200 {
+        let count = rng.gen_range(1..40);
+        let clauses = random_clauses(&mut rng, 6, count, 3);
+        let mut letters = clauses
+            .iter()
+            .flatten()
+            .map(|literal| literal.var)
+            .collect::<Vec<_>>();
+        letters.sort();
+        letters.dedup();
+        let satisfiable = all_models(&letters).any(|model| {
+            clauses
+                .iter()
+                .all(|clause| satisfies_clause(&model, clause))
+        });
+        match solve(&clauses) {
+            Some(model) => {
+                assert!(clauses
+                    .iter()
+                    .all(|clause| satisfies_clause(&model, clause)));
+                assert_eq!(model.len(), letters.len());
+            }
+            None => assert!(!satisfiable),
+        }
+    }
+}
+
+#[test]
+fn test_hundreds_of_atoms() {
+    let mut rng = StdRng::seed_from_u64(3);
+    // Under-constrained random 3-SAT over 300 letters
+    let clauses = random_clauses(&mut rng, 300, 900, 3);
+    let mut solver = Solver::new(&clauses);
+    let model = solver.solve().unwrap();
+    assert!(clauses
+        .iter()
+        .all(|clause| satisfies_clause(&model, clause)));
+    assert!(solver.stats().decisions > 0);
+
+    // Seven pigeons do not fit in six holes; X(6i+j) puts pigeon i in hole j
+    let pigeon = |i: u32, j: u32| Symbol::indexed('X', 6 * i + j + 1);
+    let mut clauses = (0..7)
+        .map(|i| (0..6).map(|j| Literal::new(pigeon(i, j), true)).collect())
+        .collect::<Vec<Clause>>();
+    for j in 0..6 {
+        for i in 0..7 {
+            for k in i + 1..7 {
+                clauses.push(vec![
+                    Literal::new(pigeon(i, j), false),
+                    Literal::new(pigeon(k, j), false),
+                ]);
+            }
+        }
+    }
+    let mut solver = Solver::new(&clauses);
+    assert_eq!(solver.solve(), None);
+    let stats = solver.stats();
+    assert!(stats.conflicts > 0);
+    assert!(stats.learned_clauses > 0 && stats.learned_clauses <= stats.conflicts);
+    assert!(stats.propagations > 0);
+}
@@ -0,0 +1,312 @@
+use propositional_logic_calculator::{
+    error::Inconsistent,
+    expression::{Expression, Symbol},
+    proof::parse_expression,
+    semantics::{
+        all_models, consistent_with, count_models, entails, entails_set,
+        maximal_consistent_subsets, minimal_correction_sets, minimal_sufficient_premises,
+        propagate, Classification, ConclusionMode, Entailment, Model,
+    },
+};
+
+use crate::common::parse_all;
+
+#[test]
+fn test_propagate_chain() {
+    let assumptions = parse_all(&["P>Q", "Q>R"]);
+    let mut model = Model::new();
+    model.set('P', true);
+    let propagation = propagate(&assumptions, &model);
+    assert!(!propagation.is_conflicting());
+    assert_eq!(propagation.model.get('Q'), Some(true));
+    assert_eq!(propagation.model.get('R'), Some(true));
+    assert_eq!(
+        propagation.value_of(&parse_expression("Q>R").unwrap()),
+        Some(true)
+    );
+}
+
+#[test]
+fn test_propagate_leaves_unforced_letters_unknown() {
+    let assumptions = parse_all(&["PvQ"]);
+    let propagation = propagate(&assumptions, &Model::new());
+    assert!(propagation.model.is_empty());
+    assert!(propagation.determined.is_empty());
+
+    let mut model = Model::new();
+    model.set('P', false);
+    let propagation = propagate(&assumptions, &model);
+    assert_eq!(propagation.model.get('Q'), Some(true));
+}
+
+#[test]
+fn test_propagate_conflict() {
+    let assumptions = parse_all(&["P>Q", "-Q"]);
+    let mut model = Model::new();
+    model.set('P', true);
+    let propagation = propagate(&assumptions, &model);
+    assert!(propagation.is_conflicting());
+}
+
+#[test]
+fn test_consistent_with() {
+    let gamma = parse_all(&["PvQ", "-P"]);
+    let model = consistent_with(&gamma, &parse_expression("Q>R").unwrap()).unwrap();
+    assert_eq!(model.get('P'), Some(false));
+    assert_eq!(model.get('Q'), Some(true));
+    assert_eq!(model.get('R'), Some(true));
+
+    let result = consistent_with(&gamma, &parse_expression("-Q").unwrap());
+    assert_eq!(result, Err(Inconsistent));
+}
+
+#[test]
+fn test_entails_set_modes() {
+    let gamma = parse_all(&["P>Q", "R>S", "PvR"]);
+    let delta = parse_all(&["Q", "S"]);
+    assert_eq!(
+        entails_set(&gamma, &delta, ConclusionMode::Disjunctive),
+        Entailment::Valid
+    );
+    let result = entails_set(&gamma, &delta, ConclusionMode::Conjunctive);
+    let countermodel = result.countermodel().unwrap();
+    assert!(gamma.iter().all(|x| countermodel.satisfies(x)));
+    assert!(!delta.iter().all(|x| countermodel.satisfies(x)));
+}
+
+#[test]
+fn test_entails_empty_conclusion_set() {
+    let consistent = parse_all(&["P"]);
+    let inconsistent = parse_all(&["P", "-P"]);
+    assert!(!entails_set(&consistent, &[], ConclusionMode::Disjunctive).is_valid());
+    assert!(entails_set(&inconsistent, &[], ConclusionMode::Disjunctive).is_valid());
+    assert!(entails_set(&consistent, &[], ConclusionMode::Conjunctive).is_valid());
+}
+
+#[test]
+fn test_maximal_consistent_subsets() {
+    let premises = parse_all(&["P", "Q", "P>-Q", "R"]);
+    assert_eq!(
+        maximal_consistent_subsets(&premises),
+        vec![vec![0, 1, 3], vec![0, 2, 3], vec![1, 2, 3]]
+    );
+    assert_eq!(
+        minimal_correction_sets(&premises),
+        vec![vec![2], vec![1], vec![0]]
+    );
+}
+
+#[test]
+fn test_maximal_consistent_subsets_of_many_premises() {
+    // Far more premises than subsets could be listed for
+    let premises = (1..80)
+        .map(|i| format!("P{}", i))
+        .chain(["-P1".to_string()])
+        .map(|x| parse_expression(&x).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        maximal_consistent_subsets(&premises),
+        vec![(0..79).collect::<Vec<_>>(), (1..80).collect()]
+    );
+    assert_eq!(
+        maximal_consistent_subsets(&parse_all(&["F", "P&-P"])),
+        vec![Vec::<usize>::new()]
+    );
+    assert_eq!(maximal_consistent_subsets(&[]), vec![Vec::<usize>::new()]);
+}
+
+#[test]
+fn test_consistent_premises_are_their_own_maximal_subset() {
+    let premises = parse_all(&["P", "Q"]);
+    assert_eq!(maximal_consistent_subsets(&premises), vec![vec![0, 1]]);
+    assert_eq!(
+        minimal_correction_sets(&premises),
+        vec![Vec::<usize>::new()]
+    );
+}
+
+#[test]
+fn test_minimal_sufficient_premises() {
+    let assumptions = parse_all(&["P>Q", "Q>R", "P", "R", "S"]);
+    let conclusion = parse_expression("R").unwrap();
+    assert_eq!(
+        minimal_sufficient_premises(&assumptions, &conclusion),
+        Some(vec![3])
+    );
+
+    let conclusion = parse_expression("Q").unwrap();
+    assert_eq!(
+        minimal_sufficient_premises(&assumptions, &conclusion),
+        Some(vec![0, 2])
+    );
+
+    let conclusion = parse_expression("-S").unwrap();
+    assert_eq!(minimal_sufficient_premises(&assumptions, &conclusion), None);
+
+    // Too many premises to list every subset of
+    let assumptions = (1..=70)
+        .map(|i| parse_expression(&format!("P{}", i)).unwrap())
+        .collect::<Vec<_>>();
+    let conclusion = parse_expression("P3&P70").unwrap();
+    assert_eq!(
+        minimal_sufficient_premises(&assumptions, &conclusion),
+        Some(vec![2, 69])
+    );
+    let conclusion = parse_expression("Q").unwrap();
+    assert_eq!(minimal_sufficient_premises(&assumptions, &conclusion), None);
+}
+
+#[test]
+fn test_biconditional_semantics() {
+    let iff = parse_expression("P<->Q").unwrap();
+    let model = [('P', false), ('Q', false)].into_iter().collect::<Model>();
+    assert_eq!(model.evaluate(&iff), Some(true));
+
+    let mut model = Model::new();
+    model.set('P', true);
+    let propagation = propagate(&parse_all(&["-(P<->Q)"]), &model);
+    assert_eq!(propagation.model.get('Q'), Some(false));
+}
+
+#[test]
+fn test_constant_semantics() {
+    let model = Model::new();
+    assert_eq!(model.evaluate(&Expression::Top), Some(true));
+    assert_eq!(
+        model.evaluate(&parse_expression("P&F").unwrap()),
+        Some(false)
+    );
+    assert!(consistent_with(&parse_all(&["P>F"]), &parse_expression("P").unwrap()).is_err());
+    assert!(entails_set(&[], &parse_all(&["-F"]), ConclusionMode::Conjunctive).is_valid());
+}
+
+#[test]
+fn test_classify() {
+    for (formula, classification) in [
+        ("Pv-P", Classification::Tautology),
+        ("(P>Q)<->(-Q>-P)", Classification::Tautology),
+        ("T", Classification::Tautology),
+        ("(PvQ)&-P&-Q", Classification::Contradiction),
+        ("F", Classification::Contradiction),
+        ("P", Classification::Contingent),
+        ("(P&Q)v(-P&-Q)", Classification::Contingent),
+    ] {
+        assert_eq!(
+            parse_expression(formula).unwrap().classify(),
+            classification,
+            "{}",
+            formula
+        );
+    }
+    assert_eq!(Classification::Contingent.to_string(), "contingent");
+
+    // Past the size of a truth table
+    let conjunction = (1..=70)
+        .map(|i| format!("P{}", i))
+        .collect::<Vec<_>>()
+        .join("&");
+    for (formula, classification) in [
+        (format!("({})>P35", conjunction), Classification::Tautology),
+        (
+            format!("{}&-P35", conjunction),
+            Classification::Contradiction,
+        ),
+        (conjunction, Classification::Contingent),
+    ] {
+        assert_eq!(
+            parse_expression(&formula).unwrap().classify(),
+            classification
+        );
+    }
+}
+
+#[test]
+fn test_entails() {
+    let premises = parse_all(&["PvQ", "P>R"]);
+    assert_eq!(
+        entails(&premises, &parse_expression("RvQ").unwrap()),
+        Entailment::Valid
+    );
+    let conclusion = parse_expression("R").unwrap();
+    let entailment = entails(&premises, &conclusion);
+    let model = entailment.countermodel().unwrap();
+    assert!(premises.iter().all(|premise| model.satisfies(premise)));
+    assert_eq!(model.evaluate(&conclusion), Some(false));
+    assert_eq!(model.len(), 3);
+
+    // With no premises, entailment is validity; with `⊥`, it is inconsistency
+    assert!(entails(&[], &parse_expression("Pv-P").unwrap()).is_valid());
+    assert!(entails(&parse_all(&["P", "-P"]), &Expression::Bottom).is_valid());
+    assert!(!entails(&parse_all(&["P"]), &Expression::Bottom).is_valid());
+}
+
+#[test]
+fn test_entails_with_many_letters() {
+    // Too many letters for a truth table, so the models are found by the SAT solver
+    let chain = (1..70)
+        .map(|i| format!("P{}>P{}", i, i + 1))
+        .chain(["P1".to_string()])
+        .map(|x| parse_expression(&x).unwrap())
+        .collect::<Vec<_>>();
+    assert!(entails(&chain, &parse_expression("P70").unwrap()).is_valid());
+    let conclusion = parse_expression("-P70&Q").unwrap();
+    let entailment = entails(&chain, &conclusion);
+    let model = entailment.countermodel().unwrap();
+    assert!(chain.iter().all(|premise| model.satisfies(premise)));
+    assert_eq!(model.evaluate(&conclusion), Some(false));
+    assert_eq!(model.len(), 71);
+    assert!(consistent_with(&chain, &parse_expression("-P70").unwrap()).is_err());
+}
+
+#[test]
+#[should_panic(expected = "too many sentence letters")]
+fn test_all_models_of_too_many_letters() {
+    let atoms = (1..=64)
+        .map(|i| Symbol::indexed('P', i))
+        .collect::<Vec<_>>();
+    all_models(&atoms).next();
+}
+
+#[test]
+fn test_count_models() {
+    for formula in [
+        "P",
+        "T",
+        "-F",
+        "P&T",
+        "(P>Q)&(Q>R)&(R>S)",
+        "(P<->Q)v(R&-S)",
+        "(P&Q)v(R&S)v(-P&-S)",
+        "((P>Q)>P)>P",
+        "(PvQ)&(RvS)&-(P&R)",
+    ] {
+        let formula = parse_expression(formula).unwrap();
+        let mut atoms = formula
+            .list_expressions()
+            .into_iter()
+            .filter_map(|sub| match sub {
+                Expression::Var(var) => Some(var),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        atoms.sort();
+        atoms.dedup();
+        let count = all_models(&atoms)
+            .filter(|model| model.satisfies(&formula))
+            .count();
+        assert_eq!(count_models(&formula), count as u128, "{}", formula);
+    }
+
+    // 2^100 models of a tautology over 100 letters
+    let formula = (1..=100)
+        .map(|i| format!("(P{}v-P{})", i, i))
+        .collect::<Vec<_>>()
+        .join("&");
+    assert_eq!(count_models(&parse_expression(&formula).unwrap()), 1 << 100);
+    // A chain of 60 conditionals has one model for each place the chain turns true
+    let chain = (1..60)
+        .map(|i| format!("(Q{}>Q{})", i, i + 1))
+        .collect::<Vec<_>>()
+        .join("&");
+    assert_eq!(count_models(&parse_expression(&chain).unwrap()), 61);
+}
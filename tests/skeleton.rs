@@ -0,0 +1,55 @@
+use propositional_logic_calculator::skeleton::{skeleton, Blank, SkeletonSettings};
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::common::create_proof;
+
+#[test]
+fn test_premises_and_conclusion_stay_visible() {
+    let proof = create_proof(&["P>Q", "Q>R"], "P>R");
+    let mut rng = StdRng::seed_from_u64(2012);
+    let settings = SkeletonSettings {
+        formula_rate: 1.0,
+        rule_rate: 1.0,
+        ..SkeletonSettings::HARD
+    };
+    let exercise = skeleton(&mut rng, &proof, &settings);
+    let last = proof.lines().len() - 1;
+    assert_eq!(exercise.blanks[0], None);
+    assert_eq!(exercise.blanks[1], None);
+    assert_eq!(exercise.blanks[last], Some(Blank::Rule));
+    assert!(exercise.blanks[2..last]
+        .iter()
+        .all(|blank| *blank == Some(Blank::Both)));
+
+    let shown = exercise.to_string();
+    assert!(shown.starts_with("Line 1: (P -> Q) [1] using A\n"));
+    assert!(shown.contains("Line 3: ____ [3] using ____\n"));
+    assert!(shown.ends_with(&format!("Line {}: (P -> R) [1, 2] using ____\n", last + 1)));
+}
+
+#[test]
+fn test_answer_key_fills_the_blanks() {
+    let proof = create_proof(&["P>Q", "Q>R"], "P>R");
+    let mut rng = StdRng::seed_from_u64(2012);
+    for _ in 0..20 {
+        let exercise = skeleton(&mut rng, &proof, &SkeletonSettings::MEDIUM);
+        let hidden = exercise.blanks.iter().filter(|b| b.is_some()).count();
+        assert!(hidden >= 1);
+        assert_eq!(exercise.answer_key().lines().count(), hidden);
+        assert_eq!(exercise.lines, proof.lines());
+    }
+}
+
+#[test]
+fn test_easy_hides_only_rules() {
+    let proof = create_proof(&["P>Q", "Q>R"], "P>R");
+    let mut rng = StdRng::seed_from_u64(7);
+    for _ in 0..20 {
+        let exercise = skeleton(&mut rng, &proof, &SkeletonSettings::EASY);
+        assert!(exercise
+            .blanks
+            .iter()
+            .flatten()
+            .all(|blank| *blank == Blank::Rule));
+    }
+}
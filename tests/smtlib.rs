@@ -0,0 +1,124 @@
+use propositional_logic_calculator::{
+    error::SmtLibError,
+    expression::Expression,
+    proof::{parse_expression, Proof},
+    smtlib::{parse_smtlib, SmtLibScript},
+};
+
+#[test]
+fn test_connectives() {
+    let assertions = parse_smtlib(
+        "; Boolean connectives
+         (declare-const a Bool)
+         (declare-const b Bool)
+         (declare-fun |c d| () Bool)
+         (assert (and a b |c d|))
+         (assert (or (not a) b))
+         (assert (=> a b |c d|))
+         (assert (= a b |c d|))
+         (assert (xor a b))
+         (assert (ite a b |c d|))
+         (assert (! (and true (not false)) :named consts))",
+    )
+    .unwrap();
+    let expected = [
+        "(P&Q)&R",
+        "-PvQ",
+        "P>(Q>R)",
+        "(P<->Q)&(Q<->R)",
+        "-(P<->Q)",
+        "(P>Q)&(-P>R)",
+        "T&-F",
+    ];
+    assert_eq!(assertions.len(), expected.len());
+    for (assertion, expected) in assertions.iter().zip(expected) {
+        assert_eq!(*assertion, parse_expression(expected).unwrap());
+    }
+}
+
+#[test]
+fn test_definitions_and_atoms() {
+    let script = SmtLibScript::parse(
+        "(set-info :source |A script
+         over two lines|)
+         (declare-const x Bool)
+         (declare-const y Bool)
+         (define-fun both () Bool (and x y))
+         (assert (not both))
+         (check-sat)
+         (exit)",
+    )
+    .unwrap();
+    assert_eq!(
+        script.atoms,
+        vec![("x".to_string(), 'P'.into()), ("y".to_string(), 'Q'.into())]
+    );
+    assert_eq!(script.assertions, vec![parse_expression("-(P&Q)").unwrap()]);
+}
+
+#[test]
+fn test_unsat_script_proves_falsum() {
+    let assertions = parse_smtlib(
+        "(declare-const p Bool)
+         (declare-const q Bool)
+         (assert (=> p q))
+         (assert p)
+         (assert (not q))",
+    )
+    .unwrap();
+    let mut proof = Proof::new(assertions, Expression::Bottom);
+    assert!(proof.search().is_ok());
+}
+
+#[test]
+fn test_errors() {
+    assert!(matches!(
+        parse_smtlib("(declare-const x Int)"),
+        Err(SmtLibError::Unsupported(s, 1)) if s == "Int"
+    ));
+    assert!(matches!(
+        parse_smtlib("(declare-fun f (Bool) Bool)"),
+        Err(SmtLibError::Unsupported(s, 1)) if s == "declare-fun"
+    ));
+    assert!(matches!(
+        parse_smtlib("(declare-const p Bool)\n(assert (and p q))"),
+        Err(SmtLibError::UndeclaredSymbol(s, 2)) if s == "q"
+    ));
+    assert!(matches!(
+        parse_smtlib("(declare-const p Bool)\n(assert (forall ((x Bool)) x))"),
+        Err(SmtLibError::Unsupported(..))
+    ));
+    assert!(matches!(
+        parse_smtlib("(push 1)"),
+        Err(SmtLibError::Unsupported(s, 1)) if s == "push"
+    ));
+    assert!(matches!(
+        parse_smtlib("(assert true"),
+        Err(SmtLibError::Unexpected(s, 1)) if s == "end of input"
+    ));
+    assert!(matches!(
+        parse_smtlib("(assert true))"),
+        Err(SmtLibError::Unexpected(s, 1)) if s == "')'"
+    ));
+    let declarations = (0..25)
+        .map(|i| format!("(declare-const p{i} Bool)"))
+        .collect::<String>();
+    assert!(matches!(
+        parse_smtlib(&declarations),
+        Err(SmtLibError::TooManyAtoms(s)) if s == "p24"
+    ));
+
+    // Deep nesting is an error rather than a stack overflow
+    let nested = |depth| {
+        format!(
+            "(declare-const p Bool)\n(assert {}p{})",
+            "(not ".repeat(depth),
+            ")".repeat(depth)
+        )
+    };
+    assert!(parse_smtlib(&nested(200)).is_ok());
+    assert!(matches!(
+        parse_smtlib(&nested(100_000)),
+        Err(SmtLibError::TooDeeplyNested(256, 2))
+    ));
+}
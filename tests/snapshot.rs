@@ -0,0 +1,48 @@
+use propositional_logic_calculator::{
+    render::Citations,
+    snapshot::{assert_snapshot, check_snapshot, diff},
+};
+use std::{env, fs, process};
+
+use crate::common::create_proof;
+
+#[test]
+fn test_snapshots() {
+    let mut proof = create_proof(&["P>(Q>R)"], "Q>(P>R)");
+    assert_snapshot("tests/snapshots/exportation.txt", &proof);
+    // How the proof is set up to be shown does not change its snapshot
+    proof.set_citations(Citations::Labels);
+    proof.set_max_width(Some(20));
+    assert_snapshot("tests/snapshots/exportation.txt", &proof);
+
+    let proof = create_proof(&["PvQ", "-P"], "Q");
+    assert_snapshot("tests/snapshots/disjunctive_syllogism.txt", &proof);
+}
+
+#[test]
+fn test_snapshot_mismatch() {
+    let dir = env::temp_dir().join(format!("plc-snapshot-{}", process::id()));
+    let path = dir.join("mpp.txt");
+    let proof = create_proof(&["P>Q", "P"], "Q");
+    // Written the first time, then matched
+    assert_eq!(check_snapshot(&path, &proof).unwrap(), None);
+    assert_eq!(check_snapshot(&path, &proof).unwrap(), None);
+
+    let snapshot = fs::read_to_string(&path).unwrap();
+    fs::write(&path, snapshot.replace("MPP", "MTT").replace('\n', "\r\n")).unwrap();
+    let difference = check_snapshot(&path, &proof).unwrap().unwrap();
+    assert!(difference.contains("- Line 3: Q [1, 2] using MTT from lines 1, 2\n"));
+    assert!(difference.contains("+ Line 3: Q [1, 2] using MPP from lines 1, 2\n"));
+    assert!(difference.starts_with("  Assumptions:"));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_diff() {
+    assert_eq!(diff("", ""), None);
+    assert_eq!(diff("a\n", "a\nb\n").unwrap(), "  a\n+ b\n");
+    assert_eq!(
+        diff("a\nb\nc\n", "b\nc\nd\n").unwrap(),
+        "- a\n  b\n  c\n+ d\n"
+    );
+}
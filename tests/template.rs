@@ -0,0 +1,90 @@
+use propositional_logic_calculator::{
+    error::{ParserError, TemplateError},
+    lines::{Line, Rule},
+    proof::parse_expression,
+    template::Template,
+};
+
+#[test]
+fn test_fill() {
+    let template = Template::parse("(?2 & P) > ?1 v ?2").unwrap();
+    assert_eq!(template.holes(), vec![1, 2]);
+    let filled = template
+        .fill(&[
+            parse_expression("Q").unwrap(),
+            parse_expression("~R").unwrap(),
+        ])
+        .unwrap();
+    assert_eq!(filled, parse_expression("(~R&P)>(Qv~R)").unwrap());
+    assert!(matches!(
+        template.fill(&[parse_expression("Q").unwrap()]),
+        Err(TemplateError::MissingFilling(2))
+    ));
+}
+
+#[test]
+fn test_parse_errors() {
+    assert!(matches!(
+        Template::parse("P & ?"),
+        Err(TemplateError::ParserError(ParserError::InvalidExpression(
+            '?', 4
+        )))
+    ));
+    assert!(matches!(
+        Template::parse("?1 & ?12 &"),
+        Err(TemplateError::ParserError(ParserError::EmptyExpression(10)))
+    ));
+}
+
+#[test]
+fn test_makes_valid() {
+    let premises = [
+        Template::parse("P > ?1").unwrap(),
+        Template::parse("?1 > R").unwrap(),
+    ];
+    let conclusion = Template::parse("P > R").unwrap();
+    assert!(
+        Template::makes_valid(&premises, &conclusion, &[parse_expression("Q").unwrap()]).unwrap()
+    );
+
+    let conclusion = Template::parse("?1").unwrap();
+    let premise = [Template::parse("P & Q").unwrap()];
+    assert!(
+        Template::makes_valid(&premise, &conclusion, &[parse_expression("Q").unwrap()]).unwrap()
+    );
+    assert!(
+        !Template::makes_valid(&premise, &conclusion, &[parse_expression("R").unwrap()]).unwrap()
+    );
+}
+
+#[test]
+fn test_is_correct_step() {
+    let lines = vec![
+        Line::new(
+            vec![1],
+            1,
+            parse_expression("P>Q").unwrap(),
+            Rule::Assumption,
+            vec![],
+        ),
+        Line::new(
+            vec![2],
+            2,
+            parse_expression("P").unwrap(),
+            Rule::Assumption,
+            vec![],
+        ),
+    ];
+    let template = Template::parse("?1").unwrap();
+    let q = [parse_expression("Q").unwrap()];
+    assert!(template
+        .is_correct_step(&q, &lines, &Rule::ModusPonens)
+        .unwrap());
+    assert!(!template
+        .is_correct_step(&q, &lines, &Rule::ModusTollens)
+        .unwrap());
+    let r = [parse_expression("R").unwrap()];
+    assert!(!template
+        .is_correct_step(&r, &lines, &Rule::ModusPonens)
+        .unwrap());
+}
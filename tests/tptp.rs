@@ -0,0 +1,112 @@
+use propositional_logic_calculator::{
+    error::TptpError, expression::Expression, proof::parse_expression, tptp::TptpProblem,
+};
+
+#[test]
+fn test_fof_connectives() {
+    let problem = TptpProblem::parse(
+        "fof(a, axiom, (p & q & r) | ~s).
+         fof(b, axiom, (p => q) <=> (q <= p)).
+         fof(c, axiom, (p <~> q) & (p ~| q) & (p ~& q)).
+         fof(d, axiom, $true & ~$false).",
+    )
+    .unwrap();
+    let expected = [
+        "((P&Q)&R)v-S",
+        "(P>Q)<->(P>Q)",
+        "(-(P<->Q)&-(PvQ))&-(P&Q)",
+        "T&-F",
+    ];
+    for (premise, expected) in problem.premises.iter().zip(expected) {
+        assert_eq!(*premise, parse_expression(expected).unwrap());
+    }
+    assert_eq!(problem.conclusion(), Expression::Bottom);
+}
+
+#[test]
+fn test_comments_and_annotations() {
+    let problem = TptpProblem::parse(
+        "% A comment
+         /* A block
+            comment */
+         fof('it rains', axiom, 'it rains', file('rain.p', rains), [useful]).
+         fof(goal, conjecture, 'it rains' | dry).",
+    )
+    .unwrap();
+    assert_eq!(
+        problem.atoms,
+        vec![
+            ("it rains".to_string(), 'P'.into()),
+            ("dry".to_string(), 'Q'.into())
+        ]
+    );
+    assert_eq!(problem.conjecture, Some(parse_expression("PvQ").unwrap()));
+    assert!(problem.proof().search().is_ok());
+}
+
+#[test]
+fn test_cnf_refutation() {
+    let problem = TptpProblem::parse(
+        "cnf(c1, axiom, p | q).
+         cnf(c2, axiom, ~p).
+         cnf(c3, negated_conjecture, ~q).",
+    )
+    .unwrap();
+    assert_eq!(problem.premises.len(), 3);
+    assert_eq!(problem.conjecture, None);
+    assert!(problem.proof().search().is_ok());
+}
+
+#[test]
+fn test_errors() {
+    assert!(matches!(
+        TptpProblem::parse("fof(a, axiom, ![X]: p(X))."),
+        Err(TptpError::NotPropositional(q, 1)) if q == "!"
+    ));
+    assert!(matches!(
+        TptpProblem::parse("fof(a, axiom,\n p(a))."),
+        Err(TptpError::NotPropositional(p, 2)) if p == "p"
+    ));
+    assert!(matches!(
+        TptpProblem::parse("include('Axioms/SET001.ax')."),
+        Err(TptpError::NotPropositional(..))
+    ));
+    assert!(matches!(
+        TptpProblem::parse("fof(a, axiom, p & q | r)."),
+        Err(TptpError::Unexpected(s, 1)) if s == "'|'"
+    ));
+    assert!(matches!(
+        TptpProblem::parse("fof(a, axiom, p)"),
+        Err(TptpError::Unexpected(s, 1)) if s == "end of input"
+    ));
+    assert!(matches!(
+        TptpProblem::parse("fof(a, type, p)."),
+        Err(TptpError::UnknownRole(r, 1)) if r == "type"
+    ));
+    assert!(matches!(
+        TptpProblem::parse("fof(a, conjecture, p).\nfof(b, conjecture, q)."),
+        Err(TptpError::MultipleConjectures(2))
+    ));
+    let atoms = (0..25)
+        .map(|i| format!("a{i}"))
+        .collect::<Vec<_>>()
+        .join(" & ");
+    assert!(matches!(
+        TptpProblem::parse(&format!("fof(a, axiom, {atoms}).")),
+        Err(TptpError::TooManyAtoms(a)) if a == "a24"
+    ));
+
+    // Deep nesting is an error rather than a stack overflow
+    let nested = |depth| {
+        format!(
+            "fof(c, conjecture, {}p{}).",
+            "~ (".repeat(depth),
+            ")".repeat(depth)
+        )
+    };
+    assert!(TptpProblem::parse(&nested(100)).is_ok());
+    assert!(matches!(
+        TptpProblem::parse(&nested(100_000)),
+        Err(TptpError::TooDeeplyNested(256, 1))
+    ));
+}
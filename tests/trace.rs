@@ -0,0 +1,146 @@
+use propositional_logic_calculator::{
+    error::TraceError,
+    lines::Rule,
+    proof::{AtomUniverse, IterationUnit, Proof, SearchSettings},
+    trace::{Trace, TraceEvent},
+};
+
+use crate::common::proof_with_settings;
+
+/// The proof of exportation the traces are recorded from, before searching.
+fn exportation() -> Proof {
+    proof_with_settings(
+        &["P>(Q>R)"],
+        "Q>(P>R)",
+        SearchSettings {
+            max_line_length: 12,
+            iterations: 5_000,
+            ..Default::default()
+        },
+    )
+}
+
+fn record() -> Trace {
+    let mut proof = exportation();
+    proof.record_trace();
+    proof.search().unwrap();
+    proof.trace().unwrap()
+}
+
+#[test]
+fn test_trace_records_sub_proofs() {
+    let trace = record();
+    assert!(matches!(
+        trace.events[0],
+        TraceEvent::Expand {
+            depth: 0,
+            iteration: 1,
+            lines: 1
+        }
+    ));
+    assert!(trace.events.contains(&TraceEvent::SubProof {
+        depth: 0,
+        rule: Rule::ConditionalProofAssumption,
+        found: true
+    }));
+    assert!(trace
+        .events
+        .iter()
+        .any(|event| matches!(event, TraceEvent::Expand { depth: 1, .. })));
+}
+
+#[test]
+fn test_trace_round_trips_through_text() {
+    let trace = record();
+    let mut text = Vec::new();
+    trace.write_to(&mut text).unwrap();
+    assert!(text.starts_with(b"settings 12 5000 0.5 conclusion sequent none none\nexpand 0 1 1\n"));
+    assert_eq!(Trace::read_from(text.as_slice()).unwrap(), trace);
+
+    assert!(matches!(
+        Trace::read_from(
+            "settings 12 5000 0.5 conclusion sequent none none\nexpand 0 1\n".as_bytes()
+        ),
+        Err(TraceError::Malformed(2))
+    ));
+}
+
+#[test]
+fn test_replay() {
+    let trace = record();
+    assert_eq!(exportation().replay(&trace), Ok(()));
+
+    let mut altered = trace.clone();
+    altered.events[3] = TraceEvent::Candidates { depth: 0, count: 0 };
+    let divergence = exportation().replay(&altered).unwrap_err();
+    assert_eq!(divergence.index, 3);
+    assert_eq!(divergence.found, Some(trace.events[3].clone()));
+
+    let mut truncated = trace.clone();
+    truncated.events.pop();
+    let divergence = exportation().replay(&truncated).unwrap_err();
+    assert_eq!(divergence.expected, None);
+}
+
+#[test]
+fn test_doomed_sub_proofs_are_not_searched() {
+    let mut proof = proof_with_settings(
+        &["PvQ"],
+        "R>S",
+        SearchSettings {
+            max_line_length: 6,
+            iterations: 1_000,
+            ..Default::default()
+        },
+    );
+    proof.record_trace();
+    assert!(proof.search().is_err());
+    assert!(!proof
+        .trace()
+        .unwrap()
+        .events
+        .iter()
+        .any(|event| matches!(event, TraceEvent::SubProof { .. })));
+}
+
+#[test]
+fn test_atom_universe_round_trips() {
+    for atoms in [
+        AtomUniverse::Sequent,
+        AtomUniverse::Only(vec!['P'.into(), 'Q'.into()]),
+        AtomUniverse::Extended(vec!['R'.into()]),
+    ] {
+        let trace = Trace::new(SearchSettings {
+            atoms,
+            ..Default::default()
+        });
+        let text = trace.to_string();
+        assert_eq!(Trace::read_from(text.as_bytes()).unwrap(), trace);
+    }
+}
+
+#[test]
+fn test_limits_round_trip() {
+    let trace = Trace::new(SearchSettings {
+        max_candidates: Some(4),
+        max_formula_size: Some(7),
+        ..Default::default()
+    });
+    let text = trace.to_string();
+    assert!(text.ends_with(" 4 7\n"));
+    assert_eq!(Trace::read_from(text.as_bytes()).unwrap(), trace);
+}
+
+#[test]
+fn test_iteration_unit_round_trips() {
+    for iteration_unit in [IterationUnit::Candidates, IterationUnit::RuleApplications] {
+        let trace = Trace::new(SearchSettings {
+            iteration_unit,
+            ..Default::default()
+        });
+        let text = trace.to_string();
+        assert_eq!(Trace::read_from(text.as_bytes()).unwrap(), trace);
+    }
+    let text = Trace::new(SearchSettings::default()).to_string();
+    assert!(text.ends_with(" none none\n"));
+}